@@ -0,0 +1,23 @@
+//! Embeds the names of the SQL migrations so readiness checks can compare
+//! them with `schema_migrations` without the files being deployed.
+
+use std::{env, fs, path::Path};
+
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+
+    let mut names: Vec<String> = fs::read_dir("migrations")
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| name.starts_with('V') && name.ends_with(".sql"))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+
+    let out = Path::new(&env::var("OUT_DIR").expect("OUT_DIR is set by cargo")).join("migrations.rs");
+    let list: String = names.iter().map(|name| format!("    {name:?},\n")).collect();
+    fs::write(out, format!("pub const MIGRATIONS: &[&str] = &[\n{list}];\n")).expect("write migrations.rs");
+}
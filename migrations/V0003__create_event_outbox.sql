@@ -0,0 +1,25 @@
+-- Transactional outbox feeding the domain event stream and webhook fan-out
+
+CREATE TABLE IF NOT EXISTS event_outbox (
+    id uuid PRIMARY KEY,
+    sequence bigint GENERATED ALWAYS AS IDENTITY,
+    event_type text NOT NULL,
+    schema_version integer NOT NULL,
+    aggregate_type text NOT NULL,
+    aggregate_id uuid NOT NULL,
+    payload jsonb NOT NULL,
+    occurred_at timestamptz NOT NULL,
+    published_at timestamptz,
+    publish_attempts integer NOT NULL DEFAULT 0,
+    last_error text,
+    webhooks_dispatched_at timestamptz,
+    created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_outbox_unpublished
+    ON event_outbox (sequence)
+    WHERE published_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_event_outbox_webhooks_pending
+    ON event_outbox (sequence)
+    WHERE webhooks_dispatched_at IS NULL;
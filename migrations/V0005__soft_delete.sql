@@ -0,0 +1,33 @@
+-- Soft deletion for businesses, locations and promotions.
+-- Rows are hidden once `deleted_at` is set and hard-deleted by the purge job
+-- after the retention window.
+
+ALTER TABLE businesses
+    ADD COLUMN IF NOT EXISTS deleted_at timestamptz,
+    ADD COLUMN IF NOT EXISTS deleted_by uuid;
+
+ALTER TABLE business_locations
+    ADD COLUMN IF NOT EXISTS deleted_at timestamptz,
+    ADD COLUMN IF NOT EXISTS deleted_by uuid;
+
+ALTER TABLE business_promotions
+    ADD COLUMN IF NOT EXISTS deleted_at timestamptz,
+    ADD COLUMN IF NOT EXISTS deleted_by uuid;
+
+CREATE INDEX IF NOT EXISTS idx_businesses_owner_live
+    ON businesses (owner_user_id, created_at DESC) WHERE deleted_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_business_locations_business_live
+    ON business_locations (business_id) WHERE deleted_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_business_promotions_location_live
+    ON business_promotions (location_id, starts_at DESC) WHERE deleted_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_businesses_deleted_at
+    ON businesses (deleted_at) WHERE deleted_at IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_business_locations_deleted_at
+    ON business_locations (deleted_at) WHERE deleted_at IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_business_promotions_deleted_at
+    ON business_promotions (deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,10 @@
+-- Optimistic concurrency: every write bumps `version`, exposed as the ETag
+
+ALTER TABLE businesses
+    ADD COLUMN IF NOT EXISTS version integer NOT NULL DEFAULT 1;
+
+ALTER TABLE business_locations
+    ADD COLUMN IF NOT EXISTS version integer NOT NULL DEFAULT 1;
+
+ALTER TABLE business_promotions
+    ADD COLUMN IF NOT EXISTS version integer NOT NULL DEFAULT 1;
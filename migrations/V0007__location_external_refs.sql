@@ -0,0 +1,5 @@
+-- Bulk imports upsert locations by the external reference stored in metadata
+
+CREATE UNIQUE INDEX IF NOT EXISTS ux_business_locations_external_ref
+    ON business_locations (business_id, (metadata->>'external_ref'))
+    WHERE metadata ? 'external_ref' AND deleted_at IS NULL;
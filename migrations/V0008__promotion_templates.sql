@@ -0,0 +1,41 @@
+-- Recurring promotion templates. The scheduler materializes each occurrence
+-- as a regular business_promotions row tagged with metadata.template_id.
+
+CREATE TABLE IF NOT EXISTS promotion_templates (
+    id uuid PRIMARY KEY,
+    location_id uuid NOT NULL REFERENCES business_locations(id) ON DELETE CASCADE,
+    title text NOT NULL,
+    subtitle text,
+    description text,
+    promotion_type business_promotion_type NOT NULL,
+    image_url text,
+    prize text,
+    reward_points integer NOT NULL DEFAULT 0,
+    discount_percent integer,
+    max_claims integer,
+    per_user_limit integer,
+    requires_check_in boolean NOT NULL DEFAULT false,
+    requires_purchase boolean NOT NULL DEFAULT false,
+    terms text,
+    metadata jsonb NOT NULL DEFAULT '{}'::jsonb,
+    rrule text NOT NULL,
+    -- First occurrence, in local time of `timezone`
+    dtstart timestamp NOT NULL,
+    duration_minutes integer NOT NULL CHECK (duration_minutes > 0),
+    timezone text NOT NULL,
+    is_active boolean NOT NULL DEFAULT true,
+    -- Occurrences starting before this instant have already been materialized
+    materialized_until timestamptz,
+    created_by uuid,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_promotion_templates_location
+    ON promotion_templates (location_id);
+
+-- One promotion per template occurrence, even if it was deleted afterwards,
+-- so re-running the scheduler never recreates it
+CREATE UNIQUE INDEX IF NOT EXISTS ux_business_promotions_template_occurrence
+    ON business_promotions ((metadata->>'template_id'), starts_at)
+    WHERE metadata ? 'template_id';
@@ -0,0 +1,47 @@
+-- Promotions that apply to a single location, an explicit set of locations,
+-- or every location of a business. business_promotions.location_id is only
+-- set for single-location promotions; the targets of the other two scopes
+-- live in business_promotion_locations, which also keeps per-location claim
+-- counters.
+
+CREATE TYPE promotion_target_scope AS ENUM ('location','locations','business');
+
+CREATE TYPE promotion_limit_scope AS ENUM ('global','per_location');
+
+ALTER TABLE business_promotions
+    ADD COLUMN IF NOT EXISTS business_id uuid REFERENCES businesses(id) ON DELETE CASCADE,
+    ADD COLUMN IF NOT EXISTS target_scope promotion_target_scope NOT NULL DEFAULT 'location',
+    ADD COLUMN IF NOT EXISTS limit_scope promotion_limit_scope NOT NULL DEFAULT 'global';
+
+UPDATE business_promotions p
+SET business_id = l.business_id
+FROM business_locations l
+WHERE p.location_id = l.id AND p.business_id IS NULL;
+
+ALTER TABLE business_promotions
+    ALTER COLUMN business_id SET NOT NULL,
+    ALTER COLUMN location_id DROP NOT NULL,
+    ADD CONSTRAINT business_promotions_target_location
+        CHECK ((target_scope = 'location') = (location_id IS NOT NULL));
+
+CREATE INDEX IF NOT EXISTS idx_business_promotions_business_live
+    ON business_promotions (business_id, starts_at DESC) WHERE deleted_at IS NULL;
+
+-- Targets of single- and multi-location promotions. Business-wide promotions
+-- get a row the first time a location records a claim.
+CREATE TABLE IF NOT EXISTS business_promotion_locations (
+    promotion_id uuid NOT NULL REFERENCES business_promotions(id) ON DELETE CASCADE,
+    location_id uuid NOT NULL REFERENCES business_locations(id) ON DELETE CASCADE,
+    total_claims integer NOT NULL DEFAULT 0,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (promotion_id, location_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_business_promotion_locations_location
+    ON business_promotion_locations (location_id);
+
+INSERT INTO business_promotion_locations (promotion_id, location_id, total_claims, created_at)
+SELECT id, location_id, total_claims, created_at
+FROM business_promotions
+WHERE location_id IS NOT NULL
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,24 @@
+-- Moderation of promotions before publication. Promotions of businesses that
+-- are not trusted wait in 'pending_review' until a reviewer approves or
+-- rejects them; each decision is kept in promotion_review_events, like
+-- business_review_events does for registrations.
+
+ALTER TYPE business_promotion_status ADD VALUE IF NOT EXISTS 'pending_review';
+ALTER TYPE business_promotion_status ADD VALUE IF NOT EXISTS 'rejected';
+
+ALTER TABLE businesses
+    ADD COLUMN IF NOT EXISTS promotions_trusted boolean NOT NULL DEFAULT false;
+
+CREATE TABLE IF NOT EXISTS promotion_review_events (
+    id uuid PRIMARY KEY,
+    promotion_id uuid NOT NULL REFERENCES business_promotions(id) ON DELETE CASCADE,
+    reviewer_id uuid,
+    reviewer_name text,
+    action business_review_action NOT NULL,
+    notes text,
+    rejection_reason text,
+    created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_promotion_review_events_promotion
+    ON promotion_review_events (promotion_id, created_at);
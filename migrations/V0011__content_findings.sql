@@ -0,0 +1,8 @@
+-- Findings of the automated content policy checks, kept with the promotion
+-- or registration they were computed for and shown to reviewers.
+
+ALTER TABLE business_promotions
+    ADD COLUMN IF NOT EXISTS content_findings jsonb NOT NULL DEFAULT '[]'::jsonb;
+
+ALTER TABLE business_registration_requests
+    ADD COLUMN IF NOT EXISTS content_findings jsonb NOT NULL DEFAULT '[]'::jsonb;
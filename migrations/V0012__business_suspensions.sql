@@ -0,0 +1,38 @@
+-- Suspending a registration deactivates its business, hides its locations and
+-- pauses (or cancels) its published promotions. Each suspension records what
+-- it changed so resuming restores exactly that, and can expire on its own.
+
+ALTER TYPE business_promotion_status ADD VALUE IF NOT EXISTS 'paused';
+
+CREATE TABLE IF NOT EXISTS business_suspensions (
+    id uuid PRIMARY KEY,
+    registration_id uuid NOT NULL REFERENCES business_registration_requests(id) ON DELETE CASCADE,
+    business_id uuid REFERENCES businesses(id) ON DELETE CASCADE,
+    previous_status business_verification_status NOT NULL,
+    business_was_active boolean NOT NULL DEFAULT false,
+    hidden_location_ids uuid[] NOT NULL DEFAULT '{}',
+    cancel_promotions boolean NOT NULL DEFAULT false,
+    reason text,
+    suspended_by uuid,
+    suspended_by_name text,
+    suspended_at timestamptz NOT NULL DEFAULT now(),
+    expires_at timestamptz,
+    resumed_at timestamptz,
+    resumed_by uuid,
+    resumed_by_name text
+);
+
+-- At most one suspension in force per registration
+CREATE UNIQUE INDEX IF NOT EXISTS idx_business_suspensions_current
+    ON business_suspensions (registration_id) WHERE resumed_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_business_suspensions_expiry
+    ON business_suspensions (expires_at) WHERE resumed_at IS NULL AND expires_at IS NOT NULL;
+
+-- Promotions a suspension paused or cancelled, with the status to restore
+CREATE TABLE IF NOT EXISTS business_suspension_promotions (
+    suspension_id uuid NOT NULL REFERENCES business_suspensions(id) ON DELETE CASCADE,
+    promotion_id uuid NOT NULL REFERENCES business_promotions(id) ON DELETE CASCADE,
+    previous_status business_promotion_status NOT NULL,
+    PRIMARY KEY (suspension_id, promotion_id)
+);
@@ -0,0 +1,12 @@
+-- Token buckets shared by every replica when RATE_LIMIT_STORE=postgres.
+-- Counters are disposable, so the table skips the WAL.
+
+CREATE UNLOGGED TABLE IF NOT EXISTS rate_limit_buckets (
+    key text PRIMARY KEY,
+    tokens double precision NOT NULL,
+    allowed boolean NOT NULL DEFAULT true,
+    updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limit_buckets_updated_at
+    ON rate_limit_buckets (updated_at);
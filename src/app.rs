@@ -0,0 +1,287 @@
+//! The actix application: shared state, middleware and routes. `main` and
+//! the integration tests both build it here, so tests run the same stack
+//! the service serves.
+
+use std::sync::Arc;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::middleware::from_fn;
+use actix_web::{web, App};
+
+use crate::clients::stories::StoriesClient;
+use crate::config::{ClientsConfig, CorsConfig, HealthConfig, ServerConfig};
+use crate::content_policy::{ContentPolicy, ContentPolicyConfig};
+use crate::database::DatabaseService;
+use crate::moderation::PromotionModeration;
+use crate::rate_limit::RateLimiter;
+use crate::repository::Repositories;
+use crate::shutdown::Shutdown;
+use crate::{errors, handlers, metrics, rate_limit, telemetry};
+
+/// What every worker's `App` shares. Built with [`AppState::builder`].
+#[derive(Clone)]
+pub struct AppState {
+    /// `None` on the memory storage backend
+    db: Option<web::Data<DatabaseService>>,
+    repositories: Repositories,
+    stories_client: web::Data<StoriesClient>,
+    moderation: web::Data<PromotionModeration>,
+    content_policy: web::Data<ContentPolicy>,
+    rate_limiter: Option<web::Data<RateLimiter>>,
+    health_config: web::Data<HealthConfig>,
+    shutdown: web::Data<Shutdown>,
+    cors: CorsConfig,
+    json_limit: usize,
+    payload_limit: usize,
+}
+
+impl AppState {
+    /// Everything but the database has a default: the stories service at
+    /// its default URL, moderation off, the default content policy, no rate
+    /// limiting, default CORS and body limits, and a shutdown nobody begins.
+    /// Call it from the runtime the server runs on, like `Shutdown::new`.
+    pub fn builder(db: Arc<DatabaseService>) -> AppStateBuilder {
+        let repositories = Repositories::postgres(db.clone());
+        Self::with_storage(Some(db), repositories)
+    }
+
+    /// Like [`AppState::builder`], on a fresh [`MemoryStore`](crate::repository::MemoryStore)
+    /// and without Postgres: the routes that still need it answer
+    /// `postgres_required`
+    pub fn in_memory() -> AppStateBuilder {
+        Self::with_storage(None, Repositories::memory())
+    }
+
+    fn with_storage(db: Option<Arc<DatabaseService>>, repositories: Repositories) -> AppStateBuilder {
+        let server = ServerConfig::default();
+        let clients = ClientsConfig::default();
+        AppStateBuilder {
+            db,
+            repositories,
+            stories_client: StoriesClient::new(clients.stories_url.clone(), clients.request_timeout()),
+            moderation: PromotionModeration { enabled: false },
+            content_policy: None,
+            rate_limiter: None,
+            health_config: HealthConfig::default(),
+            shutdown: Shutdown::new(),
+            cors: CorsConfig::default(),
+            json_limit: server.json_limit_bytes,
+            payload_limit: server.payload_limit_bytes,
+        }
+    }
+
+    /// Shared data and extractor settings the handlers rely on, for
+    /// embedding [`configure`] in another `App`
+    pub fn register(&self, cfg: &mut web::ServiceConfig) {
+        let repositories = &self.repositories;
+        cfg.app_data(web::Data::from(repositories.registrations.clone()))
+            .app_data(web::Data::from(repositories.businesses.clone()))
+            .app_data(web::Data::from(repositories.locations.clone()))
+            .app_data(web::Data::from(repositories.promotions.clone()))
+            .app_data(web::Data::from(repositories.location_admins.clone()))
+            .app_data(web::Data::from(repositories.audit_logs.clone()))
+            .app_data(self.stories_client.clone())
+            .app_data(self.moderation.clone())
+            .app_data(self.content_policy.clone())
+            .app_data(self.health_config.clone())
+            .app_data(self.shutdown.clone())
+            .app_data(errors::json_config().limit(self.json_limit))
+            .app_data(web::PayloadConfig::new(self.payload_limit))
+            .app_data(errors::path_config())
+            .app_data(errors::query_config());
+        if let Some(db) = &self.db {
+            cfg.app_data(db.clone());
+        }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            cfg.app_data(rate_limiter.clone());
+        }
+    }
+}
+
+pub struct AppStateBuilder {
+    db: Option<Arc<DatabaseService>>,
+    repositories: Repositories,
+    stories_client: StoriesClient,
+    moderation: PromotionModeration,
+    content_policy: Option<Arc<ContentPolicy>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    health_config: HealthConfig,
+    shutdown: Shutdown,
+    cors: CorsConfig,
+    json_limit: usize,
+    payload_limit: usize,
+}
+
+impl AppStateBuilder {
+    /// Serve registrations, businesses, locations, promotions, location
+    /// admins, claims and the audit trail from these repositories instead
+    pub fn repositories(mut self, repositories: Repositories) -> Self {
+        self.repositories = repositories;
+        self
+    }
+
+    pub fn stories_client(mut self, stories_client: StoriesClient) -> Self {
+        self.stories_client = stories_client;
+        self
+    }
+
+    pub fn moderation(mut self, moderation: PromotionModeration) -> Self {
+        self.moderation = moderation;
+        self
+    }
+
+    pub fn content_policy(mut self, content_policy: Arc<ContentPolicy>) -> Self {
+        self.content_policy = Some(content_policy);
+        self
+    }
+
+    pub fn rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    pub fn health_config(mut self, health_config: HealthConfig) -> Self {
+        self.health_config = health_config;
+        self
+    }
+
+    /// Readiness reports draining once this shutdown begins
+    pub fn shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub fn cors(mut self, cors: CorsConfig) -> Self {
+        self.cors = cors;
+        self
+    }
+
+    /// JSON and raw body limits of `server`
+    pub fn body_limits(mut self, server: &ServerConfig) -> Self {
+        self.json_limit = server.json_limit_bytes;
+        self.payload_limit = server.payload_limit_bytes;
+        self
+    }
+
+    pub fn build(self) -> AppState {
+        let content_policy = self.content_policy.unwrap_or_else(|| {
+            Arc::new(ContentPolicy::from_config(ContentPolicyConfig::default()).expect("the default content policy is valid"))
+        });
+        AppState {
+            db: self.db.map(web::Data::from),
+            repositories: self.repositories,
+            stories_client: web::Data::new(self.stories_client),
+            moderation: web::Data::new(self.moderation),
+            content_policy: web::Data::from(content_policy),
+            rate_limiter: self.rate_limiter.map(web::Data::from),
+            health_config: web::Data::new(self.health_config),
+            shutdown: web::Data::new(self.shutdown),
+            cors: self.cors,
+            json_limit: self.json_limit,
+            payload_limit: self.payload_limit,
+        }
+    }
+}
+
+/// The `App` of one HTTP worker: [`configure`] behind the service's
+/// middleware
+pub fn build(
+    state: &AppState,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    App::new()
+        .configure(|cfg| state.register(cfg))
+        .wrap(from_fn(rate_limit::enforce))
+        .wrap(from_fn(errors::render_errors))
+        .wrap(state.cors.middleware())
+        .wrap(from_fn(metrics::track))
+        .wrap(from_fn(telemetry::correlate))
+        .configure(configure)
+}
+
+/// Every route of the service: `/metrics` and the API under `/api/v1`
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(handlers::prometheus_metrics).service(
+        web::scope("/api/v1")
+            // Health
+            .service(handlers::health_check)
+            .service(handlers::health_live)
+            .service(handlers::health_ready)
+            // Registrations (verification workflow)
+            .service(handlers::submit_registration)
+            .service(handlers::get_registration)
+            .service(handlers::get_latest_registration_for_user)
+            .service(handlers::list_registrations_for_user)
+            // Review system
+            .service(handlers::list_pending_reviews)
+            .service(handlers::get_review_stats)
+            .service(handlers::get_business_review)
+            .service(handlers::submit_review_action)
+            .service(handlers::list_registration_suspensions)
+            // Businesses
+            .service(handlers::create_business)
+            .service(handlers::get_business)
+            .service(handlers::list_businesses_for_user)
+            .service(handlers::update_business)
+            .service(handlers::patch_business)
+            .service(handlers::delete_business)
+            .service(handlers::restore_business)
+            // Locations
+            .service(handlers::import_locations)
+            .service(handlers::create_location)
+            .service(handlers::get_location)
+            .service(handlers::list_locations_for_business)
+            .service(handlers::update_location)
+            .service(handlers::patch_location)
+            .service(handlers::delete_location)
+            .service(handlers::restore_location)
+            // Promotions
+            .service(handlers::create_promotion)
+            .service(handlers::create_business_promotion)
+            .service(handlers::get_promotion)
+            .service(handlers::list_promotions_for_location)
+            .service(handlers::list_promotions_for_business)
+            .service(handlers::update_promotion)
+            .service(handlers::patch_promotion)
+            .service(handlers::delete_promotion)
+            .service(handlers::restore_promotion)
+            .service(handlers::clone_promotion)
+            .service(handlers::list_promotion_locations)
+            .service(handlers::claim_promotion)
+            // Promotion moderation
+            .service(handlers::list_pending_promotion_reviews)
+            .service(handlers::get_promotion_review)
+            .service(handlers::submit_promotion_review_action)
+            .service(handlers::set_business_promotion_trust)
+            // Promotion Templates
+            .service(handlers::create_promotion_template)
+            .service(handlers::list_promotion_templates_for_location)
+            .service(handlers::get_promotion_template)
+            .service(handlers::delete_promotion_template)
+            // Location Admins
+            .service(handlers::add_location_admin)
+            .service(handlers::list_location_admins)
+            .service(handlers::remove_location_admin)
+            // Webhooks
+            .service(handlers::create_webhook_subscription)
+            .service(handlers::list_webhook_subscriptions)
+            .service(handlers::get_webhook_subscription)
+            .service(handlers::update_webhook_subscription)
+            .service(handlers::delete_webhook_subscription)
+            .service(handlers::list_webhook_deliveries)
+            // Audit trail
+            .service(handlers::list_audit_logs)
+            // Exports
+            .service(handlers::export_businesses)
+            .service(handlers::export_promotions)
+            .service(handlers::export_locations),
+    );
+}
@@ -0,0 +1,126 @@
+//! Context and helpers for the generic audit trail (`audit_logs`).
+
+use actix_web::{HttpMessage, HttpRequest};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::models::ReviewAction;
+use crate::telemetry::RequestContext;
+
+/// Who performed a write and where it came from
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    pub actor_id: Option<Uuid>,
+    pub actor_name: Option<String>,
+    pub request_id: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl AuditContext {
+    pub fn from_request(req: &HttpRequest, actor_id: Option<Uuid>, actor_name: Option<String>) -> Self {
+        let request_id = req
+            .extensions()
+            .get::<RequestContext>()
+            .map(|context| context.request_id.clone());
+        let ip_address = req
+            .connection_info()
+            .realip_remote_addr()
+            .map(|s| s.to_string());
+
+        Self {
+            actor_id,
+            actor_name,
+            request_id,
+            ip_address,
+        }
+    }
+
+    /// Context for writes performed by the service itself (background jobs)
+    pub fn system(job: &str) -> Self {
+        Self {
+            actor_name: Some(format!("system:{job}")),
+            ..Self::default()
+        }
+    }
+}
+
+/// Kind of entity an audit entry refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditEntity {
+    Registration,
+    Business,
+    Location,
+    Promotion,
+    PromotionTemplate,
+    LocationAdmin,
+    WebhookSubscription,
+}
+
+impl AuditEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntity::Registration => "registration",
+            AuditEntity::Business => "business",
+            AuditEntity::Location => "location",
+            AuditEntity::Promotion => "promotion",
+            AuditEntity::PromotionTemplate => "promotion_template",
+            AuditEntity::LocationAdmin => "location_admin",
+            AuditEntity::WebhookSubscription => "webhook_subscription",
+        }
+    }
+}
+
+/// What was done to the entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    /// Hard delete of a soft-deleted row after the retention window
+    Purge,
+    Review(ReviewAction),
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Restore => "restore",
+            AuditAction::Purge => "purge",
+            AuditAction::Review(ReviewAction::Approve) => "review.approve",
+            AuditAction::Review(ReviewAction::Reject) => "review.reject",
+            AuditAction::Review(ReviewAction::RequestMoreInfo) => "review.request_more_info",
+            AuditAction::Review(ReviewAction::Suspend) => "review.suspend",
+            AuditAction::Review(ReviewAction::Resume) => "review.resume",
+            AuditAction::Review(ReviewAction::Comment) => "review.comment",
+        }
+    }
+}
+
+/// Field-level diff between two JSON objects: `{"field": {"from": .., "to": ..}}`.
+///
+/// Missing sides are treated as empty objects, so creations list every field
+/// with `from: null` and deletions every field with `to: null`.
+pub fn json_diff(before: Option<&Value>, after: Option<&Value>) -> Value {
+    let empty = Map::new();
+    let before = before.and_then(Value::as_object).unwrap_or(&empty);
+    let after = after.and_then(Value::as_object).unwrap_or(&empty);
+
+    let mut diff = Map::new();
+    for (key, old) in before {
+        let new = after.get(key).unwrap_or(&Value::Null);
+        if old != new {
+            diff.insert(key.clone(), serde_json::json!({ "from": old, "to": new }));
+        }
+    }
+    for (key, new) in after {
+        if !before.contains_key(key) && !new.is_null() {
+            diff.insert(key.clone(), serde_json::json!({ "from": Value::Null, "to": new }));
+        }
+    }
+
+    Value::Object(diff)
+}
@@ -1,7 +1,7 @@
 use dotenvy::dotenv;
 use glob::glob;
+use sqlx::{Connection, PgConnection};
 use std::fs;
-use tokio_postgres::{NoTls};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -9,31 +9,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set in environment");
 
-    let (mut client, connection) = tokio_postgres::connect(&database_url, NoTls).await?;
+    let mut conn = PgConnection::connect(&database_url).await?;
 
-    tokio::spawn(async move {
-        if let Err(e) = connection.await {
-            eprintln!("connection error: {}", e);
-        }
-    });
-
-    client
-        .execute(
-            "\
+    sqlx::query(
+        "\
         CREATE TABLE IF NOT EXISTS schema_migrations (\
             version VARCHAR(50) PRIMARY KEY,\
             description TEXT,\
             installed_on TIMESTAMPTZ NOT NULL DEFAULT NOW()\
         )",
-            &[],
-        )
-        .await?;
+    )
+    .execute(&mut conn)
+    .await?;
 
     let mut migrations: Vec<String> = Vec::new();
-    for entry in glob("migrations/V*.sql")? {
-        if let Ok(path) = entry {
-            migrations.push(path.to_string_lossy().to_string());
-        }
+    for path in glob("migrations/V*.sql")?.flatten() {
+        migrations.push(path.to_string_lossy().to_string());
     }
 
     migrations.sort();
@@ -50,8 +41,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .unwrap_or(&file)
             .to_string();
 
-        let row = client
-            .query_opt("SELECT version FROM schema_migrations WHERE version = $1", &[&name])
+        let row = sqlx::query("SELECT version FROM schema_migrations WHERE version = $1")
+            .bind(&name)
+            .fetch_optional(&mut conn)
             .await?;
 
         if row.is_some() {
@@ -62,13 +54,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("Applying migration: {}", name);
         let sql = fs::read_to_string(&file)?;
 
-        let txn = client.transaction().await?;
-        txn.batch_execute(&sql).await?;
-        txn.execute(
-            "INSERT INTO schema_migrations (version, description) VALUES ($1, $2)",
-            &[&name, &name],
-        )
-        .await?;
+        let mut txn = conn.begin().await?;
+        sqlx::raw_sql(&sql).execute(&mut *txn).await?;
+        sqlx::query("INSERT INTO schema_migrations (version, description) VALUES ($1, $2)")
+            .bind(&name)
+            .bind(&name)
+            .execute(&mut *txn)
+            .await?;
         txn.commit().await?;
 
         println!("Applied: {}", name);
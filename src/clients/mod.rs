@@ -1 +1,2 @@
 pub mod stories;
+pub mod webhooks;
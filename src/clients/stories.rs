@@ -1,7 +1,12 @@
+use std::time::{Duration, Instant};
+
 use serde::Serialize;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+use crate::metrics::metrics;
+use crate::telemetry;
+
 #[derive(Debug, Serialize)]
 pub struct SharePromotionRequest {
     pub author_id: Uuid,
@@ -27,21 +32,21 @@ pub struct StoriesClient {
 }
 
 impl StoriesClient {
-    pub fn new(base_url: String) -> Self {
+    pub fn new(base_url: String, timeout: Duration) -> Self {
         let normalized = normalize_base_url(&base_url);
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
         Self {
-            client: reqwest::Client::new(),
+            client,
             base_url: normalized,
         }
     }
 
     pub async fn share_promotion(&self, request: SharePromotionRequest) -> Result<(), String> {
         let url = format!("{}/stories/promotion", self.base_url);
-        let response = self.client.post(&url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+        let response = self.send("share_promotion", self.client.post(&url).json(&request)).await?;
 
         if !response.status().is_success() {
             let text = response.text().await.unwrap_or_default();
@@ -50,6 +55,33 @@ impl StoriesClient {
 
         Ok(())
     }
+
+    /// Call the service's health endpoint; any 2xx counts as up
+    pub async fn health(&self) -> Result<(), String> {
+        let url = format!("{}/health", self.base_url);
+        let response = self.send("health", self.client.get(&url)).await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("health check returned {}", response.status()))
+        }
+    }
+
+    /// Send `request` with the caller's trace context, recording its outcome
+    /// and latency under `operation`
+    async fn send(&self, operation: &str, request: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+        let started = Instant::now();
+        let result = telemetry::propagate(request).send().await;
+        let outcome = match &result {
+            Ok(response) if response.status().is_success() => "success",
+            Ok(response) if response.status().is_client_error() => "client_error",
+            Ok(_) => "server_error",
+            Err(e) if e.is_timeout() => "timeout",
+            Err(_) => "connection_error",
+        };
+        metrics().observe_stories_call(operation, outcome, started.elapsed());
+        result.map_err(|e| e.to_string())
+    }
 }
 
 fn normalize_base_url(value: &str) -> String {
@@ -0,0 +1,167 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::redirect::Policy;
+use reqwest::Url;
+use sha2::Sha256;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const SIGNATURE_HEADER: &str = "X-CrazyTrip-Signature";
+pub const EVENT_HEADER: &str = "X-CrazyTrip-Event";
+pub const DELIVERY_HEADER: &str = "X-CrazyTrip-Delivery";
+
+/// Why a delivery attempt did not succeed
+#[derive(Debug)]
+pub struct DeliveryFailure {
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+#[derive(Clone)]
+pub struct WebhookClient {
+    client: reqwest::Client,
+}
+
+impl WebhookClient {
+    pub fn new(timeout: Duration) -> Self {
+        // Redirects could lead a delivery anywhere, so none are followed
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .redirect(Policy::none())
+            .dns_resolver(Arc::new(PublicResolver))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self { client }
+    }
+
+    /// POST a signed payload to a subscriber. Any 2xx counts as delivered.
+    pub async fn deliver(
+        &self,
+        url: &str,
+        secret: &str,
+        event_type: &str,
+        delivery_id: Uuid,
+        body: Vec<u8>,
+    ) -> Result<u16, DeliveryFailure> {
+        check_target(url).map_err(|reason| DeliveryFailure {
+            status: None,
+            message: format!("Refusing to deliver to {url}: {reason}"),
+        })?;
+
+        let timestamp = chrono::Utc::now().timestamp();
+        let signature = sign_payload(secret, timestamp, &body);
+
+        let response = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, format!("t={},v1={}", timestamp, signature))
+            .header(EVENT_HEADER, event_type)
+            .header(DELIVERY_HEADER, delivery_id.to_string())
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| DeliveryFailure {
+                status: None,
+                message: e.to_string(),
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(DeliveryFailure {
+                status: Some(status.as_u16()),
+                message: format!("Subscriber responded with {}: {}", status, truncate(&text, 500)),
+            });
+        }
+
+        Ok(status.as_u16())
+    }
+}
+
+/// Why `url` may not receive deliveries: subscribers must be https and not
+/// on loopback, private or link-local addresses. Host names are checked
+/// again when they resolve, by `PublicResolver`.
+pub fn check_target(url: &str) -> Result<(), &'static str> {
+    let url = Url::parse(url).map_err(|_| "not a valid URL")?;
+    if url.scheme() != "https" {
+        return Err("only https URLs are allowed");
+    }
+    let host = url.host_str().unwrap_or_default();
+    // IPv6 hosts keep their brackets in the URL
+    let public = match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(ip) => is_public_ip(ip),
+        Err(_) => {
+            let domain = host.trim_end_matches('.').to_ascii_lowercase();
+            !domain.is_empty() && domain != "localhost" && !domain.ends_with(".localhost")
+        }
+    };
+    if public {
+        Ok(())
+    } else {
+        Err("the host is not a public address")
+    }
+}
+
+/// Reachable on the public internet: not loopback, private, link-local or
+/// unspecified (IPv4-mapped IPv6 addresses are judged as IPv4)
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast())
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ip(IpAddr::V4(ip)),
+            None => {
+                let unique_local = ip.segments()[0] & 0xfe00 == 0xfc00;
+                let link_local = ip.segments()[0] & 0xffc0 == 0xfe80;
+                !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local)
+            }
+        },
+    }
+}
+
+/// System resolver that drops non-public addresses, so a subscriber host
+/// name pointing at an internal service cannot be used to reach it
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} does not resolve to a public address", name.as_str()).into());
+            }
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// Hex-encoded HMAC-SHA256 over `"{timestamp}.{body}"`.
+///
+/// Including the timestamp lets subscribers reject replayed requests.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn truncate(value: &str, max_chars: usize) -> String {
+    value.chars().take(max_chars).collect()
+}
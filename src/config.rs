@@ -0,0 +1,716 @@
+//! Typed service configuration.
+//!
+//! Settings come from the TOML file named by `CONFIG_FILE`, when set, with
+//! environment variables taking precedence over it. Everything is checked
+//! once at startup and every problem is reported together, so the service
+//! never starts half-configured.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_cors::Cors;
+use actix_web::http::{header, Method};
+use serde::Deserialize;
+
+use crate::content_policy::{ContentPolicy, ContentPolicyConfig};
+use crate::events::EventBroker;
+use crate::jobs::audit_retention::AuditRetentionConfig;
+use crate::jobs::metrics_collector::MetricsCollectorConfig;
+use crate::jobs::outbox_relay::OutboxRelayConfig;
+use crate::jobs::promotion_scheduler::PromotionSchedulerConfig;
+use crate::jobs::soft_delete_purge::SoftDeletePurgeConfig;
+use crate::jobs::suspension_expiry::SuspensionExpiryConfig;
+use crate::jobs::webhook_dispatcher::WebhookDispatcherConfig;
+use crate::moderation::PromotionModeration;
+use crate::rate_limit::{self, RateLimitBackend, RateLimitConfig};
+use crate::repository::StorageBackend;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    pub server: ServerConfig,
+    pub cors: CorsConfig,
+    pub database: DatabaseConfig,
+    pub clients: ClientsConfig,
+    pub health: HealthConfig,
+    pub jobs: JobsConfig,
+    pub rate_limit: RateLimitConfig,
+    pub moderation: PromotionModeration,
+    pub events: EventsConfig,
+    pub content_policy: ContentPolicyConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    /// HTTP worker threads; one per CPU core when unset
+    pub workers: Option<usize>,
+    /// Largest JSON body accepted
+    pub json_limit_bytes: usize,
+    /// Largest raw body accepted, e.g. location imports
+    pub payload_limit_bytes: usize,
+    pub keep_alive_secs: u64,
+    /// Time a client has to send the request head
+    pub client_request_timeout_secs: u64,
+    /// Time in-flight requests and background tasks get to finish on
+    /// shutdown
+    pub shutdown_timeout_secs: u64,
+    /// Serve HTTPS with rustls instead of plain HTTP
+    pub tls: Option<TlsConfig>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 8082,
+            workers: None,
+            json_limit_bytes: 256 * 1024,
+            payload_limit_bytes: 5 * 1024 * 1024,
+            keep_alive_secs: 5,
+            client_request_timeout_secs: 5,
+            shutdown_timeout_secs: 30,
+            tls: None,
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn bind_address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    /// PEM certificate chain, leaf first
+    pub cert_path: PathBuf,
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1)
+    pub key_path: PathBuf,
+}
+
+impl TlsConfig {
+    pub fn server_config(&self) -> Result<rustls::ServerConfig, String> {
+        use rustls::pki_types::pem::PemObject;
+        use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+        let cert_path = self.cert_path.display();
+        let certs = CertificateDer::pem_file_iter(&self.cert_path)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .map_err(|err| format!("cannot read TLS certificate {cert_path}: {err}"))?;
+        if certs.is_empty() {
+            return Err(format!("TLS certificate {cert_path} contains no certificate"));
+        }
+        let key = PrivateKeyDer::from_pem_file(&self.key_path)
+            .map_err(|err| format!("cannot read TLS private key {}: {err}", self.key_path.display()))?;
+
+        rustls::ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|err| format!("unsupported TLS protocol versions: {err}"))?
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|err| format!("TLS certificate and key do not match: {err}"))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    /// Origins such as `https://app.crazytrip.com`, or `*` for any. Empty
+    /// allows no cross-origin calls.
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    pub allow_credentials: bool,
+    pub max_age_secs: usize,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: ["GET", "POST", "PUT", "PATCH", "DELETE"].map(String::from).to_vec(),
+            allowed_headers: [
+                "Accept",
+                "Accept-Language",
+                "Authorization",
+                "Content-Type",
+                "If-Match",
+                "traceparent",
+                "X-Actor-Id",
+                "X-Actor-Name",
+                "X-Actor-Role",
+                "X-Request-Id",
+            ]
+            .map(String::from)
+            .to_vec(),
+            allow_credentials: false,
+            max_age_secs: 3600,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Middleware for these settings; `validate` must have passed
+    pub fn middleware(&self) -> Cors {
+        let mut cors = Cors::default()
+            .allowed_methods(self.allowed_methods.iter().map(String::as_str))
+            .allowed_headers(self.allowed_headers.iter().map(String::as_str))
+            .expose_headers([
+                header::ETAG,
+                header::CONTENT_LANGUAGE,
+                header::RETRY_AFTER,
+                crate::telemetry::REQUEST_ID_HEADER,
+                header::HeaderName::from_static("x-ratelimit-limit"),
+                header::HeaderName::from_static("x-ratelimit-remaining"),
+                header::HeaderName::from_static("x-ratelimit-reset"),
+            ])
+            .max_age(self.max_age_secs);
+        for origin in &self.allowed_origins {
+            cors = if origin == "*" {
+                cors.allow_any_origin()
+            } else {
+                cors.allowed_origin(origin)
+            };
+        }
+        if self.allow_credentials {
+            cors = cors.supports_credentials();
+        }
+        cors
+    }
+
+    fn validate(&self, errors: &mut Vec<String>) {
+        for origin in &self.allowed_origins {
+            if origin == "*" {
+                if self.allow_credentials {
+                    errors.push("cors.allowed_origins cannot be * when cors.allow_credentials is set".to_string());
+                }
+                continue;
+            }
+            let valid = reqwest::Url::parse(origin).is_ok_and(|url| {
+                matches!(url.scheme(), "http" | "https") && url.host().is_some() && url.path() == "/" && !origin.ends_with('/')
+            });
+            if !valid {
+                errors.push(format!("cors.allowed_origins entry '{origin}' is not an origin like https://example.com"));
+            }
+        }
+        for method in &self.allowed_methods {
+            if Method::from_str(method).is_err() {
+                errors.push(format!("cors.allowed_methods entry '{method}' is not an HTTP method"));
+            }
+        }
+        for name in &self.allowed_headers {
+            if header::HeaderName::from_str(name).is_err() {
+                errors.push(format!("cors.allowed_headers entry '{name}' is not a header name"));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    /// Where the service keeps its data; `url` is only needed for Postgres
+    pub backend: StorageBackend,
+    pub url: String,
+    pub pool_max_size: usize,
+    /// Time a request waits for a free connection before failing with 503
+    pub pool_timeout_secs: u64,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackend::Postgres,
+            url: String::new(),
+            pool_max_size: 16,
+            pool_timeout_secs: 5,
+        }
+    }
+}
+
+impl DatabaseConfig {
+    pub fn pool_timeout(&self) -> Duration {
+        Duration::from_secs(self.pool_timeout_secs)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientsConfig {
+    pub stories_url: String,
+    /// Timeout of each call to another CrazyTrip service
+    pub request_timeout_secs: u64,
+}
+
+impl Default for ClientsConfig {
+    fn default() -> Self {
+        Self {
+            stories_url: "http://localhost:8083".to_string(),
+            request_timeout_secs: 10,
+        }
+    }
+}
+
+impl ClientsConfig {
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout_secs)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthConfig {
+    /// Include the stories service in readiness
+    pub probe_stories: bool,
+    /// Time each readiness probe gets before it counts as down
+    pub check_timeout_ms: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            probe_stories: false,
+            check_timeout_ms: 2000,
+        }
+    }
+}
+
+impl HealthConfig {
+    pub fn check_timeout(&self) -> Duration {
+        Duration::from_millis(self.check_timeout_ms)
+    }
+}
+
+/// Background jobs; the outbox relay runs on both storage backends, the
+/// others only on Postgres
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobsConfig {
+    pub outbox_relay: OutboxRelayConfig,
+    pub webhook_dispatcher: WebhookDispatcherConfig,
+    pub audit_retention: AuditRetentionConfig,
+    pub soft_delete_purge: SoftDeletePurgeConfig,
+    pub suspension_expiry: SuspensionExpiryConfig,
+    pub promotion_scheduler: PromotionSchedulerConfig,
+    pub metrics_collector: MetricsCollectorConfig,
+}
+
+impl JobsConfig {
+    fn validate(&self, errors: &mut Vec<String>) {
+        for (name, value) in [
+            ("jobs.outbox_relay.poll_interval_secs", self.outbox_relay.poll_interval_secs),
+            ("jobs.webhook_dispatcher.poll_interval_secs", self.webhook_dispatcher.poll_interval_secs),
+            ("jobs.webhook_dispatcher.request_timeout_secs", self.webhook_dispatcher.request_timeout_secs),
+            ("jobs.audit_retention.purge_interval_secs", self.audit_retention.purge_interval_secs),
+            ("jobs.soft_delete_purge.purge_interval_secs", self.soft_delete_purge.purge_interval_secs),
+            ("jobs.suspension_expiry.interval_secs", self.suspension_expiry.interval_secs),
+            ("jobs.promotion_scheduler.interval_secs", self.promotion_scheduler.interval_secs),
+            ("jobs.metrics_collector.interval_secs", self.metrics_collector.interval_secs),
+        ] {
+            if value == 0 {
+                errors.push(format!("{name} must be positive"));
+            }
+        }
+        for (name, value) in [
+            ("jobs.outbox_relay.batch_size", self.outbox_relay.batch_size),
+            ("jobs.webhook_dispatcher.batch_size", self.webhook_dispatcher.batch_size),
+            ("jobs.webhook_dispatcher.max_attempts", i64::from(self.webhook_dispatcher.max_attempts)),
+            (
+                "jobs.webhook_dispatcher.disable_after_failures",
+                i64::from(self.webhook_dispatcher.disable_after_failures),
+            ),
+            ("jobs.suspension_expiry.batch_size", self.suspension_expiry.batch_size),
+        ] {
+            if value <= 0 {
+                errors.push(format!("{name} must be positive"));
+            }
+        }
+        // 0 turns these off, except for the outbox, where it purges relayed
+        // events right away
+        for (name, value) in [
+            ("jobs.outbox_relay.retention_days", i64::from(self.outbox_relay.retention_days)),
+            ("jobs.audit_retention.retention_days", i64::from(self.audit_retention.retention_days)),
+            ("jobs.soft_delete_purge.retention_days", i64::from(self.soft_delete_purge.retention_days)),
+            ("jobs.promotion_scheduler.horizon_days", self.promotion_scheduler.horizon_days),
+        ] {
+            if value < 0 {
+                errors.push(format!("{name} must not be negative"));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EventsConfig {
+    /// Where the outbox relay publishes domain events
+    pub broker: EventBroker,
+    pub nats_url: String,
+    pub kafka_brokers: String,
+    pub kafka_topic: String,
+}
+
+impl Default for EventsConfig {
+    fn default() -> Self {
+        Self {
+            broker: EventBroker::None,
+            nats_url: "nats://localhost:4222".to_string(),
+            kafka_brokers: "localhost:9092".to_string(),
+            kafka_topic: "crazytrip.business-review.events".to_string(),
+        }
+    }
+}
+
+impl EventsConfig {
+    fn validate(&self, errors: &mut Vec<String>) {
+        if !self.broker.is_available() {
+            errors.push(format!(
+                "events.broker '{}' is not supported by this build (enable the matching cargo feature)",
+                self.broker
+            ));
+        }
+        let required = match self.broker {
+            EventBroker::Nats => vec![("events.nats_url", &self.nats_url)],
+            EventBroker::Kafka => vec![("events.kafka_brokers", &self.kafka_brokers), ("events.kafka_topic", &self.kafka_topic)],
+            EventBroker::None | EventBroker::Memory => Vec::new(),
+        };
+        for (name, value) in required {
+            if value.trim().is_empty() {
+                errors.push(format!("{name} must not be empty"));
+            }
+        }
+    }
+}
+
+impl AppConfig {
+    /// Read `CONFIG_FILE` (if set), apply the environment on top and validate
+    pub fn load() -> Result<Self, String> {
+        let mut config = match std::env::var("CONFIG_FILE") {
+            Ok(path) if !path.trim().is_empty() => {
+                let text = std::fs::read_to_string(&path).map_err(|err| format!("cannot read {path}: {err}"))?;
+                toml::from_str(&text).map_err(|err| format!("invalid configuration in {path}: {err}"))?
+            }
+            _ => AppConfig::default(),
+        };
+
+        let mut errors = Vec::new();
+        config.apply_env(&mut errors);
+        config.validate(&mut errors);
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+
+    fn apply_env(&mut self, errors: &mut Vec<String>) {
+        let server = &mut self.server;
+        env_override("HOST", &mut server.host, errors);
+        env_override("PORT", &mut server.port, errors);
+        if let Some(workers) = env_value("WORKERS", errors) {
+            server.workers = Some(workers);
+        }
+        env_override("JSON_LIMIT_BYTES", &mut server.json_limit_bytes, errors);
+        env_override("PAYLOAD_LIMIT_BYTES", &mut server.payload_limit_bytes, errors);
+        env_override("KEEP_ALIVE_SECS", &mut server.keep_alive_secs, errors);
+        env_override("CLIENT_REQUEST_TIMEOUT_SECS", &mut server.client_request_timeout_secs, errors);
+        env_override("SHUTDOWN_TIMEOUT_SECS", &mut server.shutdown_timeout_secs, errors);
+        match (env_value::<PathBuf>("TLS_CERT_PATH", errors), env_value::<PathBuf>("TLS_KEY_PATH", errors)) {
+            (Some(cert_path), Some(key_path)) => server.tls = Some(TlsConfig { cert_path, key_path }),
+            (None, None) => {}
+            _ => errors.push("TLS_CERT_PATH and TLS_KEY_PATH must be set together".to_string()),
+        }
+
+        let cors = &mut self.cors;
+        env_list("CORS_ALLOWED_ORIGINS", &mut cors.allowed_origins);
+        env_list("CORS_ALLOWED_METHODS", &mut cors.allowed_methods);
+        env_list("CORS_ALLOWED_HEADERS", &mut cors.allowed_headers);
+        env_override("CORS_ALLOW_CREDENTIALS", &mut cors.allow_credentials, errors);
+        env_override("CORS_MAX_AGE_SECS", &mut cors.max_age_secs, errors);
+
+        let database = &mut self.database;
+        env_override("DATABASE_BACKEND", &mut database.backend, errors);
+        env_override("DATABASE_URL", &mut database.url, errors);
+        env_override("DATABASE_POOL_MAX_SIZE", &mut database.pool_max_size, errors);
+        env_override("DATABASE_POOL_TIMEOUT_SECS", &mut database.pool_timeout_secs, errors);
+
+        let clients = &mut self.clients;
+        env_override("STORIES_SERVICE_URL", &mut clients.stories_url, errors);
+        env_override("HTTP_CLIENT_TIMEOUT_SECS", &mut clients.request_timeout_secs, errors);
+
+        let health = &mut self.health;
+        env_override("HEALTH_PROBE_STORIES", &mut health.probe_stories, errors);
+        env_override("HEALTH_CHECK_TIMEOUT_MS", &mut health.check_timeout_ms, errors);
+
+        let jobs = &mut self.jobs;
+        env_override("OUTBOX_POLL_INTERVAL_SECS", &mut jobs.outbox_relay.poll_interval_secs, errors);
+        env_override("OUTBOX_BATCH_SIZE", &mut jobs.outbox_relay.batch_size, errors);
+        env_override("OUTBOX_RETENTION_DAYS", &mut jobs.outbox_relay.retention_days, errors);
+        env_override("WEBHOOK_POLL_INTERVAL_SECS", &mut jobs.webhook_dispatcher.poll_interval_secs, errors);
+        env_override("WEBHOOK_BATCH_SIZE", &mut jobs.webhook_dispatcher.batch_size, errors);
+        env_override("WEBHOOK_MAX_ATTEMPTS", &mut jobs.webhook_dispatcher.max_attempts, errors);
+        env_override("WEBHOOK_DISABLE_AFTER_FAILURES", &mut jobs.webhook_dispatcher.disable_after_failures, errors);
+        env_override("WEBHOOK_TIMEOUT_SECS", &mut jobs.webhook_dispatcher.request_timeout_secs, errors);
+        env_override("AUDIT_RETENTION_DAYS", &mut jobs.audit_retention.retention_days, errors);
+        env_override("AUDIT_PURGE_INTERVAL_SECS", &mut jobs.audit_retention.purge_interval_secs, errors);
+        env_override("SOFT_DELETE_RETENTION_DAYS", &mut jobs.soft_delete_purge.retention_days, errors);
+        env_override("SOFT_DELETE_PURGE_INTERVAL_SECS", &mut jobs.soft_delete_purge.purge_interval_secs, errors);
+        env_override("SUSPENSION_EXPIRY_INTERVAL_SECS", &mut jobs.suspension_expiry.interval_secs, errors);
+        env_override("SUSPENSION_EXPIRY_BATCH_SIZE", &mut jobs.suspension_expiry.batch_size, errors);
+        env_override("PROMOTION_SCHEDULE_HORIZON_DAYS", &mut jobs.promotion_scheduler.horizon_days, errors);
+        env_override("PROMOTION_SCHEDULER_INTERVAL_SECS", &mut jobs.promotion_scheduler.interval_secs, errors);
+        env_override("METRICS_COLLECT_INTERVAL_SECS", &mut jobs.metrics_collector.interval_secs, errors);
+
+        let rate_limit = &mut self.rate_limit;
+        env_override("RATE_LIMIT_ENABLED", &mut rate_limit.enabled, errors);
+        env_override("RATE_LIMIT_STORE", &mut rate_limit.store, errors);
+        env_override("RATE_LIMIT_READ_BURST", &mut rate_limit.read.capacity, errors);
+        env_override("RATE_LIMIT_READ_PER_MINUTE", &mut rate_limit.read.per_minute, errors);
+        env_override("RATE_LIMIT_WRITE_BURST", &mut rate_limit.write.capacity, errors);
+        env_override("RATE_LIMIT_WRITE_PER_MINUTE", &mut rate_limit.write.per_minute, errors);
+        env_networks("RATE_LIMIT_ALLOWLIST", &mut rate_limit.allowlist, errors);
+        env_networks("RATE_LIMIT_TRUSTED_GATEWAYS", &mut rate_limit.trusted_gateways, errors);
+        env_override("RATE_LIMIT_TRUST_FORWARDED_FOR", &mut rate_limit.trust_forwarded_for, errors);
+        env_override("RATE_LIMIT_PRUNE_INTERVAL_SECS", &mut rate_limit.prune_interval_secs, errors);
+
+        env_override("PROMOTION_MODERATION_ENABLED", &mut self.moderation.enabled, errors);
+
+        let events = &mut self.events;
+        env_override("EVENT_BROKER", &mut events.broker, errors);
+        env_override("NATS_URL", &mut events.nats_url, errors);
+        env_override("KAFKA_BROKERS", &mut events.kafka_brokers, errors);
+        env_override("KAFKA_TOPIC", &mut events.kafka_topic, errors);
+
+        // A separate file replaces the whole section
+        if let Some(path) = env_value::<String>("CONTENT_POLICY_FILE", errors) {
+            match std::fs::read_to_string(&path) {
+                Ok(text) => match toml::from_str(&text) {
+                    Ok(policy) => self.content_policy = policy,
+                    Err(err) => errors.push(format!("invalid content policy in {path}: {err}")),
+                },
+                Err(err) => errors.push(format!("cannot read {path}: {err}")),
+            }
+        }
+    }
+
+    fn validate(&self, errors: &mut Vec<String>) {
+        let server = &self.server;
+        if server.host.trim().is_empty() {
+            errors.push("server.host must not be empty".to_string());
+        }
+        if server.port == 0 {
+            errors.push("server.port must be between 1 and 65535".to_string());
+        }
+        if server.workers == Some(0) {
+            errors.push("server.workers must be at least 1".to_string());
+        }
+        for (name, value) in [
+            ("server.json_limit_bytes", server.json_limit_bytes as u64),
+            ("server.payload_limit_bytes", server.payload_limit_bytes as u64),
+            ("server.client_request_timeout_secs", server.client_request_timeout_secs),
+            ("database.pool_max_size", self.database.pool_max_size as u64),
+            ("database.pool_timeout_secs", self.database.pool_timeout_secs),
+            ("clients.request_timeout_secs", self.clients.request_timeout_secs),
+            ("health.check_timeout_ms", self.health.check_timeout_ms),
+            ("rate_limit.read.capacity", u64::from(self.rate_limit.read.capacity)),
+            ("rate_limit.read.per_minute", u64::from(self.rate_limit.read.per_minute)),
+            ("rate_limit.write.capacity", u64::from(self.rate_limit.write.capacity)),
+            ("rate_limit.write.per_minute", u64::from(self.rate_limit.write.per_minute)),
+            ("rate_limit.prune_interval_secs", self.rate_limit.prune_interval_secs),
+        ] {
+            if value == 0 {
+                errors.push(format!("{name} must be positive"));
+            }
+        }
+        if let Some(tls) = &server.tls {
+            if let Err(err) = tls.server_config() {
+                errors.push(err);
+            }
+        }
+
+        self.cors.validate(errors);
+
+        if self.database.backend == StorageBackend::Postgres {
+            if self.database.url.trim().is_empty() {
+                errors.push("DATABASE_URL must be set in environment (or database.url in CONFIG_FILE)".to_string());
+            } else if self.database.url.parse::<sqlx::postgres::PgConnectOptions>().is_err() {
+                errors.push("DATABASE_URL is not a valid Postgres connection string".to_string());
+            }
+        }
+
+        let stories_url_valid = reqwest::Url::parse(&self.clients.stories_url)
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+        if !stories_url_valid {
+            errors.push(format!("STORIES_SERVICE_URL '{}' is not an http(s) URL", self.clients.stories_url));
+        }
+
+        self.jobs.validate(errors);
+
+        if self.rate_limit.store == RateLimitBackend::Postgres && self.database.backend != StorageBackend::Postgres {
+            errors.push("rate_limit.store 'postgres' needs the Postgres storage backend".to_string());
+        }
+
+        self.events.validate(errors);
+
+        if let Err(err) = ContentPolicy::from_config(self.content_policy.clone()) {
+            errors.push(format!("invalid content policy: {err}"));
+        }
+    }
+}
+
+/// Parsed value of `key`, `None` when unset; a value that does not parse is
+/// reported rather than ignored
+fn env_value<T: FromStr>(key: &str, errors: &mut Vec<String>) -> Option<T> {
+    let value = std::env::var(key).ok().filter(|value| !value.trim().is_empty())?;
+    match value.trim().parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            errors.push(format!("{key} has an invalid value '{value}'"));
+            None
+        }
+    }
+}
+
+fn env_override<T: FromStr>(key: &str, target: &mut T, errors: &mut Vec<String>) {
+    if let Some(value) = env_value(key, errors) {
+        *target = value;
+    }
+}
+
+/// Comma-separated IPs or CIDR networks; an empty variable leaves the file
+/// value alone
+fn env_networks(key: &str, target: &mut Vec<ipnet::IpNet>, errors: &mut Vec<String>) {
+    let Some(value) = env_value::<String>(key, errors) else {
+        return;
+    };
+    match rate_limit::parse_networks(key, &value) {
+        Ok(networks) if !networks.is_empty() => *target = networks,
+        Ok(_) => {}
+        Err(err) => errors.push(err),
+    }
+}
+
+/// Comma-separated list; an empty variable leaves the file value alone
+fn env_list(key: &str, target: &mut Vec<String>) {
+    if let Ok(value) = std::env::var(key) {
+        let items: Vec<String> = value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect();
+        if !items.is_empty() {
+            *target = items;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Validation errors of `text` read as a configuration file
+    fn errors(text: &str) -> Vec<String> {
+        let config: AppConfig = toml::from_str(text).expect("valid TOML");
+        let mut errors = Vec::new();
+        config.validate(&mut errors);
+        errors
+    }
+
+    #[test]
+    fn job_and_rate_limit_sections_are_read() {
+        let config: AppConfig = toml::from_str(
+            r#"
+            [jobs.soft_delete_purge]
+            retention_days = 0
+            purge_interval_secs = 60
+
+            [rate_limit]
+            store = "postgres"
+            allowlist = ["10.0.0.0/8", "127.0.0.1"]
+
+            [rate_limit.read]
+            capacity = 5
+            per_minute = 10
+
+            [moderation]
+            enabled = true
+
+            [events]
+            broker = "memory"
+
+            [content_policy.banned_words]
+            words = ["estafa"]
+            "#,
+        )
+        .expect("valid configuration");
+
+        assert_eq!(config.jobs.soft_delete_purge.retention_days, 0);
+        assert_eq!(config.jobs.soft_delete_purge.purge_interval(), Duration::from_secs(60));
+        assert_eq!(config.jobs.outbox_relay.batch_size, 100);
+        assert_eq!(config.rate_limit.store, RateLimitBackend::Postgres);
+        assert_eq!(config.rate_limit.allowlist.len(), 2);
+        assert_eq!(config.rate_limit.read.capacity, 5);
+        assert_eq!(config.rate_limit.write.capacity, 20);
+        assert!(config.moderation.enabled);
+        assert_eq!(config.events.broker, EventBroker::Memory);
+        assert_eq!(config.content_policy.banned_words.words, ["estafa"]);
+    }
+
+    #[test]
+    fn unknown_keys_and_bad_networks_are_rejected() {
+        assert!(toml::from_str::<AppConfig>("[jobs.outbox_relay]\npoll_interval = 2").is_err());
+        assert!(toml::from_str::<AppConfig>("[rate_limit]\ntrusted_gateways = [\"gateway\"]").is_err());
+        assert!(toml::from_str::<AppConfig>("[events]\nbroker = \"rabbitmq\"").is_err());
+    }
+
+    #[test]
+    fn zero_intervals_and_negative_sizes_are_rejected() {
+        let errors = errors(
+            r#"
+            [database]
+            backend = "memory"
+
+            [jobs.soft_delete_purge]
+            purge_interval_secs = 0
+
+            [jobs.suspension_expiry]
+            batch_size = -1
+
+            [jobs.audit_retention]
+            retention_days = -5
+
+            [rate_limit]
+            store = "postgres"
+            prune_interval_secs = 0
+
+            [rate_limit.write]
+            capacity = 0
+            per_minute = 60
+            "#,
+        );
+
+        assert_eq!(
+            errors,
+            [
+                "rate_limit.write.capacity must be positive",
+                "rate_limit.prune_interval_secs must be positive",
+                "jobs.soft_delete_purge.purge_interval_secs must be positive",
+                "jobs.suspension_expiry.batch_size must be positive",
+                "jobs.audit_retention.retention_days must not be negative",
+                "rate_limit.store 'postgres' needs the Postgres storage backend",
+            ]
+        );
+    }
+
+    #[test]
+    fn defaults_are_valid_on_the_memory_backend() {
+        assert!(errors("[database]\nbackend = \"memory\"").is_empty());
+    }
+}
@@ -0,0 +1,563 @@
+//! Automated content policy checks on promotion and registration text.
+//!
+//! A `ContentPolicy` runs a list of `ContentRule`s over the text fields of a
+//! promotion or registration before a reviewer sees it. Findings never reject
+//! anything by themselves; they are stored with the entity and shown in the
+//! review queues. The built-in rules are configured by the `content_policy`
+//! section of the service configuration, or a separate TOML file named by
+//! `CONTENT_POLICY_FILE`; without either they run with their defaults and an
+//! empty banned word list.
+
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+
+use crate::models::{
+    BusinessPromotion, ContentFinding, ContentFindings, FindingSeverity, NewBusinessPromotion, NewBusinessRegistration,
+};
+
+const EXCERPT_CHARS: usize = 80;
+
+/// Text and facts of one entity, as seen by the rules
+#[derive(Debug, Clone, Default)]
+pub struct ContentSubject<'a> {
+    /// `(field, text)` pairs; absent optional fields are left out
+    pub fields: Vec<(&'static str, &'a str)>,
+    pub discount_percent: Option<i32>,
+    pub requires_purchase: bool,
+}
+
+impl<'a> ContentSubject<'a> {
+    fn promotion(
+        title: &'a str,
+        subtitle: Option<&'a str>,
+        description: Option<&'a str>,
+        terms: Option<&'a str>,
+        prize: Option<&'a str>,
+        discount_percent: Option<i32>,
+        requires_purchase: bool,
+    ) -> Self {
+        let optional = [("subtitle", subtitle), ("description", description), ("terms", terms), ("prize", prize)];
+        Self {
+            fields: std::iter::once(("title", title))
+                .chain(optional.into_iter().filter_map(|(field, text)| text.map(|text| (field, text))))
+                .collect(),
+            discount_percent,
+            requires_purchase,
+        }
+    }
+}
+
+impl<'a> From<&'a NewBusinessPromotion> for ContentSubject<'a> {
+    fn from(promotion: &'a NewBusinessPromotion) -> Self {
+        Self::promotion(
+            &promotion.title,
+            promotion.subtitle.as_deref(),
+            promotion.description.as_deref(),
+            promotion.terms.as_deref(),
+            promotion.prize.as_deref(),
+            promotion.discount_percent,
+            promotion.requires_purchase,
+        )
+    }
+}
+
+impl<'a> From<&'a BusinessPromotion> for ContentSubject<'a> {
+    fn from(promotion: &'a BusinessPromotion) -> Self {
+        Self::promotion(
+            &promotion.title,
+            promotion.subtitle.as_deref(),
+            promotion.description.as_deref(),
+            promotion.terms.as_deref(),
+            promotion.prize.as_deref(),
+            promotion.discount_percent,
+            promotion.requires_purchase,
+        )
+    }
+}
+
+impl<'a> From<&'a NewBusinessRegistration> for ContentSubject<'a> {
+    fn from(registration: &'a NewBusinessRegistration) -> Self {
+        let mut fields = vec![("name", registration.name.as_str())];
+        if let Some(description) = registration.description.as_deref() {
+            fields.push(("description", description));
+        }
+        Self {
+            fields,
+            ..Default::default()
+        }
+    }
+}
+
+/// One check of the policy. Implementations push a finding per problem.
+pub trait ContentRule: Send + Sync {
+    fn check(&self, subject: &ContentSubject<'_>, findings: &mut Vec<ContentFinding>);
+}
+
+pub struct ContentPolicy {
+    rules: Vec<Box<dyn ContentRule>>,
+}
+
+impl ContentPolicy {
+    pub fn new(rules: Vec<Box<dyn ContentRule>>) -> Self {
+        Self { rules }
+    }
+
+    /// Built-in rules as configured; disabled rules are left out
+    pub fn from_config(config: ContentPolicyConfig) -> Result<Self, String> {
+        let mut rules: Vec<Box<dyn ContentRule>> = Vec::new();
+        if config.banned_words.enabled && config.banned_words.words.iter().any(|word| !word.trim().is_empty()) {
+            rules.push(Box::new(BannedWordsRule::new(config.banned_words)?));
+        }
+        if config.contact_info.enabled {
+            rules.push(Box::new(ContactInfoRule::new(config.contact_info)));
+        }
+        if config.shouting.enabled {
+            rules.push(Box::new(ShoutingRule(config.shouting)));
+        }
+        if config.suspicious_discount.enabled {
+            rules.push(Box::new(SuspiciousDiscountRule::new(config.suspicious_discount)));
+        }
+        Ok(Self::new(rules))
+    }
+
+    pub fn check<'a>(&self, subject: impl Into<ContentSubject<'a>>) -> ContentFindings {
+        let subject = subject.into();
+        let mut findings = Vec::new();
+        for rule in &self.rules {
+            rule.check(&subject, &mut findings);
+        }
+        ContentFindings(findings)
+    }
+}
+
+// ============================================================================
+// CONFIGURATION
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContentPolicyConfig {
+    pub banned_words: BannedWordsConfig,
+    pub contact_info: ContactInfoConfig,
+    pub shouting: ShoutingConfig,
+    pub suspicious_discount: SuspiciousDiscountConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BannedWordsConfig {
+    pub enabled: bool,
+    pub severity: FindingSeverity,
+    /// Matched as whole words, ignoring case
+    pub words: Vec<String>,
+    /// Fields to scan; every field when empty
+    pub fields: Vec<String>,
+}
+
+impl Default for BannedWordsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            severity: FindingSeverity::High,
+            words: Vec::new(),
+            fields: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContactInfoConfig {
+    pub enabled: bool,
+    pub severity: FindingSeverity,
+    /// Fields where phone numbers are not allowed
+    pub phone_fields: Vec<String>,
+    /// Fields where URLs are not allowed
+    pub url_fields: Vec<String>,
+}
+
+impl Default for ContactInfoConfig {
+    fn default() -> Self {
+        let fields: Vec<String> = ["title", "subtitle", "description", "prize", "name"].map(String::from).into();
+        Self {
+            enabled: true,
+            severity: FindingSeverity::Medium,
+            phone_fields: fields.clone(),
+            url_fields: fields,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShoutingConfig {
+    pub enabled: bool,
+    pub severity: FindingSeverity,
+    /// Share of upper-case letters above which a field is shouting
+    pub max_caps_ratio: f64,
+    /// Shorter fields are not checked for caps
+    pub min_letters: usize,
+    pub max_emoji: usize,
+}
+
+impl Default for ShoutingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            severity: FindingSeverity::Low,
+            max_caps_ratio: 0.7,
+            min_letters: 12,
+            max_emoji: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SuspiciousDiscountConfig {
+    pub enabled: bool,
+    pub severity: FindingSeverity,
+    /// A discount this large combined with `requires_purchase` is flagged
+    pub max_discount_with_purchase: i32,
+    /// Percentages in the text at least this large must not exceed the
+    /// promotion's `discount_percent`
+    pub min_claimed_percent: i32,
+}
+
+impl Default for SuspiciousDiscountConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            severity: FindingSeverity::Medium,
+            max_discount_with_purchase: 100,
+            min_claimed_percent: 50,
+        }
+    }
+}
+
+// ============================================================================
+// BUILT-IN RULES
+// ============================================================================
+
+struct BannedWordsRule {
+    severity: FindingSeverity,
+    pattern: Regex,
+    fields: Vec<String>,
+}
+
+impl BannedWordsRule {
+    fn new(config: BannedWordsConfig) -> Result<Self, String> {
+        // An empty alternative would match every text
+        let words: Vec<String> = config
+            .words
+            .iter()
+            .map(|word| word.trim())
+            .filter(|word| !word.is_empty())
+            .map(regex::escape)
+            .collect();
+        let pattern = RegexBuilder::new(&format!(r"\b(?:{})\b", words.join("|")))
+            .case_insensitive(true)
+            .build()
+            .map_err(|err| format!("invalid banned word list: {err}"))?;
+        Ok(Self {
+            severity: config.severity,
+            pattern,
+            fields: config.fields,
+        })
+    }
+}
+
+impl ContentRule for BannedWordsRule {
+    fn check(&self, subject: &ContentSubject<'_>, findings: &mut Vec<ContentFinding>) {
+        for (field, text) in &subject.fields {
+            if !self.fields.is_empty() && !self.fields.iter().any(|f| f == field) {
+                continue;
+            }
+            for found in self.pattern.find_iter(text) {
+                findings.push(finding("banned_word", field, self.severity, Some(found.as_str())));
+            }
+        }
+    }
+}
+
+struct ContactInfoRule {
+    config: ContactInfoConfig,
+    phone: Regex,
+    date: Regex,
+    url: Regex,
+}
+
+impl ContactInfoRule {
+    fn new(config: ContactInfoConfig) -> Self {
+        Self {
+            config,
+            phone: Regex::new(r"\+?\d[\d\s().-]{5,}\d").expect("phone pattern"),
+            date: Regex::new(r"^\d{1,4}[-/.]\d{1,2}[-/.]\d{1,4}$").expect("date pattern"),
+            url: Regex::new(
+                r"(?i)\b(?:https?://|www\.)\S+|\b[a-z0-9][a-z0-9-]*\.(?:com|net|org|info|biz|io|co|app|shop|store|online|site|xyz|me|ly)\b(?:/\S*)?",
+            )
+            .expect("url pattern"),
+        }
+    }
+}
+
+impl ContentRule for ContactInfoRule {
+    fn check(&self, subject: &ContentSubject<'_>, findings: &mut Vec<ContentFinding>) {
+        for (field, text) in &subject.fields {
+            if self.config.phone_fields.iter().any(|f| f == field) {
+                let phones = self.phone.find_iter(text).map(|found| found.as_str().trim()).filter(|candidate| {
+                    candidate.chars().filter(char::is_ascii_digit).count() >= 7 && !self.date.is_match(candidate)
+                });
+                for phone in phones {
+                    findings.push(finding("phone_number", field, self.config.severity, Some(phone)));
+                }
+            }
+            if self.config.url_fields.iter().any(|f| f == field) {
+                for found in self.url.find_iter(text) {
+                    let url = found.as_str().trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+                    findings.push(finding("url", field, self.config.severity, Some(url)));
+                }
+            }
+        }
+    }
+}
+
+struct ShoutingRule(ShoutingConfig);
+
+impl ContentRule for ShoutingRule {
+    fn check(&self, subject: &ContentSubject<'_>, findings: &mut Vec<ContentFinding>) {
+        let config = &self.0;
+        for (field, text) in &subject.fields {
+            let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+            let upper = text.chars().filter(|c| c.is_uppercase()).count();
+            if letters >= config.min_letters && upper as f64 / letters as f64 > config.max_caps_ratio {
+                findings.push(finding("excessive_caps", field, config.severity, Some(text)));
+            }
+            if text.chars().filter(|c| is_emoji(*c)).count() > config.max_emoji {
+                findings.push(finding("excessive_emoji", field, config.severity, Some(text)));
+            }
+        }
+    }
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF)
+}
+
+struct SuspiciousDiscountRule {
+    config: SuspiciousDiscountConfig,
+    percent: Regex,
+}
+
+impl SuspiciousDiscountRule {
+    fn new(config: SuspiciousDiscountConfig) -> Self {
+        Self {
+            config,
+            percent: Regex::new(r"(\d{1,3})\s*%").expect("percent pattern"),
+        }
+    }
+}
+
+impl ContentRule for SuspiciousDiscountRule {
+    fn check(&self, subject: &ContentSubject<'_>, findings: &mut Vec<ContentFinding>) {
+        let config = &self.config;
+        if let Some(discount) = subject.discount_percent {
+            if subject.requires_purchase && discount >= config.max_discount_with_purchase {
+                findings.push(finding("suspicious_discount", "discount_percent", config.severity, Some(&format!("{discount}%"))));
+            }
+        }
+
+        let offered = subject.discount_percent.unwrap_or(0);
+        for (field, text) in &subject.fields {
+            for captures in self.percent.captures_iter(text) {
+                let claimed: i32 = captures[1].parse().unwrap_or(0);
+                if claimed >= config.min_claimed_percent && claimed > offered {
+                    findings.push(finding("suspicious_discount", field, config.severity, Some(&captures[0])));
+                }
+            }
+        }
+    }
+}
+
+fn finding(rule: &str, field: &str, severity: FindingSeverity, excerpt: Option<&str>) -> ContentFinding {
+    ContentFinding {
+        rule: rule.to_string(),
+        field: field.to_string(),
+        severity,
+        excerpt: excerpt.map(|text| text.chars().take(EXCERPT_CHARS).collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subject(text: &str) -> ContentSubject<'_> {
+        ContentSubject {
+            fields: vec![("description", text)],
+            ..Default::default()
+        }
+    }
+
+    /// `(rule, excerpt)` of each finding of `rule` on `subject`
+    fn hits(rule: &dyn ContentRule, subject: &ContentSubject<'_>) -> Vec<(String, String)> {
+        let mut findings = Vec::new();
+        rule.check(subject, &mut findings);
+        findings.into_iter().map(|f| (f.rule, f.excerpt.unwrap_or_default())).collect()
+    }
+
+    fn excerpts(rule: &dyn ContentRule, text: &str) -> Vec<String> {
+        hits(rule, &subject(text)).into_iter().map(|(_, excerpt)| excerpt).collect()
+    }
+
+    fn contact_info() -> ContactInfoRule {
+        ContactInfoRule::new(ContactInfoConfig::default())
+    }
+
+    fn shouting() -> ShoutingRule {
+        ShoutingRule(ShoutingConfig::default())
+    }
+
+    fn discount() -> SuspiciousDiscountRule {
+        SuspiciousDiscountRule::new(SuspiciousDiscountConfig::default())
+    }
+
+    #[test]
+    fn phone_numbers_are_found() {
+        let rule = contact_info();
+        assert_eq!(excerpts(&rule, "Book at +34 612 345 678 today"), ["+34 612 345 678"]);
+        assert_eq!(excerpts(&rule, "Call (555) 123-4567.").len(), 1);
+        assert_eq!(excerpts(&rule, "WhatsApp 612.345.678"), ["612.345.678"]);
+    }
+
+    #[test]
+    fn dates_prices_and_short_numbers_are_not_phone_numbers() {
+        let rule = contact_info();
+        for text in [
+            "Valid until 2026-12-31",
+            "From 31/12/2026 only",
+            "Menu for 12.50 euros",
+            "Tables 1 to 20, open 18:00",
+            "Only 999 units",
+        ] {
+            assert!(excerpts(&rule, text).is_empty(), "{text}");
+        }
+    }
+
+    #[test]
+    fn phone_numbers_only_count_in_configured_fields() {
+        let rule = contact_info();
+        let subject = ContentSubject {
+            fields: vec![("terms", "Questions: +34 612 345 678")],
+            ..Default::default()
+        };
+        assert!(hits(&rule, &subject).is_empty());
+    }
+
+    #[test]
+    fn urls_are_found_without_trailing_punctuation() {
+        let rule = contact_info();
+        assert_eq!(excerpts(&rule, "See https://deals.example/x?y=1."), ["https://deals.example/x?y=1"]);
+        assert_eq!(excerpts(&rule, "Order at www.cafe.es!"), ["www.cafe.es"]);
+        assert_eq!(excerpts(&rule, "More on cafedelpuerto.com/menu, now"), ["cafedelpuerto.com/menu"]);
+    }
+
+    #[test]
+    fn plain_words_with_dots_are_not_urls() {
+        let rule = contact_info();
+        for text in ["Coffee. Cake. Done.", "Open Mon.-Fri.", "Version 2.0 of our menu"] {
+            assert!(excerpts(&rule, text).is_empty(), "{text}");
+        }
+    }
+
+    #[test]
+    fn mostly_upper_case_text_is_shouting() {
+        let rule = shouting();
+        assert_eq!(hits(&rule, &subject("BEST COFFEE IN TOWN TODAY"))[0].0, "excessive_caps");
+        assert!(hits(&rule, &subject("Best Coffee In Town Today")).is_empty());
+        // Too short to judge
+        assert!(hits(&rule, &subject("NEW MENU")).is_empty());
+        // Exactly at the ratio is allowed: 7 of 10 letters
+        assert!(hits(&rule, &subject("ABCDEFGhij")).is_empty());
+        assert!(hits(&rule, &subject("ABCDEFGHIJKLmnop")).iter().any(|(rule, _)| rule == "excessive_caps"));
+    }
+
+    #[test]
+    fn emoji_are_counted_across_ranges() {
+        let rule = shouting();
+        // Pictographs, miscellaneous symbols, dingbats and arrows
+        assert_eq!(hits(&rule, &subject("Deal 🎉☕✨⭐")), [("excessive_emoji".to_string(), "Deal 🎉☕✨⭐".to_string())]);
+        assert!(hits(&rule, &subject("Deal 🎉☕✨")).is_empty());
+        // Accented letters and currency signs are not emoji
+        assert!(hits(&rule, &subject("Menú ñandú €€€ £")).is_empty());
+    }
+
+    #[test]
+    fn claimed_percent_must_match_the_discount() {
+        let rule = discount();
+        let claim = |text, discount_percent| ContentSubject {
+            fields: vec![("title", text)],
+            discount_percent,
+            requires_purchase: false,
+        };
+
+        assert_eq!(hits(&rule, &claim("50% off everything", Some(20))), [("suspicious_discount".to_string(), "50%".to_string())]);
+        assert_eq!(hits(&rule, &claim("Up to 70 % off", None)).len(), 1);
+        assert!(hits(&rule, &claim("50% off everything", Some(50))).is_empty());
+        assert!(hits(&rule, &claim("50% off everything", Some(60))).is_empty());
+        // Small percentages are not claims worth checking
+        assert!(hits(&rule, &claim("10% off", None)).is_empty());
+    }
+
+    #[test]
+    fn dates_and_prices_are_not_claimed_percents() {
+        let rule = discount();
+        for text in ["Valid until 31/12/2026", "Menu for 50 euros", "Two for €99", "Open 2026-10-18"] {
+            assert!(hits(&rule, &subject(text)).is_empty(), "{text}");
+        }
+    }
+
+    #[test]
+    fn full_discounts_requiring_a_purchase_are_suspicious() {
+        let rule = discount();
+        let promotion = |requires_purchase| ContentSubject {
+            fields: vec![("title", "Free coffee")],
+            discount_percent: Some(100),
+            requires_purchase,
+        };
+        assert_eq!(hits(&rule, &promotion(true)), [("suspicious_discount".to_string(), "100%".to_string())]);
+        assert!(hits(&rule, &promotion(false)).is_empty());
+    }
+
+    #[test]
+    fn banned_words_match_whole_words_ignoring_case() {
+        let rule = BannedWordsRule::new(BannedWordsConfig {
+            words: vec!["casino".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(excerpts(&rule, "Win at the CASINO tonight"), ["CASINO"]);
+        assert!(excerpts(&rule, "Casinos nearby").is_empty());
+    }
+
+    #[test]
+    fn blank_banned_words_are_ignored() {
+        let rule = BannedWordsRule::new(BannedWordsConfig {
+            words: vec!["casino".to_string(), String::new(), "  ".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(excerpts(&rule, "Fresh bread every morning").is_empty());
+        assert_eq!(excerpts(&rule, "Casino night"), ["Casino"]);
+
+        let config = ContentPolicyConfig {
+            banned_words: BannedWordsConfig {
+                words: vec![String::new()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let policy = ContentPolicy::from_config(config).unwrap();
+        assert!(policy.check(subject("Fresh bread every morning")).0.is_empty());
+    }
+}
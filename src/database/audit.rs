@@ -0,0 +1,78 @@
+use serde::Serialize;
+use sqlx::PgConnection;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{DatabaseService, Error};
+use crate::audit::{json_diff, AuditAction, AuditContext, AuditEntity};
+use crate::models::{AuditLog, AuditLogFilter};
+
+/// Append an audit entry as part of the caller's transaction.
+pub(super) async fn record<T: Serialize>(
+    conn: &mut PgConnection,
+    ctx: &AuditContext,
+    entity: AuditEntity,
+    entity_id: Uuid,
+    action: AuditAction,
+    before: Option<&T>,
+    after: Option<&T>,
+) -> Result<(), Error> {
+    let before = before.map(serde_json::to_value).transpose()?;
+    let after = after.map(serde_json::to_value).transpose()?;
+    let diff = json_diff(before.as_ref(), after.as_ref());
+
+    sqlx::query!(
+        "INSERT INTO audit_logs (id, actor_id, actor_name, entity_type, entity_id, action, before, after, diff, request_id, ip_address) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+        Uuid::new_v4(), ctx.actor_id, ctx.actor_name, entity.as_str(), entity_id, action.as_str(), before, after, diff, ctx.request_id, ctx.ip_address,
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+impl DatabaseService {
+    #[instrument(skip_all)]
+    pub async fn list_audit_logs(&self, filter: &AuditLogFilter) -> Result<Vec<AuditLog>, Error> {
+        let limit = filter.limit.unwrap_or(50).clamp(1, 500);
+        let offset = filter.offset.unwrap_or(0).max(0);
+
+        Ok(sqlx::query_as!(
+            AuditLog,
+            r#"
+            SELECT id, actor_id, actor_name, entity_type, entity_id, action, before, after, diff, request_id, ip_address, created_at
+            FROM audit_logs
+            WHERE ($1::text IS NULL OR entity_type = $1)
+              AND ($2::uuid IS NULL OR entity_id = $2)
+              AND ($3::uuid IS NULL OR actor_id = $3)
+              AND ($4::text IS NULL OR action = $4)
+              AND ($5::timestamptz IS NULL OR created_at >= $5)
+              AND ($6::timestamptz IS NULL OR created_at < $6)
+            ORDER BY created_at DESC
+            LIMIT $7 OFFSET $8
+            "#,
+            filter.entity_type, filter.entity_id, filter.actor_id, filter.action, filter.from, filter.to, limit, offset,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Delete audit entries older than `retention_days`.
+    #[instrument(skip_all)]
+    pub async fn purge_audit_logs(&self, retention_days: i32) -> Result<u64, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // Lift the append-only trigger for this transaction only
+        sqlx::query!("SET LOCAL app.audit_purge = 'on'").execute(&mut *tx).await?;
+        let deleted = sqlx::query!(
+            "DELETE FROM audit_logs WHERE created_at < NOW() - make_interval(days => $1)",
+            retention_days,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,133 @@
+//! Column lists of the entities loaded by more than one query.
+//!
+//! Each macro expands to `sqlx::query_as!` over `$head <columns> $tail`, so a
+//! list and its type overrides are written once while every query using it is
+//! still checked against the schema at compile time. Queries read as
+//! `query_business!("SELECT", "FROM businesses WHERE id = $1", id)`; an empty
+//! tail ends a statement with `RETURNING` columns, and a head may itself be
+//! several literals joined with `+`.
+
+macro_rules! query_business {
+    ($head:literal $(+ $more:literal)*, $tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            crate::models::Business,
+            $head $(+ $more)* +
+                " id, registration_id, owner_user_id, business_name, category, tax_id, description, website, \
+                   logo_url, is_active, promotions_trusted, metadata, created_at, updated_at, deleted_at, deleted_by, \
+                   version "
+                + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+macro_rules! query_location {
+    ($head:literal $(+ $more:literal)*, $tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            crate::models::BusinessLocation,
+            $head $(+ $more)* +
+                " id, business_id, location_name, formatted_address, street, city, state_region, postal_code, \
+                   country, latitude, longitude, google_place_id, timezone, phone, email, is_active, is_primary, \
+                   operating_hours, notes, metadata, created_at, updated_at, deleted_at, deleted_by, version "
+                + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+macro_rules! query_promotion {
+    ($head:literal $(+ $more:literal)*, $tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            crate::models::BusinessPromotion,
+            $head $(+ $more)* +
+                " id, business_id, location_id, \
+                   target_scope AS \"target_scope: crate::models::PromotionTargetScope\", \
+                   limit_scope AS \"limit_scope: crate::models::PromotionLimitScope\", \
+                   title, subtitle, description, \
+                   promotion_type AS \"promotion_type: crate::models::BusinessPromotionType\", \
+                   status AS \"status: crate::models::BusinessPromotionStatus\", \
+                   image_url, prize, reward_points, discount_percent, max_claims, per_user_limit, total_claims, \
+                   requires_check_in, requires_purchase, terms, metadata, \
+                   content_findings AS \"content_findings: crate::models::ContentFindings\", \
+                   starts_at, ends_at, published_at, created_by, updated_by, created_at, updated_at, deleted_at, \
+                   deleted_by, version "
+                + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+/// `INSERT` of a `NewBusinessPromotion` returning the promotion, with an
+/// optional `ON CONFLICT` clause spliced in before `RETURNING`
+macro_rules! insert_promotion {
+    ($promotion:expr $(, $on_conflict:literal)?) => {{
+        let promotion: &crate::models::NewBusinessPromotion = $promotion;
+        query_promotion!(
+            "INSERT INTO business_promotions (id, business_id, location_id, target_scope, limit_scope, title, subtitle, description, promotion_type, status, image_url, prize, reward_points, discount_percent, max_claims, per_user_limit, total_claims, requires_check_in, requires_purchase, terms, metadata, content_findings, starts_at, ends_at, published_at, created_by, updated_by, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29) "
+                $(+ $on_conflict)? + " RETURNING",
+            "",
+            promotion.id, promotion.business_id, promotion.location_id, promotion.target_scope as _, promotion.limit_scope as _, promotion.title, promotion.subtitle, promotion.description, promotion.promotion_type as _, promotion.status as _, promotion.image_url, promotion.prize, promotion.reward_points, promotion.discount_percent, promotion.max_claims, promotion.per_user_limit, promotion.total_claims, promotion.requires_check_in, promotion.requires_purchase, promotion.terms, promotion.metadata, promotion.content_findings as _, promotion.starts_at, promotion.ends_at, promotion.published_at, promotion.created_by, promotion.updated_by, promotion.created_at, promotion.updated_at,
+        )
+    }};
+}
+
+macro_rules! query_registration {
+    ($head:literal $(+ $more:literal)*, $tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            crate::models::BusinessRegistration,
+            $head $(+ $more)* +
+                " id, user_id, business_id, name, category, address, description, phone, website, tax_id, \
+                   document_urls AS \"document_urls: sqlx::types::Json<Vec<String>>\", is_multi_user_team, \
+                   status AS \"status: crate::models::BusinessVerificationStatus\", \
+                   owner_email, owner_username, rejection_reason, reviewer_notes, reviewer_id, reviewer_name, \
+                   content_findings AS \"content_findings: crate::models::ContentFindings\", \
+                   submitted_at, updated_at "
+                + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+macro_rules! query_location_admin {
+    ($head:literal $(+ $more:literal)*, $tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            crate::models::LocationAdmin,
+            $head $(+ $more)* +
+                " id, location_id, user_id, user_email, user_username, \
+                   role AS \"role: crate::models::LocationAdminRole\", \
+                   granted_by, granted_by_username, is_active, granted_at, created_at, updated_at "
+                + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+macro_rules! query_promotion_template {
+    ($head:literal $(+ $more:literal)*, $tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            crate::models::PromotionTemplate,
+            $head $(+ $more)* +
+                " id, location_id, title, subtitle, description, \
+                   promotion_type AS \"promotion_type: crate::models::BusinessPromotionType\", \
+                   image_url, prize, reward_points, discount_percent, max_claims, per_user_limit, \
+                   requires_check_in, requires_purchase, terms, metadata, rrule, dtstart, duration_minutes, \
+                   timezone, is_active, materialized_until, created_by, created_at, updated_at "
+                + $tail
+            $(, $arg)*
+        )
+    };
+}
+
+macro_rules! query_webhook_subscription {
+    ($head:literal $(+ $more:literal)*, $tail:literal $(, $arg:expr)* $(,)?) => {
+        sqlx::query_as!(
+            crate::models::WebhookSubscription,
+            $head $(+ $more)* +
+                " id, url, secret, event_types AS \"event_types: Vec<crate::models::WebhookEventType>\", \
+                   description, is_active, consecutive_failures, disabled_at, disabled_reason, created_by, \
+                   created_at, updated_at "
+                + $tail
+            $(, $arg)*
+        )
+    };
+}
@@ -0,0 +1,149 @@
+use futures_util::stream::{self, BoxStream, Stream, StreamExt};
+use tokio::sync::mpsc;
+use tracing::instrument;
+
+use super::{DatabaseService, DbPool, Error};
+use crate::models::{
+    ActivityStatus, BusinessExportRow, BusinessPromotionStatus, BusinessPromotionType, ExportFilter,
+    LocationExportRow, PromotionExportRow, PromotionTargetScope,
+};
+use crate::shutdown::Shutdown;
+
+/// Rows read ahead of a slow client before the query waits for it
+const EXPORT_BUFFER_ROWS: usize = 256;
+
+impl DatabaseService {
+    /// Live businesses matching `filter`, streamed in creation order
+    #[instrument(skip_all)]
+    pub async fn export_businesses(
+        &self,
+        filter: &ExportFilter<ActivityStatus>,
+        shutdown: &Shutdown,
+    ) -> Result<impl Stream<Item = Result<BusinessExportRow, Error>> + 'static, Error> {
+        let is_active = filter.status.map(ActivityStatus::is_active);
+        let query = sqlx::query_as!(
+            BusinessExportRow,
+            "SELECT b.id, b.business_name, b.category, b.owner_user_id, b.tax_id, b.website, b.is_active, \
+                (SELECT COUNT(*) FROM business_locations l WHERE l.business_id = b.id AND l.deleted_at IS NULL) AS \"location_count!\", \
+                (SELECT COUNT(*) FROM business_promotions p LEFT JOIN business_locations l ON p.location_id = l.id WHERE p.business_id = b.id AND p.status = 'active' AND p.deleted_at IS NULL AND l.deleted_at IS NULL) AS \"active_promotion_count!\", \
+                b.created_at, b.updated_at \
+             FROM businesses b \
+             WHERE b.deleted_at IS NULL \
+                AND ($1::bool IS NULL OR b.is_active = $1) \
+                AND ($2::text IS NULL OR LOWER(b.category) = LOWER($2)) \
+                AND ($3::text IS NULL OR EXISTS (SELECT 1 FROM business_locations l WHERE l.business_id = b.id AND l.deleted_at IS NULL AND LOWER(l.country) = LOWER($3))) \
+             ORDER BY b.created_at, b.id",
+            is_active, filter.category, filter.country,
+        );
+        stream_rows(self.pool.clone(), shutdown, move |pool| query.fetch(pool)).await
+    }
+
+    /// Live promotions of live businesses matching `filter`. Location columns
+    /// are only filled for single-location promotions; `country` matches any
+    /// location a promotion applies to.
+    #[instrument(skip_all)]
+    pub async fn export_promotions(
+        &self,
+        filter: &ExportFilter<BusinessPromotionStatus>,
+        shutdown: &Shutdown,
+    ) -> Result<impl Stream<Item = Result<PromotionExportRow, Error>> + 'static, Error> {
+        let query = sqlx::query_as!(
+            PromotionExportRow,
+            "SELECT p.id, p.title, p.promotion_type AS \"promotion_type: BusinessPromotionType\", p.status AS \"status: BusinessPromotionStatus\", \
+                p.discount_percent, p.reward_points, p.max_claims, p.total_claims, p.starts_at, p.ends_at, p.published_at, \
+                p.target_scope AS \"target_scope: PromotionTargetScope\", p.location_id, l.location_name AS \"location_name?\", \
+                l.city, l.country, b.id AS business_id, b.business_name, b.category, p.created_at \
+             FROM business_promotions p \
+             INNER JOIN businesses b ON p.business_id = b.id \
+             LEFT JOIN business_locations l ON p.location_id = l.id \
+             WHERE p.deleted_at IS NULL AND l.deleted_at IS NULL AND b.deleted_at IS NULL \
+                AND ($1::business_promotion_status IS NULL OR p.status = $1) \
+                AND ($2::text IS NULL OR LOWER(b.category) = LOWER($2)) \
+                AND ($3::text IS NULL OR EXISTS ( \
+                    SELECT 1 FROM business_locations tl \
+                    WHERE tl.business_id = p.business_id AND tl.deleted_at IS NULL AND LOWER(tl.country) = LOWER($3) \
+                        AND (p.target_scope = 'business' OR EXISTS (SELECT 1 FROM business_promotion_locations t WHERE t.promotion_id = p.id AND t.location_id = tl.id)) \
+                )) \
+             ORDER BY p.starts_at, p.id",
+            filter.status as _, filter.category, filter.country,
+        );
+        stream_rows(self.pool.clone(), shutdown, move |pool| query.fetch(pool)).await
+    }
+
+    /// Live locations of live businesses matching `filter`, with counts of the
+    /// promotions that apply to them
+    #[instrument(skip_all)]
+    pub async fn export_locations(
+        &self,
+        filter: &ExportFilter<ActivityStatus>,
+        shutdown: &Shutdown,
+    ) -> Result<impl Stream<Item = Result<LocationExportRow, Error>> + 'static, Error> {
+        let is_active = filter.status.map(ActivityStatus::is_active);
+        let query = sqlx::query_as!(
+            LocationExportRow,
+            "SELECT l.id, l.business_id, b.business_name, b.category, l.location_name, l.formatted_address, l.city, l.country, \
+                l.latitude, l.longitude, l.is_active, l.is_primary, counts.promotion_count AS \"promotion_count!\", \
+                counts.active_promotion_count AS \"active_promotion_count!\" \
+             FROM business_locations l \
+             INNER JOIN businesses b ON l.business_id = b.id \
+             CROSS JOIN LATERAL ( \
+                SELECT COUNT(*) AS promotion_count, COUNT(*) FILTER (WHERE p.status = 'active') AS active_promotion_count \
+                FROM business_promotions p \
+                WHERE p.business_id = l.business_id AND p.deleted_at IS NULL \
+                    AND (p.target_scope = 'business' OR EXISTS (SELECT 1 FROM business_promotion_locations t WHERE t.promotion_id = p.id AND t.location_id = l.id)) \
+             ) counts \
+             WHERE l.deleted_at IS NULL AND b.deleted_at IS NULL \
+                AND ($1::bool IS NULL OR l.is_active = $1) \
+                AND ($2::text IS NULL OR LOWER(b.category) = LOWER($2)) \
+                AND ($3::text IS NULL OR LOWER(l.country) = LOWER($3)) \
+             ORDER BY l.created_at, l.id",
+            is_active, filter.category, filter.country,
+        );
+        stream_rows(self.pool.clone(), shutdown, move |pool| query.fetch(pool)).await
+    }
+}
+
+/// Stream the rows of `fetch` as Postgres sends them. A tracked task owning
+/// a clone of the pool drives the query into a bounded channel, so rows are
+/// never collected in memory and the connection goes back to the pool once
+/// the stream ends or is dropped. Shutdown ends the stream with an error
+/// rather than letting it pass for a complete export. An error before the
+/// first row is returned rather than streamed.
+async fn stream_rows<T, F>(
+    pool: DbPool,
+    shutdown: &Shutdown,
+    fetch: F,
+) -> Result<impl Stream<Item = Result<T, Error>> + 'static, Error>
+where
+    T: Send + 'static,
+    F: for<'p> FnOnce(&'p DbPool) -> BoxStream<'p, Result<T, sqlx::Error>> + Send + 'static,
+{
+    let (sender, mut receiver) = mpsc::channel(EXPORT_BUFFER_ROWS);
+    let stop = shutdown.clone();
+    shutdown.spawn("export_rows", async move {
+        let forward = async {
+            let mut rows = fetch(&pool);
+            while let Some(row) = rows.next().await {
+                if sender.send(row.map_err(Error::from)).await.is_err() {
+                    break;
+                }
+            }
+        };
+        tokio::select! {
+            _ = forward => {}
+            // The client went away while the query was between rows
+            _ = sender.closed() => {}
+            _ = stop.cancelled() => {
+                let interrupted = Error::Internal("Export interrupted by shutdown".to_string());
+                let _ = sender.send(Err(interrupted)).await;
+            }
+        }
+    });
+
+    let first = receiver.recv().await.transpose()?;
+    let rest = stream::unfold(receiver, |mut receiver| async move {
+        let next = receiver.recv().await?;
+        Some((next, receiver))
+    });
+    Ok(stream::iter(first.map(Ok)).chain(rest))
+}
@@ -0,0 +1,38 @@
+use tracing::instrument;
+
+use super::{DatabaseService, Error};
+
+/// Connection counts of the pool at one instant
+#[derive(Debug, Clone, Copy)]
+pub struct PoolStatus {
+    pub max_size: usize,
+    /// Connections open, idle or in use
+    pub size: usize,
+    /// Idle connections ready to be checked out
+    pub available: usize,
+}
+
+impl DatabaseService {
+    /// Cheapest round-trip that proves a connection works
+    #[instrument(skip_all)]
+    pub async fn ping(&self) -> Result<(), Error> {
+        sqlx::query!("SELECT 1 AS one").fetch_one(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Migration files recorded as applied by `bin/migrate`
+    #[instrument(skip_all)]
+    pub async fn applied_migrations(&self) -> Result<Vec<String>, Error> {
+        Ok(sqlx::query_scalar!("SELECT version FROM schema_migrations ORDER BY version")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    pub fn pool_status(&self) -> PoolStatus {
+        PoolStatus {
+            max_size: self.pool.options().get_max_connections() as usize,
+            size: self.pool.size() as usize,
+            available: self.pool.num_idle(),
+        }
+    }
+}
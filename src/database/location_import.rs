@@ -0,0 +1,97 @@
+use serde_json::{json, Value};
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{audit, insert_location, outbox, DatabaseService, Error};
+use crate::audit::{AuditAction, AuditContext, AuditEntity};
+use crate::errors::AppError;
+use crate::events::DomainEventPayload;
+use crate::models::{LocationImportReport, LocationImportRow};
+
+impl DatabaseService {
+    /// Create or update a business's locations from already validated import
+    /// rows, all in one transaction. Rows whose `external_ref` matches a live
+    /// location (`metadata.external_ref`) update it; the rest are inserted.
+    /// With `dry_run` every write is rolled back, so the report shows what a
+    /// real import would do.
+    #[instrument(skip_all)]
+    pub async fn import_locations(
+        &self,
+        business_id: Uuid,
+        rows: Vec<LocationImportRow>,
+        dry_run: bool,
+        ctx: &AuditContext,
+    ) -> Result<LocationImportReport, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!("SELECT id FROM businesses WHERE id = $1 AND deleted_at IS NULL FOR SHARE", business_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Business"))?;
+
+        let total_rows = rows.len();
+        let (mut created, mut updated) = (0, 0);
+        let mut locations = Vec::with_capacity(total_rows);
+        let mut events = Vec::with_capacity(total_rows);
+
+        for LocationImportRow { external_ref, location: request } in rows {
+            let existing = match &external_ref {
+                Some(external_ref) => query_location!(
+                    "SELECT",
+                    "FROM business_locations WHERE business_id = $1 AND metadata->>'external_ref' = $2 AND deleted_at IS NULL FOR UPDATE",
+                    business_id, external_ref,
+                )
+                .fetch_optional(&mut *tx)
+                .await?,
+                None => None,
+            };
+
+            let location = match existing {
+                Some(before) => {
+                    let location = query_location!(
+                        "UPDATE business_locations SET location_name = $2, formatted_address = $3, street = $4, city = $5, state_region = $6, postal_code = $7, country = $8, latitude = $9, longitude = $10, google_place_id = $11, timezone = $12, phone = $13, email = $14, is_primary = $15, operating_hours = $16, notes = $17, version = version + 1, updated_at = NOW() WHERE id = $1 RETURNING",
+                        "",
+                        before.id, request.location_name, request.formatted_address, request.street, request.city, request.state_region, request.postal_code, request.country, request.latitude, request.longitude, request.google_place_id, request.timezone, request.phone, request.email, request.is_primary, request.operating_hours, request.notes,
+                    )
+                    .fetch_one(&mut *tx)
+                    .await?;
+                    audit::record(&mut tx, ctx, AuditEntity::Location, location.id, AuditAction::Update, Some(&before), Some(&location)).await?;
+                    events.push(DomainEventPayload::LocationUpdated(location.clone()));
+                    updated += 1;
+                    location
+                }
+                None => {
+                    let mut new_location = request.into_new_location(business_id);
+                    if let Some(external_ref) = external_ref {
+                        new_location.metadata = merge_external_ref(new_location.metadata, external_ref);
+                    }
+                    let location = insert_location(&mut tx, &new_location).await?;
+                    audit::record(&mut tx, ctx, AuditEntity::Location, location.id, AuditAction::Create, None, Some(&location)).await?;
+                    events.push(DomainEventPayload::LocationCreated(location.clone()));
+                    created += 1;
+                    location
+                }
+            };
+            locations.push(location);
+        }
+
+        outbox::enqueue_events(&mut tx, events).await?;
+        if dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+
+        Ok(LocationImportReport { dry_run, total_rows, created, updated, errors: Vec::new(), locations })
+    }
+}
+
+fn merge_external_ref(metadata: Value, external_ref: String) -> Value {
+    match metadata {
+        Value::Object(mut object) => {
+            object.insert("external_ref".to_string(), Value::String(external_ref));
+            Value::Object(object)
+        }
+        _ => json!({ "external_ref": external_ref }),
+    }
+}
@@ -0,0 +1,60 @@
+use tracing::instrument;
+
+use super::{DatabaseService, Error};
+
+/// Queue depths and counts exported as Prometheus gauges
+#[derive(Debug, Clone, Default)]
+pub struct DomainSnapshot {
+    pub pending_registrations: i64,
+    pub oldest_pending_registration_secs: f64,
+    pub pending_promotion_reviews: i64,
+    pub oldest_pending_promotion_review_secs: f64,
+    /// Every promotion type, with zero when none is active
+    pub active_promotions_by_type: Vec<(String, i64)>,
+    pub unpublished_events: i64,
+}
+
+impl DatabaseService {
+    #[instrument(skip_all)]
+    pub async fn domain_snapshot(&self) -> Result<DomainSnapshot, Error> {
+        let registrations = sqlx::query!(
+            r#"SELECT COUNT(*) AS "pending!",
+                    COALESCE(EXTRACT(EPOCH FROM NOW() - MIN(submitted_at)), 0)::float8 AS "oldest_secs!"
+             FROM business_registration_requests WHERE status = 'pending'"#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let promotion_reviews = sqlx::query!(
+            r#"SELECT COUNT(*) AS "pending!",
+                    COALESCE(EXTRACT(EPOCH FROM NOW() - MIN(p.updated_at)), 0)::float8 AS "oldest_secs!"
+             FROM business_promotions p INNER JOIN businesses b ON p.business_id = b.id
+             WHERE p.status = 'pending_review' AND p.deleted_at IS NULL AND b.deleted_at IS NULL"#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let active = sqlx::query!(
+            r#"SELECT t.promotion_type::text AS "promotion_type!", COUNT(p.id) AS "active!"
+             FROM unnest(enum_range(NULL::business_promotion_type)) AS t(promotion_type)
+             LEFT JOIN business_promotions p
+                 ON p.promotion_type = t.promotion_type AND p.status = 'active' AND p.deleted_at IS NULL
+             GROUP BY t.promotion_type ORDER BY t.promotion_type"#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let unpublished = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "unpublished!" FROM event_outbox WHERE published_at IS NULL"#)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(DomainSnapshot {
+            pending_registrations: registrations.pending,
+            oldest_pending_registration_secs: registrations.oldest_secs,
+            pending_promotion_reviews: promotion_reviews.pending,
+            oldest_pending_promotion_review_secs: promotion_reviews.oldest_secs,
+            active_promotions_by_type: active.into_iter().map(|row| (row.promotion_type, row.active)).collect(),
+            unpublished_events: unpublished,
+        })
+    }
+}
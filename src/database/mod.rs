@@ -1,47 +1,89 @@
-use chrono::{DateTime, Utc};
-use deadpool_postgres::{Config, ManagerConfig, Pool, RecyclingMethod, Runtime};
-use tokio_postgres::NoTls;
+#[macro_use]
+mod columns;
+
+mod audit;
+mod exports;
+mod health;
+mod location_import;
+mod metrics;
+mod outbox;
+mod promotion_claims;
+mod promotion_reviews;
+mod promotion_templates;
+mod rate_limits;
+mod soft_delete;
+mod suspensions;
+mod webhooks;
+
+pub use health::PoolStatus;
+pub use metrics::DomainSnapshot;
+pub use suspensions::SuspensionActor;
+
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+use serde_json::json;
+use sqlx::postgres::{PgConnection, PgPool, PgPoolOptions};
+use tracing::instrument;
 use uuid::Uuid;
 
+use crate::config::DatabaseConfig;
+use crate::audit::{AuditAction, AuditContext, AuditEntity};
+use crate::errors::{AppError, FieldError};
+use crate::events::{DomainEventPayload, EntityDeleted, LocationAdminRemoved};
 use crate::models::{
-    Business, BusinessLocation, BusinessPromotion, BusinessRegistration, BusinessReviewEvent,
+    Business, BusinessLocation, BusinessPromotion, BusinessPromotionStatus, BusinessRegistration,
     BusinessVerificationStatus, LocationAdmin, NewBusiness, NewBusinessLocation,
     NewBusinessPromotion, NewBusinessRegistration, NewLocationAdmin, PendingBusinessReview,
-    RegistrationWithHistory, ReviewAction, ReviewStats,
+    ReviewAction, ReviewStats,
 };
 
-type Error = Box<dyn std::error::Error + Send + Sync>;
+type Error = AppError;
 
-pub type DbPool = Pool;
+pub type DbPool = PgPool;
+
+/// How long `close` waits for checked-out connections to come back
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct DatabaseService {
     pool: DbPool,
 }
 
 impl DatabaseService {
-    pub async fn new(database_url: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let mut cfg = Config::new();
-        cfg.url = Some(database_url.to_string());
-        cfg.manager = Some(ManagerConfig {
-            recycling_method: RecyclingMethod::Fast,
-        });
-
-        let pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls)?;
-        let client = pool.get().await?;
-        client.execute("SELECT 1", &[]).await?;
+    #[instrument(skip_all)]
+    pub async fn new(config: &DatabaseConfig) -> Result<Self, Error> {
+        let pool = PgPoolOptions::new()
+            .max_connections(u32::try_from(config.pool_max_size).unwrap_or(u32::MAX))
+            .acquire_timeout(config.pool_timeout())
+            .connect(&config.url)
+            .await?;
+        sqlx::query!("SELECT 1 AS one").fetch_one(&pool).await?;
 
         log::info!("Database connection established");
         Ok(Self { pool })
     }
 
-    pub async fn get_client(
-        &self,
-    ) -> Result<deadpool_postgres::Client, Box<dyn std::error::Error + Send + Sync>> {
-        Ok(self.pool.get().await?)
+    /// Close the pool on shutdown: later checkouts fail right away, idle
+    /// connections are dropped and those still in use are waited for up to
+    /// `CLOSE_TIMEOUT`
+    pub async fn close(&self) {
+        let size = self.pool.size();
+        let in_use = size.saturating_sub(self.pool.num_idle() as u32);
+        if tokio::time::timeout(CLOSE_TIMEOUT, self.pool.close()).await.is_err() {
+            log::warn!("Gave up waiting for database connections still in use after {:?}", CLOSE_TIMEOUT);
+        }
+        log::info!("Closed database pool ({} connections open, {} were in use)", size, in_use);
+    }
+
+    /// The connection pool, for queries outside this service such as test
+    /// fixtures
+    pub fn pool(&self) -> &DbPool {
+        &self.pool
     }
 
     /// Initialize database schema
-    pub async fn init_schema(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    #[instrument(skip_all)]
+    pub async fn init_schema(&self) -> Result<(), Error> {
         // Schema creation is intentionally omitted here.
         // Use the SQL files under `migrations/` and the `bin/` helpers to create or migrate the database.
         log::info!("Skipping inline DDL in init_schema; use migrations/ and bin/ scripts to manage schema");
@@ -52,133 +94,125 @@ impl DatabaseService {
     // BUSINESS REGISTRATION (Verification Workflow)
     // ========================================================================
 
+    #[instrument(skip_all)]
     pub async fn create_registration(
         &self,
         registration: NewBusinessRegistration,
-    ) -> Result<BusinessRegistration, Box<dyn std::error::Error + Send + Sync>> {
-        let client = self.get_client().await?;
+        ctx: &AuditContext,
+    ) -> Result<BusinessRegistration, Error> {
+        let mut tx = self.pool.begin().await?;
 
-        let row = client.query_one(
-            r#"
-            INSERT INTO business_registration_requests (
+        let registration = query_registration!(
+            "INSERT INTO business_registration_requests (
                 id, user_id, business_id, name, category, address, description,
                 phone, website, tax_id, document_urls, is_multi_user_team, status,
                 owner_email, owner_username, rejection_reason, reviewer_notes,
-                reviewer_id, reviewer_name, submitted_at, updated_at
+                reviewer_id, reviewer_name, content_findings, submitted_at, updated_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
-            RETURNING *
-            "#,
-            &[
-                &registration.id,
-                &registration.user_id,
-                &registration.business_id,
-                &registration.name,
-                &registration.category,
-                &registration.address,
-                &registration.description,
-                &registration.phone,
-                &registration.website,
-                &registration.tax_id,
-                &registration.document_urls,
-                &registration.is_multi_user_team,
-                &registration.status,
-                &registration.owner_email,
-                &registration.owner_username,
-                &registration.rejection_reason,
-                &registration.reviewer_notes,
-                &registration.reviewer_id,
-                &registration.reviewer_name,
-                &registration.submitted_at,
-                &registration.updated_at,
-            ],
-        ).await?;
-
-        Ok(row_to_business_registration(&row))
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22)
+            RETURNING",
+            "",
+            registration.id,
+            registration.user_id,
+            registration.business_id,
+            registration.name,
+            registration.category,
+            registration.address,
+            registration.description,
+            registration.phone,
+            registration.website,
+            registration.tax_id,
+            registration.document_urls as _,
+            registration.is_multi_user_team,
+            registration.status as _,
+            registration.owner_email,
+            registration.owner_username,
+            registration.rejection_reason,
+            registration.reviewer_notes,
+            registration.reviewer_id,
+            registration.reviewer_name,
+            registration.content_findings as _,
+            registration.submitted_at,
+            registration.updated_at,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        audit::record(&mut tx, ctx, AuditEntity::Registration, registration.id, AuditAction::Create, None, Some(&registration)).await?;
+        outbox::enqueue_events(&mut tx, [DomainEventPayload::RegistrationSubmitted(registration.clone())]).await?;
+        tx.commit().await?;
+
+        Ok(registration)
     }
 
+    #[instrument(skip_all)]
     pub async fn get_registration_by_id(
         &self,
         registration_id: Uuid,
-    ) -> Result<Option<BusinessRegistration>, Box<dyn std::error::Error + Send + Sync>> {
-        let client = self.get_client().await?;
-
-        let rows = client.query(
-            "SELECT * FROM business_registration_requests WHERE id = $1",
-            &[&registration_id],
-        ).await?;
-
-        if rows.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(row_to_business_registration(&rows[0])))
-        }
+    ) -> Result<Option<BusinessRegistration>, Error> {
+        Ok(query_registration!("SELECT", "FROM business_registration_requests WHERE id = $1", registration_id)
+            .fetch_optional(&self.pool)
+            .await?)
     }
 
+    #[instrument(skip_all)]
     pub async fn get_latest_registration_for_user(
         &self,
         user_id: Uuid,
-    ) -> Result<Option<BusinessRegistration>, Box<dyn std::error::Error + Send + Sync>> {
-        let client = self.get_client().await?;
-
-        let rows = client.query(
-            r#"
-            SELECT * FROM business_registration_requests
+    ) -> Result<Option<BusinessRegistration>, Error> {
+        Ok(query_registration!(
+            "SELECT",
+            "FROM business_registration_requests
             WHERE user_id = $1
             ORDER BY submitted_at DESC
-            LIMIT 1
-            "#,
-            &[&user_id],
-        ).await?;
-
-        if rows.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(row_to_business_registration(&rows[0])))
-        }
+            LIMIT 1",
+            user_id,
+        )
+        .fetch_optional(&self.pool)
+        .await?)
     }
 
+    #[instrument(skip_all)]
     pub async fn list_registrations_for_user(
         &self,
         user_id: Uuid,
-    ) -> Result<Vec<BusinessRegistration>, Box<dyn std::error::Error + Send + Sync>> {
-        let client = self.get_client().await?;
-
-        let rows = client.query(
-            r#"
-            SELECT * FROM business_registration_requests
+    ) -> Result<Vec<BusinessRegistration>, Error> {
+        Ok(query_registration!(
+            "SELECT",
+            "FROM business_registration_requests
             WHERE user_id = $1
-            ORDER BY submitted_at DESC
-            "#,
-            &[&user_id],
-        ).await?;
-
-        Ok(rows.iter().map(row_to_business_registration).collect())
+            ORDER BY submitted_at DESC",
+            user_id,
+        )
+        .fetch_all(&self.pool)
+        .await?)
     }
 
+    #[instrument(skip_all)]
     pub async fn list_pending_reviews(
         &self,
         limit: i64,
         offset: i64,
-    ) -> Result<Vec<PendingBusinessReview>, Box<dyn std::error::Error + Send + Sync>> {
-        let client = self.get_client().await?;
-
-        let rows = client.query(
+    ) -> Result<Vec<PendingBusinessReview>, Error> {
+        Ok(sqlx::query_as!(
+            PendingBusinessReview,
             r#"
-            SELECT id, name, category, address, tax_id, document_urls,
-                   submitted_at, owner_email, owner_username
+            SELECT id, name, category, address, tax_id, document_urls AS "document_urls: sqlx::types::Json<Vec<String>>",
+                   submitted_at, owner_email, owner_username, content_findings AS "content_findings: crate::models::ContentFindings"
             FROM business_registration_requests
             WHERE status = 'pending'
             ORDER BY submitted_at ASC
             LIMIT $1 OFFSET $2
             "#,
-            &[&limit, &offset],
-        ).await?;
-
-        Ok(rows.iter().map(row_to_pending_business_review).collect())
+            limit,
+            offset,
+        )
+        .fetch_all(&self.pool)
+        .await?)
     }
 
-    // TODO: Port remaining methods (record_review_event, list_review_events, get_registration_with_history, get_review_stats, etc.)
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all)]
     pub async fn record_review_event(
         &self,
         registration_id: Uuid,
@@ -188,346 +222,539 @@ impl DatabaseService {
         notes: Option<String>,
         rejection_reason: Option<String>,
         new_status: BusinessVerificationStatus,
+        ctx: &AuditContext,
     ) -> Result<BusinessRegistration, Error> {
-        let mut client = self.pool.get().await?;
-        let tx = client.transaction().await?;
-
-        tx.execute(
-            "INSERT INTO business_review_events (registration_id, reviewer_id, reviewer_name, action, notes, rejection_reason) VALUES ($1, $2, $3, $4, $5, $6)",
-            &[&registration_id, &reviewer_id, &reviewer_name, &action, &notes, &rejection_reason],
-        ).await?;
-
-        let row = tx.query_one(
-            "UPDATE business_registration_requests SET status = $2, rejection_reason = $3, reviewer_notes = COALESCE($4, reviewer_notes), reviewer_id = COALESCE($5, reviewer_id), reviewer_name = COALESCE($6, reviewer_name), updated_at = NOW() WHERE id = $1 RETURNING id, user_id, business_id, name, category, address, description, phone, website, tax_id, document_urls, is_multi_user_team, status, owner_email, owner_username, rejection_reason, reviewer_notes, reviewer_id, reviewer_name, submitted_at, updated_at",
-            &[&registration_id, &new_status, &rejection_reason, &notes, &reviewer_id, &reviewer_name],
-        ).await?;
-
+        let mut tx = self.pool.begin().await?;
+
+        let before = query_registration!("SELECT", "FROM business_registration_requests WHERE id = $1 FOR UPDATE", registration_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            "INSERT INTO business_review_events (id, registration_id, reviewer_id, reviewer_name, action, notes, rejection_reason) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            Uuid::new_v4(), registration_id, reviewer_id, reviewer_name, action as _, notes, rejection_reason,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let registration = query_registration!(
+            "UPDATE business_registration_requests SET status = $2, rejection_reason = $3, reviewer_notes = COALESCE($4, reviewer_notes), reviewer_id = COALESCE($5, reviewer_id), reviewer_name = COALESCE($6, reviewer_name), updated_at = NOW() WHERE id = $1 RETURNING",
+            "",
+            registration_id, new_status as _, rejection_reason, notes, reviewer_id, reviewer_name,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        audit::record(&mut tx, ctx, AuditEntity::Registration, registration_id, AuditAction::Review(action), before.as_ref(), Some(&registration)).await?;
+        outbox::enqueue_events(&mut tx, DomainEventPayload::from_review_action(action, registration.clone())).await?;
         tx.commit().await?;
 
-        Ok(row_to_business_registration(&row))
+        Ok(registration)
     }
 
-    pub async fn create_business(&self, business: NewBusiness) -> Result<Business, Error> {
-        let client = self.pool.get().await?;
-        let row = client.query_one(
-            "INSERT INTO businesses (id, registration_id, owner_user_id, business_name, category, tax_id, description, website, logo_url, is_active, metadata, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13) RETURNING id, registration_id, owner_user_id, business_name, category, tax_id, description, website, logo_url, is_active, metadata, created_at, updated_at",
-            &[&business.id, &business.registration_id, &business.owner_user_id, &business.business_name, &business.category, &business.tax_id, &business.description, &business.website, &business.logo_url, &business.is_active, &business.metadata, &business.created_at, &business.updated_at],
-        ).await?;
+    #[instrument(skip_all)]
+    pub async fn create_business(&self, business: NewBusiness, ctx: &AuditContext) -> Result<Business, Error> {
+        let mut tx = self.pool.begin().await?;
+        let business = query_business!(
+            "INSERT INTO businesses (id, registration_id, owner_user_id, business_name, category, tax_id, description, website, logo_url, is_active, metadata, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13) RETURNING",
+            "",
+            business.id, business.registration_id, business.owner_user_id, business.business_name, business.category, business.tax_id, business.description, business.website, business.logo_url, business.is_active, business.metadata, business.created_at, business.updated_at,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
 
-        Ok(row_to_business(&row))
+        audit::record(&mut tx, ctx, AuditEntity::Business, business.id, AuditAction::Create, None, Some(&business)).await?;
+        outbox::enqueue_events(&mut tx, [DomainEventPayload::BusinessCreated(business.clone())]).await?;
+        tx.commit().await?;
+
+        Ok(business)
     }
 
+    #[instrument(skip_all)]
     pub async fn get_business(&self, business_id: Uuid) -> Result<Option<Business>, Error> {
-        let client = self.pool.get().await?;
-        let row = client.query_opt(
-            "SELECT id, registration_id, owner_user_id, business_name, category, tax_id, description, website, logo_url, is_active, metadata, created_at, updated_at FROM businesses WHERE id = $1",
-            &[&business_id],
-        ).await?;
+        Ok(query_business!("SELECT", "FROM businesses WHERE id = $1 AND deleted_at IS NULL", business_id)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn list_businesses_for_user(&self, user_id: Uuid, include_deleted: bool) -> Result<Vec<Business>, Error> {
+        Ok(query_business!(
+            "SELECT",
+            "FROM businesses WHERE owner_user_id = $1 AND ($2 OR deleted_at IS NULL) ORDER BY created_at DESC",
+            user_id, include_deleted,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Write `business` if it still has the version it was read with
+    #[instrument(skip_all)]
+    pub async fn update_business(&self, business: Business, ctx: &AuditContext) -> Result<Business, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = query_business!("SELECT", "FROM businesses WHERE id = $1 AND deleted_at IS NULL FOR UPDATE", business.id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Business"))?;
+        let business = query_business!(
+            "UPDATE businesses SET registration_id = $2, business_name = $3, category = $4, tax_id = $5, description = $6, website = $7, logo_url = $8, is_active = $9, metadata = $10, version = version + 1, updated_at = NOW() WHERE id = $1 AND version = $11 RETURNING",
+            "",
+            business.id, business.registration_id, business.business_name, business.category, business.tax_id, business.description, business.website, business.logo_url, business.is_active, business.metadata, business.version,
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::VersionMismatch { current_version: before.version })?;
+
+        audit::record(&mut tx, ctx, AuditEntity::Business, business.id, AuditAction::Update, Some(&before), Some(&business)).await?;
+        outbox::enqueue_events(&mut tx, [DomainEventPayload::BusinessUpdated(business.clone())]).await?;
+        tx.commit().await?;
 
-        Ok(row.map(|r| row_to_business(&r)))
+        Ok(business)
     }
 
-    pub async fn list_businesses_for_user(&self, user_id: Uuid) -> Result<Vec<Business>, Error> {
-        let client = self.pool.get().await?;
-        let rows = client.query(
-            "SELECT id, registration_id, owner_user_id, business_name, category, tax_id, description, website, logo_url, is_active, metadata, created_at, updated_at FROM businesses WHERE owner_user_id = $1 ORDER BY created_at DESC",
-            &[&user_id],
-        ).await?;
-
-        Ok(rows.into_iter().map(|r| row_to_business(&r)).collect())
-    }
+    /// Soft-delete a business together with its live locations and promotions.
+    ///
+    /// Everything is stamped with the same `deleted_at`, which is what
+    /// `restore_business` uses to bring back exactly this cascade. Refuses
+    /// while promotions are active unless `force` is set.
+    #[instrument(skip_all)]
+    pub async fn delete_business(
+        &self,
+        business_id: Uuid,
+        expected_version: Option<i32>,
+        force: bool,
+        ctx: &AuditContext,
+    ) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let current_version = sqlx::query_scalar!("SELECT version FROM businesses WHERE id = $1 AND deleted_at IS NULL FOR UPDATE", business_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Business"))?;
+        if expected_version.is_some_and(|expected| expected != current_version) {
+            return Err(AppError::VersionMismatch { current_version });
+        }
 
-    pub async fn update_business(&self, business: Business) -> Result<Business, Error> {
-        let client = self.pool.get().await?;
-        let row = client.query_one(
-            "UPDATE businesses SET registration_id = $2, business_name = $3, category = $4, tax_id = $5, description = $6, website = $7, logo_url = $8, is_active = $9, metadata = $10, updated_at = NOW() WHERE id = $1 RETURNING id, registration_id, owner_user_id, business_name, category, tax_id, description, website, logo_url, is_active, metadata, created_at, updated_at",
-            &[&business.id, &business.registration_id, &business.owner_user_id, &business.business_name, &business.category, &business.tax_id, &business.description, &business.website, &business.logo_url, &business.is_active, &business.metadata],
-        ).await?;
+        if !force {
+            let active = sqlx::query_scalar!(
+                r#"SELECT COUNT(*) AS "active!" FROM business_promotions p LEFT JOIN business_locations l ON p.location_id = l.id WHERE p.business_id = $1 AND p.status = 'active' AND p.deleted_at IS NULL AND l.deleted_at IS NULL"#,
+                business_id,
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            if active > 0 {
+                return Err(AppError::ActivePromotions(active));
+            }
+        }
 
-        Ok(row_to_business(&row))
-    }
+        let promotions = query_promotion!(
+            "UPDATE business_promotions SET deleted_at = NOW(), deleted_by = $2, version = version + 1 WHERE business_id = $1 AND deleted_at IS NULL RETURNING",
+            "",
+            business_id, ctx.actor_id,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let locations = query_location!(
+            "UPDATE business_locations SET deleted_at = NOW(), deleted_by = $2, version = version + 1 WHERE business_id = $1 AND deleted_at IS NULL RETURNING",
+            "",
+            business_id, ctx.actor_id,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let business = query_business!(
+            "UPDATE businesses SET deleted_at = NOW(), deleted_by = $2, version = version + 1 WHERE id = $1 RETURNING",
+            "",
+            business_id, ctx.actor_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for promotion in &promotions {
+            let before = BusinessPromotion { deleted_at: None, deleted_by: None, version: promotion.version - 1, ..promotion.clone() };
+            audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion.id, AuditAction::Delete, Some(&before), Some(promotion)).await?;
+        }
+        for location in &locations {
+            let before = BusinessLocation { deleted_at: None, deleted_by: None, version: location.version - 1, ..location.clone() };
+            audit::record(&mut tx, ctx, AuditEntity::Location, location.id, AuditAction::Delete, Some(&before), Some(location)).await?;
+        }
+        let before = Business { deleted_at: None, deleted_by: None, version: business.version - 1, ..business.clone() };
+        audit::record(&mut tx, ctx, AuditEntity::Business, business_id, AuditAction::Delete, Some(&before), Some(&business)).await?;
+
+        let events = promotions
+            .iter()
+            .map(|p| DomainEventPayload::PromotionDeleted(EntityDeleted { id: p.id, parent_id: p.location_id }))
+            .chain(locations.iter().map(|l| {
+                DomainEventPayload::LocationDeleted(EntityDeleted { id: l.id, parent_id: Some(business_id) })
+            }))
+            .chain([DomainEventPayload::BusinessDeleted(EntityDeleted { id: business_id, parent_id: None })])
+            .collect::<Vec<_>>();
+        outbox::enqueue_events(&mut tx, events).await?;
 
-    pub async fn delete_business(&self, business_id: Uuid) -> Result<(), Error> {
-        let client = self.pool.get().await?;
-        client.execute("DELETE FROM businesses WHERE id = $1", &[&business_id]).await?;
+        tx.commit().await?;
         Ok(())
     }
 
-    pub async fn create_location(&self, location: NewBusinessLocation) -> Result<BusinessLocation, Error> {
-        let client = self.pool.get().await?;
-        let row = client.query_one(
-            "INSERT INTO business_locations (id, business_id, location_name, formatted_address, street, city, state_region, postal_code, country, latitude, longitude, google_place_id, timezone, phone, email, is_active, is_primary, operating_hours, notes, metadata) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20) RETURNING id, business_id, location_name, formatted_address, street, city, state_region, postal_code, country, latitude, longitude, google_place_id, timezone, phone, email, is_active, is_primary, operating_hours, notes, metadata, created_at, updated_at",
-            &[&location.id, &location.business_id, &location.location_name, &location.formatted_address, &location.street, &location.city, &location.state_region, &location.postal_code, &location.country, &location.latitude, &location.longitude, &location.google_place_id, &location.timezone, &location.phone, &location.email, &location.is_active, &location.is_primary, &location.operating_hours, &location.notes, &location.metadata],
-        ).await?;
+    #[instrument(skip_all)]
+    pub async fn create_location(&self, location: NewBusinessLocation, ctx: &AuditContext) -> Result<BusinessLocation, Error> {
+        let mut tx = self.pool.begin().await?;
+        let location = insert_location(&mut tx, &location).await?;
+        audit::record(&mut tx, ctx, AuditEntity::Location, location.id, AuditAction::Create, None, Some(&location)).await?;
+        outbox::enqueue_events(&mut tx, [DomainEventPayload::LocationCreated(location.clone())]).await?;
+        tx.commit().await?;
 
-        Ok(row_to_business_location(&row))
+        Ok(location)
     }
 
+    #[instrument(skip_all)]
     pub async fn get_location(&self, location_id: Uuid) -> Result<Option<BusinessLocation>, Error> {
-        let client = self.pool.get().await?;
-        let row = client.query_opt(
-            "SELECT id, business_id, location_name, formatted_address, street, city, state_region, postal_code, country, latitude, longitude, google_place_id, timezone, phone, email, is_active, is_primary, operating_hours, notes, metadata, created_at, updated_at FROM business_locations WHERE id = $1",
-            &[&location_id],
-        ).await?;
+        Ok(query_location!("SELECT", "FROM business_locations WHERE id = $1 AND deleted_at IS NULL", location_id)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn list_locations_for_business(&self, business_id: Uuid, include_deleted: bool) -> Result<Vec<BusinessLocation>, Error> {
+        Ok(query_location!(
+            "SELECT",
+            "FROM business_locations WHERE business_id = $1 AND ($2 OR deleted_at IS NULL) ORDER BY is_primary DESC, created_at ASC",
+            business_id, include_deleted,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Write `location` if it still has the version it was read with
+    #[instrument(skip_all)]
+    pub async fn update_location(&self, location: BusinessLocation, ctx: &AuditContext) -> Result<BusinessLocation, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = query_location!("SELECT", "FROM business_locations WHERE id = $1 AND deleted_at IS NULL FOR UPDATE", location.id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Location"))?;
+        let location = query_location!(
+            "UPDATE business_locations SET location_name = $2, formatted_address = $3, street = $4, city = $5, state_region = $6, postal_code = $7, country = $8, latitude = $9, longitude = $10, google_place_id = $11, timezone = $12, phone = $13, email = $14, is_active = $15, is_primary = $16, operating_hours = $17, notes = $18, metadata = $19, version = version + 1, updated_at = NOW() WHERE id = $1 AND version = $20 RETURNING",
+            "",
+            location.id, location.location_name, location.formatted_address, location.street, location.city, location.state_region, location.postal_code, location.country, location.latitude, location.longitude, location.google_place_id, location.timezone, location.phone, location.email, location.is_active, location.is_primary, location.operating_hours, location.notes, location.metadata, location.version,
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::VersionMismatch { current_version: before.version })?;
+
+        audit::record(&mut tx, ctx, AuditEntity::Location, location.id, AuditAction::Update, Some(&before), Some(&location)).await?;
+        outbox::enqueue_events(&mut tx, [DomainEventPayload::LocationUpdated(location.clone())]).await?;
+        tx.commit().await?;
 
-        Ok(row.map(|r| row_to_business_location(&r)))
+        Ok(location)
     }
 
-    pub async fn list_locations_for_business(&self, business_id: Uuid) -> Result<Vec<BusinessLocation>, Error> {
-        let client = self.pool.get().await?;
-        let rows = client.query(
-            "SELECT id, business_id, location_name, formatted_address, street, city, state_region, postal_code, country, latitude, longitude, google_place_id, timezone, phone, email, is_active, is_primary, operating_hours, notes, metadata, created_at, updated_at FROM business_locations WHERE business_id = $1 ORDER BY is_primary DESC, created_at ASC",
-            &[&business_id],
-        ).await?;
+    /// Soft-delete a location and its live promotions
+    #[instrument(skip_all)]
+    pub async fn delete_location(&self, location_id: Uuid, expected_version: Option<i32>, ctx: &AuditContext) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
 
-        Ok(rows.into_iter().map(|r| row_to_business_location(&r)).collect())
-    }
+        let current_version = sqlx::query_scalar!("SELECT version FROM business_locations WHERE id = $1 AND deleted_at IS NULL FOR UPDATE", location_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Location"))?;
+        if expected_version.is_some_and(|expected| expected != current_version) {
+            return Err(AppError::VersionMismatch { current_version });
+        }
 
-    pub async fn update_location(&self, location: BusinessLocation) -> Result<BusinessLocation, Error> {
-        let client = self.pool.get().await?;
-        let row = client.query_one(
-            "UPDATE business_locations SET location_name = $2, formatted_address = $3, street = $4, city = $5, state_region = $6, postal_code = $7, country = $8, latitude = $9, longitude = $10, google_place_id = $11, timezone = $12, phone = $13, email = $14, is_active = $15, is_primary = $16, operating_hours = $17, notes = $18, metadata = $19, updated_at = NOW() WHERE id = $1 RETURNING id, business_id, location_name, formatted_address, street, city, state_region, postal_code, country, latitude, longitude, google_place_id, timezone, phone, email, is_active, is_primary, operating_hours, notes, metadata, created_at, updated_at",
-            &[&location.id, &location.location_name, &location.formatted_address, &location.street, &location.city, &location.state_region, &location.postal_code, &location.country, &location.latitude, &location.longitude, &location.google_place_id, &location.timezone, &location.phone, &location.email, &location.is_active, &location.is_primary, &location.operating_hours, &location.notes, &location.metadata],
-        ).await?;
+        let location = query_location!(
+            "UPDATE business_locations SET deleted_at = NOW(), deleted_by = $2, version = version + 1 WHERE id = $1 RETURNING",
+            "",
+            location_id, ctx.actor_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let promotions = query_promotion!(
+            "UPDATE business_promotions SET deleted_at = NOW(), deleted_by = $2, version = version + 1 WHERE location_id = $1 AND deleted_at IS NULL RETURNING",
+            "",
+            location_id, ctx.actor_id,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for promotion in &promotions {
+            let before = BusinessPromotion { deleted_at: None, deleted_by: None, version: promotion.version - 1, ..promotion.clone() };
+            audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion.id, AuditAction::Delete, Some(&before), Some(promotion)).await?;
+        }
+        let before = BusinessLocation { deleted_at: None, deleted_by: None, version: location.version - 1, ..location.clone() };
+        audit::record(&mut tx, ctx, AuditEntity::Location, location_id, AuditAction::Delete, Some(&before), Some(&location)).await?;
 
-        Ok(row_to_business_location(&row))
-    }
+        let events = promotions
+            .iter()
+            .map(|p| DomainEventPayload::PromotionDeleted(EntityDeleted { id: p.id, parent_id: Some(location_id) }))
+            .chain([DomainEventPayload::LocationDeleted(EntityDeleted { id: location_id, parent_id: Some(location.business_id) })])
+            .collect::<Vec<_>>();
+        outbox::enqueue_events(&mut tx, events).await?;
 
-    pub async fn delete_location(&self, location_id: Uuid) -> Result<(), Error> {
-        let client = self.pool.get().await?;
-        client.execute("DELETE FROM business_locations WHERE id = $1", &[&location_id]).await?;
+        tx.commit().await?;
         Ok(())
     }
 
-    pub async fn create_promotion(&self, promotion: NewBusinessPromotion) -> Result<BusinessPromotion, Error> {
-        let client = self.pool.get().await?;
-        let row = client.query_one(
-            "INSERT INTO business_promotions (id, location_id, title, subtitle, description, promotion_type, status, image_url, prize, reward_points, discount_percent, max_claims, per_user_limit, total_claims, requires_check_in, requires_purchase, terms, metadata, starts_at, ends_at, published_at, created_by, updated_by, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25) RETURNING id, location_id, title, subtitle, description, promotion_type, status, image_url, prize, reward_points, discount_percent, max_claims, per_user_limit, total_claims, requires_check_in, requires_purchase, terms, metadata, starts_at, ends_at, published_at, created_by, updated_by, created_at, updated_at",
-            &[&promotion.id, &promotion.location_id, &promotion.title, &promotion.subtitle, &promotion.description, &promotion.promotion_type, &promotion.status, &promotion.image_url, &promotion.prize, &promotion.reward_points, &promotion.discount_percent, &promotion.max_claims, &promotion.per_user_limit, &promotion.total_claims, &promotion.requires_check_in, &promotion.requires_purchase, &promotion.terms, &promotion.metadata, &promotion.starts_at, &promotion.ends_at, &promotion.published_at, &promotion.created_by, &promotion.updated_by, &promotion.created_at, &promotion.updated_at],
-        ).await?;
+    /// Every targeted location must be a live location of the promotion's
+    /// business
+    #[instrument(skip_all)]
+    pub async fn create_promotion(&self, promotion: NewBusinessPromotion, ctx: &AuditContext) -> Result<BusinessPromotion, Error> {
+        let mut tx = self.pool.begin().await?;
+        check_business_locations(&mut tx, promotion.business_id, &promotion.location_ids).await?;
+        let promotion = insert_promotion(&mut tx, &promotion).await?;
+        audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion.id, AuditAction::Create, None, Some(&promotion)).await?;
+        let mut events = vec![DomainEventPayload::PromotionCreated(promotion.clone())];
+        if promotion.status == BusinessPromotionStatus::Active {
+            events.push(DomainEventPayload::PromotionActivated(promotion.clone()));
+        }
+        outbox::enqueue_events(&mut tx, events).await?;
+        tx.commit().await?;
 
-        Ok(row_to_business_promotion(&row))
+        Ok(promotion)
     }
 
-    pub async fn get_promotion(&self, promotion_id: Uuid) -> Result<Option<BusinessPromotion>, Error> {
-        let client = self.pool.get().await?;
-        let row = client.query_opt(
-            "SELECT id, location_id, title, subtitle, description, promotion_type, status, image_url, prize, reward_points, discount_percent, max_claims, per_user_limit, total_claims, requires_check_in, requires_purchase, terms, metadata, starts_at, ends_at, published_at, created_by, updated_by, created_at, updated_at FROM business_promotions WHERE id = $1",
-            &[&promotion_id],
-        ).await?;
+    /// Insert copies of a promotion in one transaction. Every target must be a
+    /// live location of `business_id`.
+    #[instrument(skip_all)]
+    pub async fn clone_promotion(
+        &self,
+        business_id: Uuid,
+        clones: Vec<NewBusinessPromotion>,
+        ctx: &AuditContext,
+    ) -> Result<Vec<BusinessPromotion>, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let targets: Vec<Uuid> = clones.iter().flat_map(|clone| clone.location_ids.iter().copied()).collect();
+        check_business_locations(&mut tx, business_id, &targets).await?;
+
+        let mut promotions = Vec::with_capacity(clones.len());
+        let mut events = Vec::new();
+        for clone in &clones {
+            let promotion = insert_promotion(&mut tx, clone).await?;
+            audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion.id, AuditAction::Create, None, Some(&promotion)).await?;
+            events.push(DomainEventPayload::PromotionCreated(promotion.clone()));
+            if promotion.status == BusinessPromotionStatus::Active {
+                events.push(DomainEventPayload::PromotionActivated(promotion.clone()));
+            }
+            promotions.push(promotion);
+        }
+        outbox::enqueue_events(&mut tx, events).await?;
+        tx.commit().await?;
 
-        Ok(row.map(|r| row_to_business_promotion(&r)))
+        Ok(promotions)
     }
 
-    pub async fn list_promotions_for_location(&self, location_id: Uuid) -> Result<Vec<BusinessPromotion>, Error> {
-        let client = self.pool.get().await?;
-        let rows = client.query(
-            "SELECT id, location_id, title, subtitle, description, promotion_type, status, image_url, prize, reward_points, discount_percent, max_claims, per_user_limit, total_claims, requires_check_in, requires_purchase, terms, metadata, starts_at, ends_at, published_at, created_by, updated_by, created_at, updated_at FROM business_promotions WHERE location_id = $1 ORDER BY starts_at DESC",
-            &[&location_id],
-        ).await?;
+    #[instrument(skip_all)]
+    pub async fn get_promotion(&self, promotion_id: Uuid) -> Result<Option<BusinessPromotion>, Error> {
+        Ok(query_promotion!("SELECT", "FROM business_promotions WHERE id = $1 AND deleted_at IS NULL", promotion_id)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    /// Promotions that apply to a location: its own, the multi-location ones
+    /// that target it and the business-wide ones of its business
+    #[instrument(skip_all)]
+    pub async fn list_promotions_for_location(&self, location_id: Uuid, include_deleted: bool) -> Result<Vec<BusinessPromotion>, Error> {
+        Ok(query_promotion!(
+            "SELECT",
+            "FROM business_promotions p WHERE p.business_id = (SELECT l.business_id FROM business_locations l WHERE l.id = $1) AND (p.target_scope = 'business' OR EXISTS (SELECT 1 FROM business_promotion_locations t WHERE t.promotion_id = p.id AND t.location_id = $1)) AND ($2 OR p.deleted_at IS NULL) ORDER BY p.starts_at DESC",
+            location_id, include_deleted,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn list_promotions_for_business(&self, business_id: Uuid, include_deleted: bool) -> Result<Vec<BusinessPromotion>, Error> {
+        Ok(query_promotion!(
+            "SELECT",
+            "FROM business_promotions p WHERE p.business_id = $1 AND ($2 OR (p.deleted_at IS NULL AND NOT EXISTS (SELECT 1 FROM business_locations l WHERE l.id = p.location_id AND l.deleted_at IS NOT NULL))) ORDER BY p.starts_at DESC",
+            business_id, include_deleted,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Write `promotion` if it still has the version it was read with
+    #[instrument(skip_all)]
+    pub async fn update_promotion(&self, promotion: BusinessPromotion, ctx: &AuditContext) -> Result<BusinessPromotion, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = query_promotion!("SELECT", "FROM business_promotions WHERE id = $1 AND deleted_at IS NULL FOR UPDATE", promotion.id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Promotion"))?;
+
+        let promotion = query_promotion!(
+            "UPDATE business_promotions SET title = $2, subtitle = $3, description = $4, promotion_type = $5, status = $6, image_url = $7, prize = $8, reward_points = $9, discount_percent = $10, max_claims = $11, per_user_limit = $12, requires_check_in = $13, requires_purchase = $14, terms = $15, metadata = $16, content_findings = $17, starts_at = $18, ends_at = $19, published_at = $20, updated_by = $21, version = version + 1, updated_at = NOW() WHERE id = $1 AND version = $22 RETURNING",
+            "",
+            promotion.id, promotion.title, promotion.subtitle, promotion.description, promotion.promotion_type as _, promotion.status as _, promotion.image_url, promotion.prize, promotion.reward_points, promotion.discount_percent, promotion.max_claims, promotion.per_user_limit, promotion.requires_check_in, promotion.requires_purchase, promotion.terms, promotion.metadata, promotion.content_findings as _, promotion.starts_at, promotion.ends_at, promotion.published_at, promotion.updated_by, promotion.version,
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::VersionMismatch { current_version: before.version })?;
+
+        audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion.id, AuditAction::Update, Some(&before), Some(&promotion)).await?;
+        let mut events = vec![DomainEventPayload::PromotionUpdated(promotion.clone())];
+        if promotion.status == BusinessPromotionStatus::Active && before.status != BusinessPromotionStatus::Active {
+            events.push(DomainEventPayload::PromotionActivated(promotion.clone()));
+        }
+        outbox::enqueue_events(&mut tx, events).await?;
+        tx.commit().await?;
 
-        Ok(rows.into_iter().map(|r| row_to_business_promotion(&r)).collect())
+        Ok(promotion)
     }
 
-    pub async fn list_promotions_for_business(&self, business_id: Uuid) -> Result<Vec<BusinessPromotion>, Error> {
-        let client = self.pool.get().await?;
-        let rows = client.query(
-            "SELECT p.id, p.location_id, p.title, p.subtitle, p.description, p.promotion_type, p.status, p.image_url, p.prize, p.reward_points, p.discount_percent, p.max_claims, p.per_user_limit, p.total_claims, p.requires_check_in, p.requires_purchase, p.terms, p.metadata, p.starts_at, p.ends_at, p.published_at, p.created_by, p.updated_by, p.created_at, p.updated_at FROM business_promotions p INNER JOIN business_locations l ON p.location_id = l.id WHERE l.business_id = $1 ORDER BY p.starts_at DESC",
-            &[&business_id],
-        ).await?;
+    #[instrument(skip_all)]
+    pub async fn delete_promotion(&self, promotion_id: Uuid, expected_version: Option<i32>, ctx: &AuditContext) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
 
-        Ok(rows.into_iter().map(|r| row_to_business_promotion(&r)).collect())
-    }
+        let current_version = sqlx::query_scalar!("SELECT version FROM business_promotions WHERE id = $1 AND deleted_at IS NULL FOR UPDATE", promotion_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Promotion"))?;
+        if expected_version.is_some_and(|expected| expected != current_version) {
+            return Err(AppError::VersionMismatch { current_version });
+        }
 
-    pub async fn update_promotion(&self, promotion: BusinessPromotion) -> Result<BusinessPromotion, Error> {
-        let client = self.pool.get().await?;
-        let row = client.query_one(
-            "UPDATE business_promotions SET title = $2, subtitle = $3, description = $4, promotion_type = $5, status = $6, image_url = $7, prize = $8, reward_points = $9, discount_percent = $10, max_claims = $11, per_user_limit = $12, requires_check_in = $13, requires_purchase = $14, terms = $15, metadata = $16, starts_at = $17, ends_at = $18, published_at = $19, updated_by = $20, updated_at = NOW() WHERE id = $1 RETURNING id, location_id, title, subtitle, description, promotion_type, status, image_url, prize, reward_points, discount_percent, max_claims, per_user_limit, total_claims, requires_check_in, requires_purchase, terms, metadata, starts_at, ends_at, published_at, created_by, updated_by, created_at, updated_at",
-            &[&promotion.id, &promotion.title, &promotion.subtitle, &promotion.description, &promotion.promotion_type, &promotion.status, &promotion.image_url, &promotion.prize, &promotion.reward_points, &promotion.discount_percent, &promotion.max_claims, &promotion.per_user_limit, &promotion.requires_check_in, &promotion.requires_purchase, &promotion.terms, &promotion.metadata, &promotion.starts_at, &promotion.ends_at, &promotion.published_at, &promotion.updated_by],
-        ).await?;
+        let promotion = query_promotion!(
+            "UPDATE business_promotions SET deleted_at = NOW(), deleted_by = $2, version = version + 1 WHERE id = $1 RETURNING",
+            "",
+            promotion_id, ctx.actor_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
 
-        Ok(row_to_business_promotion(&row))
-    }
+        let before = BusinessPromotion { deleted_at: None, deleted_by: None, version: promotion.version - 1, ..promotion.clone() };
+        audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion_id, AuditAction::Delete, Some(&before), Some(&promotion)).await?;
+        outbox::enqueue_events(&mut tx, [DomainEventPayload::PromotionDeleted(EntityDeleted { id: promotion_id, parent_id: promotion.location_id })]).await?;
 
-    pub async fn delete_promotion(&self, promotion_id: Uuid) -> Result<(), Error> {
-        let client = self.pool.get().await?;
-        client.execute("DELETE FROM business_promotions WHERE id = $1", &[&promotion_id]).await?;
+        tx.commit().await?;
         Ok(())
     }
 
+    #[instrument(skip_all)]
     pub async fn get_review_stats(&self) -> Result<ReviewStats, Error> {
-        let client = self.pool.get().await?;
-        let row = client.query_one(
-            "SELECT COUNT(*) FILTER (WHERE status = 'pending') AS pending, COUNT(*) FILTER (WHERE status = 'under_review') AS under_review, COUNT(*) FILTER (WHERE status = 'approved' AND submitted_at >= NOW() - INTERVAL '1 day') AS approved_today, COUNT(*) FILTER (WHERE status = 'rejected' AND submitted_at >= NOW() - INTERVAL '1 day') AS rejected_today FROM business_registration_requests",
-            &[],
-        ).await?;
-
-        Ok(ReviewStats {
-            pending: row.get("pending"),
-            under_review: row.get("under_review"),
-            approved_today: row.get("approved_today"),
-            rejected_today: row.get("rejected_today"),
-        })
-    }
-
-    pub async fn add_location_admin(&self, admin: NewLocationAdmin) -> Result<LocationAdmin, Error> {
-        let client = self.pool.get().await?;
-        let row = client.query_one(
-            "INSERT INTO location_admins (id, location_id, user_id, user_email, user_username, role, granted_by, granted_by_username, is_active, granted_at, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) RETURNING id, location_id, user_id, user_email, user_username, role, granted_by, granted_by_username, is_active, granted_at, created_at, updated_at",
-            &[&admin.id, &admin.location_id, &admin.user_id, &admin.user_email, &admin.user_username, &admin.role, &admin.granted_by, &admin.granted_by_username, &admin.is_active, &admin.granted_at, &admin.created_at, &admin.updated_at],
-        ).await?;
+        Ok(sqlx::query_as!(
+            ReviewStats,
+            r#"SELECT COUNT(*) FILTER (WHERE status = 'pending') AS "pending!", COUNT(*) FILTER (WHERE status = 'under_review') AS "under_review!", COUNT(*) FILTER (WHERE status = 'approved' AND submitted_at >= NOW() - INTERVAL '1 day') AS "approved_today!", COUNT(*) FILTER (WHERE status = 'rejected' AND submitted_at >= NOW() - INTERVAL '1 day') AS "rejected_today!" FROM business_registration_requests"#,
+        )
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn add_location_admin(&self, admin: NewLocationAdmin, ctx: &AuditContext) -> Result<LocationAdmin, Error> {
+        let mut tx = self.pool.begin().await?;
+        let admin = query_location_admin!(
+            "INSERT INTO business_location_admins (id, location_id, user_id, user_email, user_username, role, granted_by, granted_by_username, is_active, granted_at, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) RETURNING",
+            "",
+            admin.id, admin.location_id, admin.user_id, admin.user_email, admin.user_username, admin.role as _, admin.granted_by, admin.granted_by_username, admin.is_active, admin.granted_at, admin.created_at, admin.updated_at,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        audit::record(&mut tx, ctx, AuditEntity::LocationAdmin, admin.id, AuditAction::Create, None, Some(&admin)).await?;
+        outbox::enqueue_events(&mut tx, [DomainEventPayload::LocationAdminAdded(admin.clone())]).await?;
+        tx.commit().await?;
 
-        Ok(row_to_location_admin(&row))
+        Ok(admin)
     }
 
+    #[instrument(skip_all)]
     pub async fn list_location_admins(&self, location_id: Uuid) -> Result<Vec<LocationAdmin>, Error> {
-        let client = self.pool.get().await?;
-        let rows = client.query(
-            "SELECT id, location_id, user_id, user_email, user_username, role, granted_by, granted_by_username, is_active, granted_at, created_at, updated_at FROM location_admins WHERE location_id = $1 AND is_active = TRUE ORDER BY granted_at DESC",
-            &[&location_id],
-        ).await?;
-
-        Ok(rows.into_iter().map(|r| row_to_location_admin(&r)).collect())
-    }
-
-    pub async fn remove_location_admin(&self, location_id: Uuid, user_id: Uuid) -> Result<(), Error> {
-        let client = self.pool.get().await?;
-        client.execute("UPDATE location_admins SET is_active = FALSE, updated_at = NOW() WHERE location_id = $1 AND user_id = $2", &[&location_id, &user_id]).await?;
+        Ok(query_location_admin!(
+            "SELECT",
+            "FROM business_location_admins WHERE location_id = $1 AND is_active = TRUE ORDER BY granted_at DESC",
+            location_id,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn remove_location_admin(&self, location_id: Uuid, user_id: Uuid, ctx: &AuditContext) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
+        let before = query_location_admin!(
+            "SELECT",
+            "FROM business_location_admins WHERE location_id = $1 AND user_id = $2 AND is_active = TRUE FOR UPDATE",
+            location_id, user_id,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let removed = query_location_admin!(
+            "UPDATE business_location_admins SET is_active = FALSE, updated_at = NOW() WHERE location_id = $1 AND user_id = $2 AND is_active = TRUE RETURNING",
+            "",
+            location_id, user_id,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for admin in &removed {
+            let old = before.iter().find(|b| b.id == admin.id);
+            audit::record(&mut tx, ctx, AuditEntity::LocationAdmin, admin.id, AuditAction::Delete, old, Some(admin)).await?;
+        }
+        if !removed.is_empty() {
+            outbox::enqueue_events(&mut tx, [DomainEventPayload::LocationAdminRemoved(LocationAdminRemoved { location_id, user_id })]).await?;
+        }
+        tx.commit().await?;
         Ok(())
     }
-
-    // For now, only the above are ported to demonstrate the pattern.
 }
 
-// Row mapping functions
-fn row_to_location_admin(row: &tokio_postgres::Row) -> LocationAdmin {
-    LocationAdmin {
-        id: row.get("id"),
-        location_id: row.get("location_id"),
-        user_id: row.get("user_id"),
-        user_email: row.get("user_email"),
-        user_username: row.get("user_username"),
-        role: row.get("role"),
-        granted_by: row.get("granted_by"),
-        granted_by_username: row.get("granted_by_username"),
-        is_active: row.get("is_active"),
-        granted_at: row.get("granted_at"),
-        created_at: row.get("created_at"),
-        updated_at: row.get("updated_at"),
-    }
-}
-
-fn row_to_business_promotion(row: &tokio_postgres::Row) -> BusinessPromotion {
-    BusinessPromotion {
-        id: row.get("id"),
-        location_id: row.get("location_id"),
-        title: row.get("title"),
-        subtitle: row.get("subtitle"),
-        description: row.get("description"),
-        promotion_type: row.get("promotion_type"),
-        status: row.get("status"),
-        image_url: row.get("image_url"),
-        prize: row.get("prize"),
-        reward_points: row.get("reward_points"),
-        discount_percent: row.get("discount_percent"),
-        max_claims: row.get("max_claims"),
-        per_user_limit: row.get("per_user_limit"),
-        total_claims: row.get("total_claims"),
-        requires_check_in: row.get("requires_check_in"),
-        requires_purchase: row.get("requires_purchase"),
-        terms: row.get("terms"),
-        metadata: row.get("metadata"),
-        starts_at: row.get("starts_at"),
-        ends_at: row.get("ends_at"),
-        published_at: row.get("published_at"),
-        created_by: row.get("created_by"),
-        updated_by: row.get("updated_by"),
-        created_at: row.get("created_at"),
-        updated_at: row.get("updated_at"),
-    }
+async fn insert_location(conn: &mut PgConnection, location: &NewBusinessLocation) -> Result<BusinessLocation, Error> {
+    Ok(query_location!(
+        "INSERT INTO business_locations (id, business_id, location_name, formatted_address, street, city, state_region, postal_code, country, latitude, longitude, google_place_id, timezone, phone, email, is_active, is_primary, operating_hours, notes, metadata) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20) RETURNING",
+        "",
+        location.id, location.business_id, location.location_name, location.formatted_address, location.street, location.city, location.state_region, location.postal_code, location.country, location.latitude, location.longitude, location.google_place_id, location.timezone, location.phone, location.email, location.is_active, location.is_primary, location.operating_hours, location.notes, location.metadata,
+    )
+    .fetch_one(conn)
+    .await?)
 }
 
-fn row_to_business_location(row: &tokio_postgres::Row) -> BusinessLocation {
-    BusinessLocation {
-        id: row.get("id"),
-        business_id: row.get("business_id"),
-        location_name: row.get("location_name"),
-        formatted_address: row.get("formatted_address"),
-        street: row.get("street"),
-        city: row.get("city"),
-        state_region: row.get("state_region"),
-        postal_code: row.get("postal_code"),
-        country: row.get("country"),
-        latitude: row.get("latitude"),
-        longitude: row.get("longitude"),
-        google_place_id: row.get("google_place_id"),
-        timezone: row.get("timezone"),
-        phone: row.get("phone"),
-        email: row.get("email"),
-        is_active: row.get("is_active"),
-        is_primary: row.get("is_primary"),
-        operating_hours: row.get("operating_hours"),
-        notes: row.get("notes"),
-        metadata: row.get("metadata"),
-        created_at: row.get("created_at"),
-        updated_at: row.get("updated_at"),
-    }
+async fn insert_promotion(conn: &mut PgConnection, promotion: &NewBusinessPromotion) -> Result<BusinessPromotion, Error> {
+    let inserted = insert_promotion!(promotion).fetch_one(&mut *conn).await?;
+    insert_promotion_targets(conn, promotion).await?;
+    Ok(inserted)
 }
 
-fn row_to_business(row: &tokio_postgres::Row) -> Business {
-    Business {
-        id: row.get("id"),
-        registration_id: row.get("registration_id"),
-        owner_user_id: row.get("owner_user_id"),
-        business_name: row.get("business_name"),
-        category: row.get("category"),
-        tax_id: row.get("tax_id"),
-        description: row.get("description"),
-        website: row.get("website"),
-        logo_url: row.get("logo_url"),
-        is_active: row.get("is_active"),
-        metadata: row.get("metadata"),
-        created_at: row.get("created_at"),
-        updated_at: row.get("updated_at"),
-    }
+/// Join rows for the locations `promotion` targets
+async fn insert_promotion_targets(conn: &mut PgConnection, promotion: &NewBusinessPromotion) -> Result<(), Error> {
+    sqlx::query!(
+        "INSERT INTO business_promotion_locations (promotion_id, location_id) SELECT $1, unnest($2::uuid[])",
+        promotion.id, &promotion.location_ids,
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
 }
 
-fn row_to_business_registration(row: &tokio_postgres::Row) -> BusinessRegistration {
-    BusinessRegistration {
-        id: row.get("id"),
-        user_id: row.get("user_id"),
-        business_id: row.get("business_id"),
-        name: row.get("name"),
-        category: row.get("category"),
-        address: row.get("address"),
-        description: row.get("description"),
-        phone: row.get("phone"),
-        website: row.get("website"),
-        tax_id: row.get("tax_id"),
-        document_urls: row.get("document_urls"),
-        is_multi_user_team: row.get("is_multi_user_team"),
-        status: row.get("status"),
-        owner_email: row.get("owner_email"),
-        owner_username: row.get("owner_username"),
-        rejection_reason: row.get("rejection_reason"),
-        reviewer_notes: row.get("reviewer_notes"),
-        reviewer_id: row.get("reviewer_id"),
-        reviewer_name: row.get("reviewer_name"),
-        submitted_at: row.get("submitted_at"),
-        updated_at: row.get("updated_at"),
+/// Fail with a `not_in_business` field error for every id in `location_ids`
+/// that is not a live location of `business_id`. The locations found are
+/// locked against deletion until the transaction ends.
+async fn check_business_locations(conn: &mut PgConnection, business_id: Uuid, location_ids: &[Uuid]) -> Result<(), Error> {
+    if location_ids.is_empty() {
+        return Ok(());
+    }
+    let found: HashSet<Uuid> = sqlx::query_scalar!(
+        "SELECT id FROM business_locations WHERE business_id = $1 AND id = ANY($2) AND deleted_at IS NULL FOR SHARE",
+        business_id, location_ids,
+    )
+    .fetch_all(conn)
+    .await?
+    .into_iter()
+    .collect();
+    let foreign: Vec<FieldError> = location_ids
+        .iter()
+        .filter(|location_id| !found.contains(location_id))
+        .map(|location_id| {
+            let params = BTreeMap::from([("location_id".to_string(), json!(location_id))]);
+            FieldError::new("location_ids", "not_in_business", params)
+        })
+        .collect();
+    if foreign.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Validation(foreign))
     }
 }
-
-fn row_to_pending_business_review(row: &tokio_postgres::Row) -> PendingBusinessReview {
-    PendingBusinessReview {
-        id: row.get("id"),
-        name: row.get("name"),
-        category: row.get("category"),
-        address: row.get("address"),
-        tax_id: row.get("tax_id"),
-        document_urls: row.get("document_urls"),
-        submitted_at: row.get("submitted_at"),
-        owner_email: row.get("owner_email"),
-        owner_username: row.get("owner_username"),
-    }
-}
\ No newline at end of file
@@ -0,0 +1,140 @@
+use sqlx::PgConnection;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{webhooks::enqueue_webhook_deliveries, DatabaseService, Error};
+use crate::events::{DomainEvent, DomainEventPayload, EventPublisher};
+
+/// Advisory lock held while publishing, so that only one replica relays to the
+/// broker at a time and events keep their commit order.
+const OUTBOX_PUBLISH_LOCK: i64 = 0x0B0B_0001;
+
+/// Append events to the outbox as part of the caller's transaction.
+pub(super) async fn enqueue_events(
+    conn: &mut PgConnection,
+    payloads: impl IntoIterator<Item = DomainEventPayload>,
+) -> Result<(), Error> {
+    for payload in payloads {
+        let event = DomainEvent::new(payload);
+        let body = serde_json::to_value(&event)?;
+        sqlx::query!(
+            "INSERT INTO event_outbox (id, event_type, schema_version, aggregate_type, aggregate_id, payload, occurred_at) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            event.id, event.event_type(), event.schema_version, event.aggregate_type, event.aggregate_id, body, event.occurred_at,
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+impl DatabaseService {
+    /// Turn up to `limit` outbox events into webhook deliveries.
+    /// Returns the number of outbox events processed.
+    #[instrument(skip_all)]
+    pub async fn dispatch_outbox_webhooks(&self, limit: i64) -> Result<usize, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let rows = sqlx::query!(
+            "SELECT id, payload FROM event_outbox WHERE webhooks_dispatched_at IS NULL ORDER BY sequence ASC LIMIT $1 FOR UPDATE SKIP LOCKED",
+            limit,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut processed: Vec<Uuid> = Vec::with_capacity(rows.len());
+        for row in rows {
+            match serde_json::from_value::<DomainEvent>(row.payload) {
+                Ok(event) => {
+                    if let Some(webhook_event) = event.to_webhook_event() {
+                        enqueue_webhook_deliveries(&mut tx, &webhook_event).await?;
+                    }
+                }
+                Err(err) => log::error!("Skipping undecodable outbox event {}: {err}", row.id),
+            }
+            processed.push(row.id);
+        }
+
+        if !processed.is_empty() {
+            sqlx::query!("UPDATE event_outbox SET webhooks_dispatched_at = NOW() WHERE id = ANY($1)", &processed)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(processed.len())
+    }
+
+    /// Publish up to `limit` outbox events in commit order.
+    ///
+    /// Stops at the first failure so later events are never published ahead
+    /// of an earlier one; the failed event is retried on the next cycle.
+    /// Returns the number of events published.
+    #[instrument(skip_all)]
+    pub async fn publish_outbox_events(
+        &self,
+        publisher: &dyn EventPublisher,
+        limit: i64,
+    ) -> Result<usize, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let locked = sqlx::query_scalar!(r#"SELECT pg_try_advisory_xact_lock($1) AS "locked!""#, OUTBOX_PUBLISH_LOCK)
+            .fetch_one(&mut *tx)
+            .await?;
+        if !locked {
+            return Ok(0);
+        }
+
+        let rows = sqlx::query!(
+            "SELECT id, payload FROM event_outbox WHERE published_at IS NULL ORDER BY sequence ASC LIMIT $1",
+            limit,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut published: Vec<Uuid> = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id = row.id;
+            let result = match serde_json::from_value::<DomainEvent>(row.payload) {
+                Ok(event) => publisher.publish(&event).await.map_err(|e| e.to_string()),
+                Err(err) => Err(format!("Undecodable outbox payload: {err}")),
+            };
+
+            if let Err(message) = result {
+                log::warn!("Failed to publish outbox event {} via {}: {}", id, publisher.name(), message);
+                sqlx::query!(
+                    "UPDATE event_outbox SET publish_attempts = publish_attempts + 1, last_error = $2 WHERE id = $1",
+                    id, message,
+                )
+                .execute(&mut *tx)
+                .await?;
+                break;
+            }
+            published.push(id);
+        }
+
+        if !published.is_empty() {
+            sqlx::query!(
+                "UPDATE event_outbox SET published_at = NOW(), publish_attempts = publish_attempts + 1, last_error = NULL WHERE id = ANY($1)",
+                &published,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(published.len())
+    }
+
+    /// Delete fully processed outbox rows older than `retention_days`.
+    /// Rows are only purged once `publish_required` is satisfied.
+    #[instrument(skip_all)]
+    pub async fn purge_outbox(&self, retention_days: i32, publish_required: bool) -> Result<u64, Error> {
+        let deleted = sqlx::query!(
+            "DELETE FROM event_outbox WHERE created_at < NOW() - make_interval(days => $1) AND webhooks_dispatched_at IS NOT NULL AND (published_at IS NOT NULL OR NOT $2)",
+            retention_days, publish_required,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(deleted.rows_affected())
+    }
+}
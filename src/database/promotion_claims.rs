@@ -0,0 +1,85 @@
+use chrono::Utc;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{DatabaseService, Error};
+use crate::errors::{AppError, FieldError};
+use crate::models::{
+    BusinessPromotionStatus, PromotionClaim, PromotionLimitScope, PromotionLocation, PromotionTargetScope,
+};
+
+impl DatabaseService {
+    /// Live locations a promotion applies to, with the claims recorded at each
+    #[instrument(skip_all)]
+    pub async fn list_promotion_locations(&self, promotion_id: Uuid) -> Result<Vec<PromotionLocation>, Error> {
+        Ok(sqlx::query_as!(
+            PromotionLocation,
+            r#"SELECT l.id AS location_id, l.location_name, COALESCE(t.total_claims, 0) AS "total_claims!" FROM business_promotions p INNER JOIN business_locations l ON l.business_id = p.business_id LEFT JOIN business_promotion_locations t ON t.promotion_id = p.id AND t.location_id = l.id WHERE p.id = $1 AND l.deleted_at IS NULL AND (p.target_scope = 'business' OR t.location_id IS NOT NULL) ORDER BY l.location_name, l.id"#,
+            promotion_id,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Count one claim of an active promotion at `location_id`, enforcing
+    /// `max_claims` across all locations or per location depending on the
+    /// promotion's `limit_scope`. Claims of the same promotion are serialized
+    /// on its row, so the limit holds under concurrency.
+    #[instrument(skip_all)]
+    pub async fn claim_promotion(&self, promotion_id: Uuid, location_id: Uuid) -> Result<PromotionClaim, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let promotion = query_promotion!("SELECT", "FROM business_promotions WHERE id = $1 AND deleted_at IS NULL FOR UPDATE", promotion_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Promotion"))?;
+        let now = Utc::now();
+        if promotion.status != BusinessPromotionStatus::Active || now < promotion.starts_at || now >= promotion.ends_at {
+            return Err(AppError::PromotionNotClaimable);
+        }
+
+        let location_claims = sqlx::query_scalar!(
+            r#"SELECT COALESCE(t.total_claims, 0) AS "total_claims!" FROM business_locations l LEFT JOIN business_promotion_locations t ON t.promotion_id = $2 AND t.location_id = l.id WHERE l.id = $1 AND l.business_id = $3 AND l.deleted_at IS NULL AND ($4 OR t.location_id IS NOT NULL)"#,
+            location_id, promotion.id, promotion.business_id, promotion.target_scope == PromotionTargetScope::Business,
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Validation(vec![FieldError::new("location_id", "not_targeted", Default::default())]))?;
+
+        let counted = match promotion.limit_scope {
+            PromotionLimitScope::Global => promotion.total_claims,
+            PromotionLimitScope::PerLocation => location_claims,
+        };
+        if let Some(max_claims) = promotion.max_claims.filter(|max_claims| counted >= *max_claims) {
+            return Err(AppError::ClaimLimitReached(max_claims));
+        }
+
+        let total_claims = sqlx::query_scalar!(
+            "UPDATE business_promotions SET total_claims = total_claims + 1 WHERE id = $1 RETURNING total_claims",
+            promotion.id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let location_claims = sqlx::query_scalar!(
+            "INSERT INTO business_promotion_locations (promotion_id, location_id, total_claims) VALUES ($1, $2, 1) ON CONFLICT (promotion_id, location_id) DO UPDATE SET total_claims = business_promotion_locations.total_claims + 1 RETURNING total_claims",
+            promotion.id, location_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        let counted = match promotion.limit_scope {
+            PromotionLimitScope::Global => total_claims,
+            PromotionLimitScope::PerLocation => location_claims,
+        };
+        Ok(PromotionClaim {
+            promotion_id: promotion.id,
+            location_id,
+            limit_scope: promotion.limit_scope,
+            max_claims: promotion.max_claims,
+            total_claims,
+            location_claims,
+            remaining_claims: promotion.max_claims.map(|max_claims| (max_claims - counted).max(0)),
+        })
+    }
+}
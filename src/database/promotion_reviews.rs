@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{audit, outbox, DatabaseService, Error};
+use crate::audit::{AuditAction, AuditContext, AuditEntity};
+use crate::errors::AppError;
+use crate::events::DomainEventPayload;
+use crate::models::{
+    Business, BusinessPromotion, BusinessPromotionStatus, PendingPromotionReview, PromotionReviewEvent,
+    ReviewAction,
+};
+
+impl DatabaseService {
+    /// Promotions waiting for moderation, oldest submission first
+    #[instrument(skip_all)]
+    pub async fn list_pending_promotion_reviews(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PendingPromotionReview>, Error> {
+        let promotions = query_promotion!(
+            "SELECT",
+            "FROM business_promotions p WHERE p.status = 'pending_review' AND p.deleted_at IS NULL AND EXISTS (SELECT 1 FROM businesses b WHERE b.id = p.business_id AND b.deleted_at IS NULL) ORDER BY p.updated_at ASC, p.id LIMIT $1 OFFSET $2",
+            limit, offset,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let business_ids: Vec<Uuid> = promotions.iter().map(|p| p.business_id).collect();
+        let business_names: HashMap<Uuid, String> =
+            sqlx::query!("SELECT id, business_name FROM businesses WHERE id = ANY($1)", &business_ids)
+                .fetch_all(&self.pool)
+                .await?
+                .into_iter()
+                .map(|row| (row.id, row.business_name))
+                .collect();
+
+        Ok(promotions
+            .into_iter()
+            .map(|promotion| PendingPromotionReview {
+                business_name: business_names.get(&promotion.business_id).cloned().unwrap_or_default(),
+                promotion,
+            })
+            .collect())
+    }
+
+    #[instrument(skip_all)]
+    pub async fn list_promotion_review_events(&self, promotion_id: Uuid) -> Result<Vec<PromotionReviewEvent>, Error> {
+        Ok(sqlx::query_as!(
+            PromotionReviewEvent,
+            r#"SELECT id, promotion_id, reviewer_id, reviewer_name, action AS "action: ReviewAction", notes, rejection_reason, created_at FROM promotion_review_events WHERE promotion_id = $1 ORDER BY created_at ASC"#,
+            promotion_id,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Record a moderation decision. Approving schedules or activates the
+    /// promotion depending on its dates; rejecting leaves it `rejected` until
+    /// it is edited and submitted again. Both require `pending_review`, and
+    /// nothing of a suspended business is approved.
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all)]
+    pub async fn review_promotion(
+        &self,
+        promotion_id: Uuid,
+        reviewer_id: Option<Uuid>,
+        reviewer_name: Option<String>,
+        action: ReviewAction,
+        notes: Option<String>,
+        rejection_reason: Option<String>,
+        ctx: &AuditContext,
+    ) -> Result<BusinessPromotion, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = query_promotion!("SELECT", "FROM business_promotions WHERE id = $1 AND deleted_at IS NULL FOR UPDATE", promotion_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Promotion"))?;
+
+        let decision = matches!(action, ReviewAction::Approve | ReviewAction::Reject);
+        if decision && before.status != BusinessPromotionStatus::PendingReview {
+            return Err(AppError::NotPendingReview);
+        }
+        if action == ReviewAction::Approve {
+            let suspended = sqlx::query_scalar!(
+                r#"SELECT EXISTS (SELECT 1 FROM businesses b INNER JOIN business_registration_requests r ON b.registration_id = r.id WHERE b.id = $1 AND r.status = 'suspended') AS "suspended!""#,
+                before.business_id,
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            if suspended {
+                return Err(AppError::BusinessSuspended);
+            }
+        }
+
+        let now = Utc::now();
+        let new_status = match action {
+            ReviewAction::Approve if before.ends_at <= now => BusinessPromotionStatus::Expired,
+            ReviewAction::Approve if before.starts_at > now => BusinessPromotionStatus::Scheduled,
+            ReviewAction::Approve => BusinessPromotionStatus::Active,
+            ReviewAction::Reject => BusinessPromotionStatus::Rejected,
+            _ => before.status,
+        };
+
+        sqlx::query!(
+            "INSERT INTO promotion_review_events (id, promotion_id, reviewer_id, reviewer_name, action, notes, rejection_reason) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            Uuid::new_v4(), promotion_id, reviewer_id, reviewer_name, action as _, notes, rejection_reason,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let promotion = if new_status == before.status {
+            before.clone()
+        } else {
+            query_promotion!(
+                "UPDATE business_promotions SET status = $2, updated_by = $3, version = version + 1, updated_at = NOW() WHERE id = $1 RETURNING",
+                "",
+                promotion_id, new_status as _, reviewer_id,
+            )
+            .fetch_one(&mut *tx)
+            .await?
+        };
+
+        audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion_id, AuditAction::Review(action), Some(&before), Some(&promotion)).await?;
+        if new_status != before.status {
+            let mut events = vec![DomainEventPayload::PromotionUpdated(promotion.clone())];
+            if promotion.status == BusinessPromotionStatus::Active {
+                events.push(DomainEventPayload::PromotionActivated(promotion.clone()));
+            }
+            outbox::enqueue_events(&mut tx, events).await?;
+        }
+        tx.commit().await?;
+
+        Ok(promotion)
+    }
+
+    /// Let a business publish promotions without moderation, or take it back
+    #[instrument(skip_all)]
+    pub async fn set_promotions_trusted(&self, business_id: Uuid, trusted: bool, ctx: &AuditContext) -> Result<Business, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = query_business!("SELECT", "FROM businesses WHERE id = $1 AND deleted_at IS NULL FOR UPDATE", business_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Business"))?;
+        if before.promotions_trusted == trusted {
+            return Ok(before);
+        }
+
+        let business = query_business!(
+            "UPDATE businesses SET promotions_trusted = $2, version = version + 1, updated_at = NOW() WHERE id = $1 RETURNING",
+            "",
+            business_id, trusted,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        audit::record(&mut tx, ctx, AuditEntity::Business, business_id, AuditAction::Update, Some(&before), Some(&business)).await?;
+        outbox::enqueue_events(&mut tx, [DomainEventPayload::BusinessUpdated(business.clone())]).await?;
+        tx.commit().await?;
+
+        Ok(business)
+    }
+}
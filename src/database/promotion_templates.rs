@@ -0,0 +1,150 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{audit, insert_promotion_targets, outbox, DatabaseService, Error};
+use crate::audit::{AuditAction, AuditContext, AuditEntity};
+use crate::content_policy::ContentPolicy;
+use crate::errors::AppError;
+use crate::events::DomainEventPayload;
+use crate::models::{NewPromotionTemplate, PromotionTemplate};
+use crate::moderation::PromotionModeration;
+use crate::recurrence::Recurrence;
+
+impl DatabaseService {
+    #[instrument(skip_all)]
+    pub async fn create_promotion_template(
+        &self,
+        template: NewPromotionTemplate,
+        ctx: &AuditContext,
+    ) -> Result<PromotionTemplate, Error> {
+        let mut tx = self.pool.begin().await?;
+        let template = query_promotion_template!(
+            "INSERT INTO promotion_templates (id, location_id, title, subtitle, description, promotion_type, image_url, prize, reward_points, discount_percent, max_claims, per_user_limit, requires_check_in, requires_purchase, terms, metadata, rrule, dtstart, duration_minutes, timezone, created_by) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21) RETURNING",
+            "",
+            template.id, template.location_id, template.title, template.subtitle, template.description, template.promotion_type as _, template.image_url, template.prize, template.reward_points, template.discount_percent, template.max_claims, template.per_user_limit, template.requires_check_in, template.requires_purchase, template.terms, template.metadata, template.rrule, template.dtstart, template.duration_minutes, template.timezone, template.created_by,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        audit::record(&mut tx, ctx, AuditEntity::PromotionTemplate, template.id, AuditAction::Create, None, Some(&template)).await?;
+        tx.commit().await?;
+
+        Ok(template)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn get_promotion_template(&self, template_id: Uuid) -> Result<Option<PromotionTemplate>, Error> {
+        Ok(query_promotion_template!("SELECT", "FROM promotion_templates WHERE id = $1", template_id)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn list_promotion_templates_for_location(&self, location_id: Uuid) -> Result<Vec<PromotionTemplate>, Error> {
+        Ok(query_promotion_template!("SELECT", "FROM promotion_templates WHERE location_id = $1 ORDER BY created_at DESC", location_id)
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    /// Delete a template. Promotions it already materialized are kept.
+    #[instrument(skip_all)]
+    pub async fn delete_promotion_template(&self, template_id: Uuid, ctx: &AuditContext) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
+        let template = query_promotion_template!("DELETE FROM promotion_templates WHERE id = $1 RETURNING", "", template_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Promotion template"))?;
+
+        audit::record(&mut tx, ctx, AuditEntity::PromotionTemplate, template.id, AuditAction::Delete, Some(&template), None).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Active templates of live locations of active businesses that still have
+    /// occurrences to materialize before `until`
+    #[instrument(skip_all)]
+    pub async fn promotion_templates_due(&self, until: DateTime<Utc>) -> Result<Vec<Uuid>, Error> {
+        Ok(sqlx::query_scalar!(
+            "SELECT t.id FROM promotion_templates t INNER JOIN business_locations l ON t.location_id = l.id INNER JOIN businesses b ON l.business_id = b.id WHERE t.is_active AND l.deleted_at IS NULL AND b.deleted_at IS NULL AND b.is_active AND (t.materialized_until IS NULL OR t.materialized_until < $1) ORDER BY t.id",
+            until,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Create the promotions for occurrences of a template that start between
+    /// now (or where the last run stopped) and `until`. Occurrences that
+    /// already have a promotion, even a deleted one, are skipped. Returns the
+    /// number of promotions created; templates locked by another instance are
+    /// left alone. Occurrences of untrusted businesses go through moderation
+    /// like any other new promotion.
+    #[instrument(skip_all)]
+    pub async fn materialize_promotion_template(
+        &self,
+        template_id: Uuid,
+        until: DateTime<Utc>,
+        moderation: PromotionModeration,
+        content_policy: &ContentPolicy,
+        ctx: &AuditContext,
+    ) -> Result<usize, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(template) = query_promotion_template!(
+            "SELECT",
+            "FROM promotion_templates WHERE id = $1 AND is_active FOR UPDATE SKIP LOCKED",
+            template_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(0);
+        };
+        let business = sqlx::query!(
+            "SELECT b.id, b.promotions_trusted FROM business_locations l INNER JOIN businesses b ON l.business_id = b.id WHERE l.id = $1",
+            template.location_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let (business_id, promotions_trusted) = (business.id, business.promotions_trusted);
+        let recurrence = Recurrence::parse(&template.rrule)
+            .map_err(|err| AppError::Internal(format!("template {template_id} has an invalid rrule: {err}")))?;
+        let tz: Tz = template
+            .timezone
+            .parse()
+            .map_err(|err| AppError::Internal(format!("template {template_id} has an invalid timezone: {err}")))?;
+
+        let now = Utc::now();
+        let from = template.materialized_until.map_or(now, |done| done.max(now));
+        let mut created = 0;
+        let mut events = Vec::new();
+        for starts_at in recurrence.occurrences(template.dtstart, tz, from, until) {
+            let mut new_promotion = template.occurrence(business_id, starts_at);
+            new_promotion.content_findings = content_policy.check(&new_promotion);
+            new_promotion.status = moderation.gate_new(promotions_trusted, new_promotion.status);
+            let Some(promotion) = insert_promotion!(
+                &new_promotion,
+                "ON CONFLICT ((metadata->>'template_id'), starts_at) WHERE metadata ? 'template_id' DO NOTHING"
+            )
+            .fetch_optional(&mut *tx)
+            .await?
+            else {
+                continue;
+            };
+            insert_promotion_targets(&mut tx, &new_promotion).await?;
+            audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion.id, AuditAction::Create, None, Some(&promotion)).await?;
+            events.push(DomainEventPayload::PromotionCreated(promotion));
+            created += 1;
+        }
+
+        outbox::enqueue_events(&mut tx, events).await?;
+        sqlx::query!("UPDATE promotion_templates SET materialized_until = $2 WHERE id = $1", template_id, until)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(created)
+    }
+}
@@ -0,0 +1,39 @@
+use tracing::instrument;
+
+use super::{DatabaseService, Error};
+
+impl DatabaseService {
+    /// Refill the bucket for `key` by the time elapsed since its last use and
+    /// take one token if there is one. Returns whether the request is allowed
+    /// and the tokens left afterwards.
+    #[instrument(skip_all)]
+    pub async fn take_rate_limit_token(&self, key: &str, capacity: f64, refill_per_sec: f64) -> Result<(bool, f64), Error> {
+        let row = sqlx::query!(
+            "INSERT INTO rate_limit_buckets AS b (key, tokens, allowed, updated_at) VALUES ($1, $2::float8 - 1, TRUE, NOW()) \
+             ON CONFLICT (key) DO UPDATE SET \
+                 allowed = LEAST($2, b.tokens + EXTRACT(EPOCH FROM NOW() - b.updated_at) * $3::float8) >= 1, \
+                 tokens = LEAST($2, b.tokens + EXTRACT(EPOCH FROM NOW() - b.updated_at) * $3::float8) \
+                     - CASE WHEN LEAST($2, b.tokens + EXTRACT(EPOCH FROM NOW() - b.updated_at) * $3::float8) >= 1 THEN 1 ELSE 0 END, \
+                 updated_at = NOW() \
+             RETURNING allowed, tokens",
+            key, capacity, refill_per_sec,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((row.allowed, row.tokens))
+    }
+
+    /// Drop buckets idle for longer than `idle_secs`; they would be full again
+    #[instrument(skip_all)]
+    pub async fn prune_rate_limit_buckets(&self, idle_secs: f64) -> Result<u64, Error> {
+        let deleted = sqlx::query!(
+            "DELETE FROM rate_limit_buckets WHERE updated_at < NOW() - make_interval(secs => $1)",
+            idle_secs,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(deleted.rows_affected())
+    }
+}
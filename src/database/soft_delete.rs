@@ -0,0 +1,194 @@
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{audit, outbox, DatabaseService, Error};
+use crate::audit::{AuditAction, AuditContext, AuditEntity};
+use crate::errors::AppError;
+use crate::events::DomainEventPayload;
+use crate::models::{Business, BusinessLocation, BusinessPromotion};
+
+impl DatabaseService {
+    /// Undo `delete_business`, including the locations and promotions that were
+    /// deleted in the same cascade (same `deleted_at`). Children deleted on
+    /// their own before the business stay deleted.
+    #[instrument(skip_all)]
+    pub async fn restore_business(&self, business_id: Uuid, ctx: &AuditContext) -> Result<Business, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = query_business!("SELECT", "FROM businesses WHERE id = $1 AND deleted_at IS NOT NULL FOR UPDATE", business_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Business"))?;
+
+        let business = query_business!(
+            "UPDATE businesses SET deleted_at = NULL, deleted_by = NULL, version = version + 1, updated_at = NOW() WHERE id = $1 RETURNING",
+            "",
+            business_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let locations = query_location!(
+            "UPDATE business_locations SET deleted_at = NULL, deleted_by = NULL, version = version + 1, updated_at = NOW() WHERE business_id = $1 AND deleted_at = $2 RETURNING",
+            "",
+            business_id, before.deleted_at,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let promotions = query_promotion!(
+            "UPDATE business_promotions SET deleted_at = NULL, deleted_by = NULL, version = version + 1, updated_at = NOW() WHERE business_id = $1 AND deleted_at = $2 RETURNING",
+            "",
+            business_id, before.deleted_at,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        audit::record(&mut tx, ctx, AuditEntity::Business, business_id, AuditAction::Restore, Some(&before), Some(&business)).await?;
+        for location in &locations {
+            audit::record(&mut tx, ctx, AuditEntity::Location, location.id, AuditAction::Restore, None, Some(location)).await?;
+        }
+        for promotion in &promotions {
+            audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion.id, AuditAction::Restore, None, Some(promotion)).await?;
+        }
+
+        let events = [DomainEventPayload::BusinessRestored(business.clone())]
+            .into_iter()
+            .chain(locations.into_iter().map(DomainEventPayload::LocationRestored))
+            .chain(promotions.into_iter().map(DomainEventPayload::PromotionRestored));
+        outbox::enqueue_events(&mut tx, events).await?;
+        tx.commit().await?;
+
+        Ok(business)
+    }
+
+    /// Undo `delete_location`, including the promotions deleted with it.
+    /// Refused while the parent business is deleted.
+    #[instrument(skip_all)]
+    pub async fn restore_location(&self, location_id: Uuid, ctx: &AuditContext) -> Result<BusinessLocation, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = query_location!("SELECT", "FROM business_locations WHERE id = $1 AND deleted_at IS NOT NULL FOR UPDATE", location_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Location"))?;
+        let business_deleted = sqlx::query_scalar!(
+            r#"SELECT deleted_at IS NOT NULL AS "deleted!" FROM businesses WHERE id = $1"#,
+            before.business_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if business_deleted {
+            return Err(AppError::ParentDeleted("Business"));
+        }
+
+        let location = query_location!(
+            "UPDATE business_locations SET deleted_at = NULL, deleted_by = NULL, version = version + 1, updated_at = NOW() WHERE id = $1 RETURNING",
+            "",
+            location_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let promotions = query_promotion!(
+            "UPDATE business_promotions SET deleted_at = NULL, deleted_by = NULL, version = version + 1, updated_at = NOW() WHERE location_id = $1 AND deleted_at = $2 RETURNING",
+            "",
+            location_id, before.deleted_at,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        audit::record(&mut tx, ctx, AuditEntity::Location, location_id, AuditAction::Restore, Some(&before), Some(&location)).await?;
+        for promotion in &promotions {
+            audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion.id, AuditAction::Restore, None, Some(promotion)).await?;
+        }
+
+        let events = [DomainEventPayload::LocationRestored(location.clone())]
+            .into_iter()
+            .chain(promotions.into_iter().map(DomainEventPayload::PromotionRestored));
+        outbox::enqueue_events(&mut tx, events).await?;
+        tx.commit().await?;
+
+        Ok(location)
+    }
+
+    /// Undo `delete_promotion`. Refused while its business or, for
+    /// single-location promotions, its location is deleted.
+    #[instrument(skip_all)]
+    pub async fn restore_promotion(&self, promotion_id: Uuid, ctx: &AuditContext) -> Result<BusinessPromotion, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = query_promotion!("SELECT", "FROM business_promotions WHERE id = $1 AND deleted_at IS NOT NULL FOR UPDATE", promotion_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound("Promotion"))?;
+        let parents = sqlx::query!(
+            r#"SELECT b.deleted_at IS NOT NULL AS "business_deleted!", COALESCE((SELECT l.deleted_at IS NOT NULL FROM business_locations l WHERE l.id = $2), FALSE) AS "location_deleted!" FROM businesses b WHERE b.id = $1"#,
+            before.business_id, before.location_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if parents.business_deleted {
+            return Err(AppError::ParentDeleted("Business"));
+        }
+        if parents.location_deleted {
+            return Err(AppError::ParentDeleted("Location"));
+        }
+
+        let promotion = query_promotion!(
+            "UPDATE business_promotions SET deleted_at = NULL, deleted_by = NULL, version = version + 1, updated_at = NOW() WHERE id = $1 RETURNING",
+            "",
+            promotion_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion_id, AuditAction::Restore, Some(&before), Some(&promotion)).await?;
+        outbox::enqueue_events(&mut tx, [DomainEventPayload::PromotionRestored(promotion.clone())]).await?;
+        tx.commit().await?;
+
+        Ok(promotion)
+    }
+
+    /// Hard-delete rows soft-deleted more than `retention_days` ago.
+    ///
+    /// Children go first so each removed row gets its own audit entry instead
+    /// of disappearing through `ON DELETE CASCADE`.
+    #[instrument(skip_all)]
+    pub async fn purge_soft_deleted(&self, retention_days: i32, ctx: &AuditContext) -> Result<u64, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let promotions = query_promotion!(
+            "DELETE FROM business_promotions p WHERE p.deleted_at < NOW() - make_interval(days => $1) OR EXISTS (SELECT 1 FROM businesses b WHERE b.id = p.business_id AND b.deleted_at < NOW() - make_interval(days => $1)) OR EXISTS (SELECT 1 FROM business_locations l WHERE l.id = p.location_id AND l.deleted_at < NOW() - make_interval(days => $1)) RETURNING",
+            "",
+            retention_days,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for promotion in &promotions {
+            audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion.id, AuditAction::Purge, Some(promotion), None).await?;
+        }
+
+        let locations = query_location!(
+            "DELETE FROM business_locations l WHERE l.deleted_at < NOW() - make_interval(days => $1) OR EXISTS (SELECT 1 FROM businesses b WHERE b.id = l.business_id AND b.deleted_at < NOW() - make_interval(days => $1)) RETURNING",
+            "",
+            retention_days,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for location in &locations {
+            audit::record(&mut tx, ctx, AuditEntity::Location, location.id, AuditAction::Purge, Some(location), None).await?;
+        }
+
+        let businesses = query_business!(
+            "DELETE FROM businesses WHERE deleted_at < NOW() - make_interval(days => $1) RETURNING",
+            "",
+            retention_days,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for business in &businesses {
+            audit::record(&mut tx, ctx, AuditEntity::Business, business.id, AuditAction::Purge, Some(business), None).await?;
+        }
+
+        tx.commit().await?;
+        Ok((promotions.len() + locations.len() + businesses.len()) as u64)
+    }
+}
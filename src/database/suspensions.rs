@@ -0,0 +1,403 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{audit, outbox, DatabaseService, Error};
+use crate::audit::{AuditAction, AuditContext, AuditEntity};
+use crate::errors::AppError;
+use crate::events::{DomainEventPayload, SuspensionNotice};
+use crate::models::{
+    BusinessLocation, BusinessPromotion, BusinessPromotionStatus, BusinessRegistration, BusinessSuspension,
+    BusinessVerificationStatus, ReviewAction, SuspendedPromotion,
+};
+
+/// Reviewer of a suspension or resumption; `None` for the expiry job
+pub struct SuspensionActor {
+    pub id: Option<Uuid>,
+    pub name: Option<String>,
+}
+
+impl DatabaseService {
+    /// Suspend a registration and everything customers can see of its
+    /// business: the business is deactivated, its active locations hidden and
+    /// its scheduled or active promotions paused (or cancelled). What was
+    /// changed is recorded so `resume_registration` can undo exactly that.
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all)]
+    pub async fn suspend_registration(
+        &self,
+        registration_id: Uuid,
+        actor: SuspensionActor,
+        notes: Option<String>,
+        expires_at: Option<DateTime<Utc>>,
+        cancel_promotions: bool,
+        ctx: &AuditContext,
+    ) -> Result<BusinessRegistration, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = lock_registration(&mut tx, registration_id).await?;
+        if before.status == BusinessVerificationStatus::Suspended {
+            return Err(AppError::AlreadySuspended);
+        }
+        let registration = record_status_change(
+            &mut tx,
+            registration_id,
+            &actor,
+            ReviewAction::Suspend,
+            notes.clone(),
+            BusinessVerificationStatus::Suspended,
+        )
+        .await?;
+
+        let mut events = vec![DomainEventPayload::BusinessSuspended(registration.clone())];
+        let business = query_business!("SELECT", "FROM businesses WHERE registration_id = $1 AND deleted_at IS NULL FOR UPDATE", registration_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let mut business_was_active = false;
+        let mut hidden_location_ids = Vec::new();
+        let mut promotions = Vec::new();
+        if let Some(business) = &business {
+            if let Some(updated) = query_business!(
+                "UPDATE businesses SET is_active = FALSE, version = version + 1, updated_at = NOW() WHERE id = $1 AND is_active RETURNING",
+                "",
+                business.id,
+            )
+            .fetch_optional(&mut *tx)
+            .await?
+            {
+                audit::record(&mut tx, ctx, AuditEntity::Business, business.id, AuditAction::Update, Some(business), Some(&updated)).await?;
+                events.push(DomainEventPayload::BusinessUpdated(updated));
+                business_was_active = true;
+            }
+
+            let visible: HashMap<Uuid, BusinessLocation> = query_location!(
+                "SELECT",
+                "FROM business_locations WHERE business_id = $1 AND is_active AND deleted_at IS NULL FOR UPDATE",
+                business.id,
+            )
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|location| (location.id, location))
+            .collect();
+            let visible_ids: Vec<Uuid> = visible.keys().copied().collect();
+            for location in query_location!(
+                "UPDATE business_locations SET is_active = FALSE, version = version + 1, updated_at = NOW() WHERE id = ANY($1) RETURNING",
+                "",
+                &visible_ids,
+            )
+            .fetch_all(&mut *tx)
+            .await?
+            {
+                hidden_location_ids.push(location.id);
+                audit::record(&mut tx, ctx, AuditEntity::Location, location.id, AuditAction::Update, visible.get(&location.id), Some(&location)).await?;
+                events.push(DomainEventPayload::LocationUpdated(location));
+            }
+
+            let new_status = if cancel_promotions {
+                BusinessPromotionStatus::Cancelled
+            } else {
+                BusinessPromotionStatus::Paused
+            };
+            let published: HashMap<Uuid, BusinessPromotion> = query_promotion!(
+                "SELECT",
+                "FROM business_promotions WHERE business_id = $1 AND status IN ('scheduled', 'active') AND deleted_at IS NULL FOR UPDATE",
+                business.id,
+            )
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|promotion| (promotion.id, promotion))
+            .collect();
+            let published_ids: Vec<Uuid> = published.keys().copied().collect();
+            for promotion in query_promotion!(
+                "UPDATE business_promotions SET status = $2, version = version + 1, updated_at = NOW() WHERE id = ANY($1) RETURNING",
+                "",
+                &published_ids, new_status as _,
+            )
+            .fetch_all(&mut *tx)
+            .await?
+            {
+                let before = &published[&promotion.id];
+                promotions.push(SuspendedPromotion {
+                    promotion_id: promotion.id,
+                    previous_status: before.status,
+                });
+                audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion.id, AuditAction::Update, Some(before), Some(&promotion)).await?;
+                events.push(DomainEventPayload::PromotionUpdated(promotion));
+            }
+        }
+
+        let suspension_id = Uuid::new_v4();
+        sqlx::query!(
+            "INSERT INTO business_suspensions (id, registration_id, business_id, previous_status, business_was_active, hidden_location_ids, cancel_promotions, reason, suspended_by, suspended_by_name, expires_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+            suspension_id, registration_id, business.as_ref().map(|b| b.id), before.status as _, business_was_active, &hidden_location_ids, cancel_promotions, notes, actor.id, actor.name, expires_at,
+        )
+        .execute(&mut *tx)
+        .await?;
+        let promotion_ids: Vec<Uuid> = promotions.iter().map(|p| p.promotion_id).collect();
+        let previous_statuses: Vec<BusinessPromotionStatus> = promotions.iter().map(|p| p.previous_status).collect();
+        sqlx::query!(
+            "INSERT INTO business_suspension_promotions (suspension_id, promotion_id, previous_status) SELECT $1, * FROM unnest($2::uuid[], $3::business_promotion_status[])",
+            suspension_id, &promotion_ids, previous_statuses as _,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let suspension = load_suspension(&mut tx, suspension_id).await?;
+        audit::record(&mut tx, ctx, AuditEntity::Registration, registration_id, AuditAction::Review(ReviewAction::Suspend), Some(&before), Some(&registration)).await?;
+        events.push(suspension_notice(&mut tx, suspension).await?);
+        outbox::enqueue_events(&mut tx, events).await?;
+        tx.commit().await?;
+
+        Ok(registration)
+    }
+
+    /// Lift the suspension in force: the registration gets its previous
+    /// status back, and the business, locations and paused promotions the
+    /// suspension took down are restored unless they were changed or deleted
+    /// since. Promotions whose end date passed meanwhile come back expired.
+    #[instrument(skip_all)]
+    pub async fn resume_registration(
+        &self,
+        registration_id: Uuid,
+        actor: SuspensionActor,
+        notes: Option<String>,
+        ctx: &AuditContext,
+    ) -> Result<BusinessRegistration, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = lock_registration(&mut tx, registration_id).await?;
+        if before.status != BusinessVerificationStatus::Suspended {
+            return Err(AppError::NotSuspended);
+        }
+        let suspension_id = sqlx::query_scalar!(
+            "SELECT id FROM business_suspensions WHERE registration_id = $1 AND resumed_at IS NULL FOR UPDATE",
+            registration_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(suspension_id) = suspension_id else {
+            // Suspended before suspensions were recorded: nothing to restore
+            let registration = record_status_change(&mut tx, registration_id, &actor, ReviewAction::Resume, notes, BusinessVerificationStatus::Approved).await?;
+            audit::record(&mut tx, ctx, AuditEntity::Registration, registration_id, AuditAction::Review(ReviewAction::Resume), Some(&before), Some(&registration)).await?;
+            outbox::enqueue_events(&mut tx, [DomainEventPayload::BusinessResumed(registration.clone())]).await?;
+            tx.commit().await?;
+            return Ok(registration);
+        };
+        let suspension = load_suspension(&mut tx, suspension_id).await?;
+
+        let registration = record_status_change(&mut tx, registration_id, &actor, ReviewAction::Resume, notes, suspension.previous_status).await?;
+        let mut events = vec![DomainEventPayload::BusinessResumed(registration.clone())];
+
+        if let Some(business_id) = suspension.business_id.filter(|_| suspension.business_was_active) {
+            let before_business = query_business!("SELECT", "FROM businesses WHERE id = $1 AND deleted_at IS NULL AND NOT is_active FOR UPDATE", business_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+            if let Some(before_business) = before_business {
+                let business = query_business!(
+                    "UPDATE businesses SET is_active = TRUE, version = version + 1, updated_at = NOW() WHERE id = $1 RETURNING",
+                    "",
+                    business_id,
+                )
+                .fetch_one(&mut *tx)
+                .await?;
+                audit::record(&mut tx, ctx, AuditEntity::Business, business_id, AuditAction::Update, Some(&before_business), Some(&business)).await?;
+                events.push(DomainEventPayload::BusinessUpdated(business));
+            }
+        }
+
+        let hidden: HashMap<Uuid, BusinessLocation> = query_location!(
+            "SELECT",
+            "FROM business_locations WHERE id = ANY($1) AND NOT is_active AND deleted_at IS NULL FOR UPDATE",
+            &suspension.hidden_location_ids,
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|location| (location.id, location))
+        .collect();
+        let hidden_ids: Vec<Uuid> = hidden.keys().copied().collect();
+        for location in query_location!(
+            "UPDATE business_locations SET is_active = TRUE, version = version + 1, updated_at = NOW() WHERE id = ANY($1) RETURNING",
+            "",
+            &hidden_ids,
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        {
+            audit::record(&mut tx, ctx, AuditEntity::Location, location.id, AuditAction::Update, hidden.get(&location.id), Some(&location)).await?;
+            events.push(DomainEventPayload::LocationUpdated(location));
+        }
+
+        let paused: HashMap<Uuid, BusinessPromotion> = query_promotion!(
+            "SELECT",
+            "FROM business_promotions WHERE id IN (SELECT promotion_id FROM business_suspension_promotions WHERE suspension_id = $1) AND status = 'paused' AND deleted_at IS NULL FOR UPDATE",
+            suspension_id,
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|promotion| (promotion.id, promotion))
+        .collect();
+        for promotion in query_promotion!(
+            "UPDATE business_promotions SET status = CASE WHEN ends_at <= NOW() THEN 'expired'::business_promotion_status ELSE previous_status END, version = version + 1, updated_at = NOW() FROM business_suspension_promotions WHERE suspension_id = $1 AND promotion_id = id AND status = 'paused' AND deleted_at IS NULL RETURNING",
+            "",
+            suspension_id,
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        {
+            let activated = promotion.status == BusinessPromotionStatus::Active;
+            audit::record(&mut tx, ctx, AuditEntity::Promotion, promotion.id, AuditAction::Update, paused.get(&promotion.id), Some(&promotion)).await?;
+            events.push(DomainEventPayload::PromotionUpdated(promotion.clone()));
+            if activated {
+                events.push(DomainEventPayload::PromotionActivated(promotion));
+            }
+        }
+
+        sqlx::query!(
+            "UPDATE business_suspensions SET resumed_at = NOW(), resumed_by = $2, resumed_by_name = $3 WHERE id = $1",
+            suspension_id, actor.id, actor.name,
+        )
+        .execute(&mut *tx)
+        .await?;
+        let suspension = load_suspension(&mut tx, suspension_id).await?;
+        audit::record(&mut tx, ctx, AuditEntity::Registration, registration_id, AuditAction::Review(ReviewAction::Resume), Some(&before), Some(&registration)).await?;
+        events.push(suspension_notice(&mut tx, suspension).await?);
+        outbox::enqueue_events(&mut tx, events).await?;
+        tx.commit().await?;
+
+        Ok(registration)
+    }
+
+    /// Suspensions of a registration, latest first
+    #[instrument(skip_all)]
+    pub async fn list_suspensions(&self, registration_id: Uuid) -> Result<Vec<BusinessSuspension>, Error> {
+        let mut conn = self.pool.acquire().await?;
+        let ids = sqlx::query_scalar!(
+            "SELECT id FROM business_suspensions WHERE registration_id = $1 ORDER BY suspended_at DESC",
+            registration_id,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut suspensions = Vec::with_capacity(ids.len());
+        for id in ids {
+            suspensions.push(load_suspension(&mut conn, id).await?);
+        }
+        Ok(suspensions)
+    }
+
+    /// Whether the registration behind a business is suspended
+    #[instrument(skip_all)]
+    pub async fn is_business_suspended(&self, business_id: Uuid) -> Result<bool, Error> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM businesses b INNER JOIN business_registration_requests r ON b.registration_id = r.id WHERE b.id = $1 AND r.status = 'suspended') AS "suspended!""#,
+            business_id,
+        )
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    /// Registrations whose suspension has reached its expiry time
+    #[instrument(skip_all)]
+    pub async fn expired_suspensions(&self, limit: i64) -> Result<Vec<Uuid>, Error> {
+        Ok(sqlx::query_scalar!(
+            "SELECT registration_id FROM business_suspensions WHERE resumed_at IS NULL AND expires_at <= NOW() ORDER BY expires_at LIMIT $1",
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+}
+
+async fn lock_registration(conn: &mut PgConnection, registration_id: Uuid) -> Result<BusinessRegistration, Error> {
+    query_registration!("SELECT", "FROM business_registration_requests WHERE id = $1 FOR UPDATE", registration_id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or(AppError::NotFound("Registration"))
+}
+
+/// Review event and new status of a suspension or resumption
+async fn record_status_change(
+    conn: &mut PgConnection,
+    registration_id: Uuid,
+    actor: &SuspensionActor,
+    action: ReviewAction,
+    notes: Option<String>,
+    new_status: BusinessVerificationStatus,
+) -> Result<BusinessRegistration, Error> {
+    sqlx::query!(
+        "INSERT INTO business_review_events (id, registration_id, reviewer_id, reviewer_name, action, notes) VALUES ($1, $2, $3, $4, $5, $6)",
+        Uuid::new_v4(), registration_id, actor.id, actor.name, action as _, notes,
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(query_registration!(
+        "UPDATE business_registration_requests SET status = $2, reviewer_notes = COALESCE($3, reviewer_notes), reviewer_id = COALESCE($4, reviewer_id), reviewer_name = COALESCE($5, reviewer_name), updated_at = NOW() WHERE id = $1 RETURNING",
+        "",
+        registration_id, new_status as _, notes, actor.id, actor.name,
+    )
+    .fetch_one(conn)
+    .await?)
+}
+
+async fn load_suspension(conn: &mut PgConnection, suspension_id: Uuid) -> Result<BusinessSuspension, Error> {
+    let row = sqlx::query!(
+        r#"SELECT id, registration_id, business_id, previous_status AS "previous_status: BusinessVerificationStatus", business_was_active, hidden_location_ids, cancel_promotions, reason, suspended_by, suspended_by_name, suspended_at, expires_at, resumed_at, resumed_by, resumed_by_name FROM business_suspensions WHERE id = $1"#,
+        suspension_id,
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    let promotions = sqlx::query_as!(
+        SuspendedPromotion,
+        r#"SELECT promotion_id, previous_status AS "previous_status: BusinessPromotionStatus" FROM business_suspension_promotions WHERE suspension_id = $1 ORDER BY promotion_id"#,
+        suspension_id,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(BusinessSuspension {
+        id: row.id,
+        registration_id: row.registration_id,
+        business_id: row.business_id,
+        previous_status: row.previous_status,
+        business_was_active: row.business_was_active,
+        hidden_location_ids: row.hidden_location_ids,
+        promotions,
+        cancel_promotions: row.cancel_promotions,
+        reason: row.reason,
+        suspended_by: row.suspended_by,
+        suspended_by_name: row.suspended_by_name,
+        suspended_at: row.suspended_at,
+        expires_at: row.expires_at,
+        resumed_at: row.resumed_at,
+        resumed_by: row.resumed_by,
+        resumed_by_name: row.resumed_by_name,
+    })
+}
+
+/// Notice for the business owner and its active location admins
+async fn suspension_notice(conn: &mut PgConnection, suspension: BusinessSuspension) -> Result<DomainEventPayload, Error> {
+    let recipient_user_ids = match suspension.business_id {
+        Some(business_id) => sqlx::query_scalar!(
+            r#"SELECT owner_user_id AS "user_id!" FROM businesses WHERE id = $1 UNION SELECT a.user_id FROM business_location_admins a INNER JOIN business_locations l ON a.location_id = l.id WHERE l.business_id = $1 AND a.is_active"#,
+            business_id,
+        )
+        .fetch_all(conn)
+        .await?,
+        None => sqlx::query_scalar!("SELECT user_id FROM business_registration_requests WHERE id = $1", suspension.registration_id)
+            .fetch_all(conn)
+            .await?,
+    };
+
+    Ok(DomainEventPayload::SuspensionNotice(SuspensionNotice {
+        suspension,
+        recipient_user_ids,
+    }))
+}
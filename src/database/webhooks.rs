@@ -0,0 +1,253 @@
+use sqlx::PgConnection;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{audit, DatabaseService, Error};
+use crate::audit::{AuditAction, AuditContext, AuditEntity};
+use crate::models::{
+    NewWebhookSubscription, PendingWebhookDelivery, WebhookDelivery, WebhookDeliveryStatus, WebhookEvent,
+    WebhookSubscription,
+};
+
+impl DatabaseService {
+    // ========================================================================
+    // WEBHOOK SUBSCRIPTIONS
+    // ========================================================================
+
+    #[instrument(skip_all)]
+    pub async fn create_webhook_subscription(
+        &self,
+        subscription: NewWebhookSubscription,
+        ctx: &AuditContext,
+    ) -> Result<WebhookSubscription, Error> {
+        let mut tx = self.pool.begin().await?;
+        let subscription = query_webhook_subscription!(
+            "INSERT INTO webhook_subscriptions (id, url, secret, event_types, description, created_by) VALUES ($1, $2, $3, $4, $5, $6) RETURNING",
+            "",
+            subscription.id, subscription.url, subscription.secret, &subscription.event_types as _, subscription.description, subscription.created_by,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        audit::record(&mut tx, ctx, AuditEntity::WebhookSubscription, subscription.id, AuditAction::Create, None, Some(&subscription)).await?;
+        tx.commit().await?;
+
+        Ok(subscription)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn get_webhook_subscription(
+        &self,
+        subscription_id: Uuid,
+    ) -> Result<Option<WebhookSubscription>, Error> {
+        Ok(query_webhook_subscription!("SELECT", "FROM webhook_subscriptions WHERE id = $1", subscription_id)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn list_webhook_subscriptions(&self) -> Result<Vec<WebhookSubscription>, Error> {
+        Ok(query_webhook_subscription!("SELECT", "FROM webhook_subscriptions ORDER BY created_at DESC")
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn update_webhook_subscription(
+        &self,
+        subscription: WebhookSubscription,
+        ctx: &AuditContext,
+    ) -> Result<WebhookSubscription, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = query_webhook_subscription!("SELECT", "FROM webhook_subscriptions WHERE id = $1 FOR UPDATE", subscription.id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let subscription = query_webhook_subscription!(
+            "UPDATE webhook_subscriptions SET url = $2, event_types = $3, description = $4, is_active = $5, consecutive_failures = $6, disabled_at = $7, disabled_reason = $8, updated_at = NOW() WHERE id = $1 RETURNING",
+            "",
+            subscription.id, subscription.url, &subscription.event_types as _, subscription.description, subscription.is_active, subscription.consecutive_failures, subscription.disabled_at, subscription.disabled_reason,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        audit::record(&mut tx, ctx, AuditEntity::WebhookSubscription, subscription.id, AuditAction::Update, before.as_ref(), Some(&subscription)).await?;
+        tx.commit().await?;
+
+        Ok(subscription)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn delete_webhook_subscription(
+        &self,
+        subscription_id: Uuid,
+        ctx: &AuditContext,
+    ) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let deleted = query_webhook_subscription!("DELETE FROM webhook_subscriptions WHERE id = $1 RETURNING", "", subscription_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        if let Some(before) = deleted {
+            audit::record(&mut tx, ctx, AuditEntity::WebhookSubscription, subscription_id, AuditAction::Delete, Some(&before), None).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // WEBHOOK DELIVERIES
+    // ========================================================================
+
+    #[instrument(skip_all)]
+    pub async fn list_webhook_deliveries(
+        &self,
+        subscription_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        Ok(sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id, subscription_id, event_id, event_type, payload, status AS "status: WebhookDeliveryStatus", attempts, next_attempt_at, last_attempt_at, response_status, last_error, delivered_at, created_at, updated_at FROM webhook_deliveries WHERE subscription_id = $1 ORDER BY created_at DESC LIMIT $2 OFFSET $3"#,
+            subscription_id, limit, offset,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Claim up to `limit` due deliveries for this dispatcher.
+    ///
+    /// Claimed rows get their `next_attempt_at` pushed forward by `lease_secs` so
+    /// that other replicas skip them while the HTTP call is in flight; a crash
+    /// mid-delivery simply lets the lease expire and the row is retried.
+    #[instrument(skip_all)]
+    pub async fn claim_due_webhook_deliveries(
+        &self,
+        limit: i64,
+        lease_secs: i64,
+    ) -> Result<Vec<PendingWebhookDelivery>, Error> {
+        let lease_secs = lease_secs as f64;
+        Ok(sqlx::query_as!(
+            PendingWebhookDelivery,
+            r#"
+            UPDATE webhook_deliveries d
+            SET next_attempt_at = NOW() + make_interval(secs => $2), updated_at = NOW()
+            FROM webhook_subscriptions s
+            WHERE d.subscription_id = s.id
+              AND d.id IN (
+                SELECT wd.id FROM webhook_deliveries wd
+                INNER JOIN webhook_subscriptions ws ON ws.id = wd.subscription_id
+                WHERE wd.status = 'pending' AND wd.next_attempt_at <= NOW() AND ws.is_active = TRUE
+                ORDER BY wd.next_attempt_at ASC
+                LIMIT $1
+                FOR UPDATE OF wd SKIP LOCKED
+              )
+            RETURNING d.id, d.subscription_id, d.event_type, d.payload, d.attempts, s.url, s.secret
+            "#,
+            limit, lease_secs,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn mark_webhook_delivery_succeeded(
+        &self,
+        delivery: &PendingWebhookDelivery,
+        response_status: i32,
+    ) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            "UPDATE webhook_deliveries SET status = 'succeeded', attempts = attempts + 1, last_attempt_at = NOW(), delivered_at = NOW(), response_status = $2, last_error = NULL, updated_at = NOW() WHERE id = $1",
+            delivery.id, response_status,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE webhook_subscriptions SET consecutive_failures = 0, updated_at = NOW() WHERE id = $1 AND consecutive_failures <> 0",
+            delivery.subscription_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Record a failed attempt. When `retry_in_secs` is `None` the delivery is
+    /// given up on. The subscription is disabled once it accumulates
+    /// `disable_after` consecutive failed attempts; returns whether that happened.
+    #[instrument(skip_all)]
+    pub async fn mark_webhook_delivery_failed(
+        &self,
+        delivery: &PendingWebhookDelivery,
+        response_status: Option<i32>,
+        error: &str,
+        retry_in_secs: Option<i64>,
+        disable_after: i32,
+    ) -> Result<bool, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        match retry_in_secs {
+            Some(secs) => {
+                let secs = secs as f64;
+                sqlx::query!(
+                    "UPDATE webhook_deliveries SET attempts = attempts + 1, last_attempt_at = NOW(), next_attempt_at = NOW() + make_interval(secs => $4), response_status = $2, last_error = $3, updated_at = NOW() WHERE id = $1",
+                    delivery.id, response_status, error, secs,
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            None => {
+                sqlx::query!(
+                    "UPDATE webhook_deliveries SET status = 'failed', attempts = attempts + 1, last_attempt_at = NOW(), response_status = $2, last_error = $3, updated_at = NOW() WHERE id = $1",
+                    delivery.id, response_status, error,
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        let is_active = sqlx::query_scalar!(
+            r#"
+            UPDATE webhook_subscriptions
+            SET consecutive_failures = consecutive_failures + 1,
+                is_active = CASE WHEN consecutive_failures + 1 >= $2 THEN FALSE ELSE is_active END,
+                disabled_at = CASE WHEN is_active AND consecutive_failures + 1 >= $2 THEN NOW() ELSE disabled_at END,
+                disabled_reason = CASE WHEN is_active AND consecutive_failures + 1 >= $2 THEN $3 ELSE disabled_reason END,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING is_active
+            "#,
+            delivery.subscription_id, disable_after, format!("Disabled after {disable_after} consecutive failed deliveries"),
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(!is_active)
+    }
+}
+
+/// Fan an event out to every active subscription listening for its type, as
+/// part of the caller's transaction. Returns the number of deliveries queued.
+pub(super) async fn enqueue_webhook_deliveries(
+    conn: &mut PgConnection,
+    event: &WebhookEvent,
+) -> Result<u64, Error> {
+    let payload = serde_json::to_value(event)?;
+    let queued = sqlx::query!(
+        "INSERT INTO webhook_deliveries (id, subscription_id, event_id, event_type, payload) SELECT gen_random_uuid(), id, $1, $2, $3 FROM webhook_subscriptions WHERE is_active = TRUE AND $2 = ANY(event_types)",
+        event.id, event.event_type.as_str(), payload,
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(queued.rows_affected())
+}
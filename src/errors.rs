@@ -0,0 +1,476 @@
+//! Typed error model shared by the database layer and the HTTP handlers.
+//!
+//! Every error carries a stable machine-readable `code` next to its message.
+//! Responses use the regular `ApiResponse` envelope, or an RFC 7807
+//! `application/problem+json` document when the client asks for one. Messages
+//! come from the `i18n` catalog in the locale negotiated from `Accept-Language`.
+
+use std::collections::BTreeMap;
+
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::error::{JsonPayloadError, PathError, QueryPayloadError};
+use actix_web::http::header::{self, HeaderMap, HeaderValue};
+use actix_web::http::StatusCode;
+use actix_web::middleware::Next;
+use actix_web::{web, HttpResponse, ResponseError};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::postgres::PgDatabaseError;
+use validator::{ValidationErrors, ValidationErrorsKind};
+
+use crate::handlers::entity_tag;
+use crate::i18n::{self, Locale};
+use crate::models::{ApiResponse, LocationImportRowError, PromotionRuleViolation};
+
+const PROBLEM_JSON: &str = "application/problem+json";
+const MERGE_PATCH_JSON: &str = "application/merge-patch+json";
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("{message}")]
+    BadRequest { code: &'static str, message: String },
+    #[error("Validation failed")]
+    Validation(Vec<FieldError>),
+    #[error(transparent)]
+    BusinessRule(#[from] PromotionRuleViolation),
+    /// Bulk import refused because some rows are invalid
+    #[error("{} import row(s) failed validation", .0.len())]
+    InvalidImportRows(Vec<LocationImportRowError>),
+    #[error("Import exceeds {max} rows")]
+    ImportTooLarge { max: usize },
+    #[error("Admin role required")]
+    AdminRequired,
+    /// Carries the resource name, e.g. `"Business"`
+    #[error("{0} not found")]
+    NotFound(&'static str),
+    #[error("Business has {0} active promotion(s)")]
+    ActivePromotions(i64),
+    /// Change refused because it would expose a suspended business again
+    #[error("Business is suspended")]
+    BusinessSuspended,
+    #[error("Registration is already suspended")]
+    AlreadySuspended,
+    #[error("Registration is not suspended")]
+    NotSuspended,
+    /// Moderation decision on a promotion that is not waiting for one
+    #[error("Promotion is not pending review")]
+    NotPendingReview,
+    /// Claim refused because the promotion is not active right now
+    #[error("Promotion is not active")]
+    PromotionNotClaimable,
+    /// Claim refused by `max_claims`; carries the limit
+    #[error("Promotion reached its limit of {0} claim(s)")]
+    ClaimLimitReached(i32),
+    /// Restore refused while the named parent resource is still deleted
+    #[error("Parent {0} is deleted")]
+    ParentDeleted(&'static str),
+    /// `If-Match` no longer matches; carries the current version
+    #[error("Version mismatch, current version is {current_version}")]
+    VersionMismatch { current_version: i32 },
+    #[error("Invalid If-Match header")]
+    InvalidPrecondition,
+    /// Caller ran out of its request budget; carries the seconds to wait
+    #[error("Too many requests, retry in {retry_after_secs}s")]
+    RateLimited { retry_after_secs: u64 },
+    /// Feature without a repository, called on the memory storage backend
+    #[error("This feature needs the Postgres storage backend")]
+    PostgresRequired,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("{0}")]
+    Internal(String),
+}
+
+/// One failed constraint on one request field
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    /// Dotted path to the field, e.g. `opening_hours.monday` or `items[2].name`
+    pub field: String,
+    /// Validator code such as `length`, `range`, `email`
+    pub code: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, Value>,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, code: impl Into<String>, params: BTreeMap<String, Value>) -> Self {
+        let code = code.into();
+        Self {
+            field: field.into(),
+            message: i18n::field_message(Locale::fallback(), &code, &params),
+            code,
+            params,
+        }
+    }
+
+    /// Error whose message interpolates a free-form `{detail}`
+    pub fn with_detail(field: impl Into<String>, code: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(field, code, BTreeMap::from([("detail".to_string(), Value::String(detail.into()))]))
+    }
+
+    pub(crate) fn localized(&self, locale: Locale) -> Self {
+        Self {
+            message: i18n::field_message(locale, &self.code, &self.params),
+            ..self.clone()
+        }
+    }
+}
+
+impl AppError {
+    pub fn bad_request(code: &'static str, message: impl Into<String>) -> Self {
+        Self::BadRequest { code, message: message.into() }
+    }
+
+    /// Stable identifier clients can branch on
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::BadRequest { code, .. } => code,
+            AppError::Validation(_) => "validation_failed",
+            AppError::BusinessRule(violation) => violation.code(),
+            AppError::InvalidImportRows(_) => "invalid_import_rows",
+            AppError::ImportTooLarge { .. } => "import_too_large",
+            AppError::AdminRequired => "admin_required",
+            AppError::NotFound(_) => "not_found",
+            AppError::ActivePromotions(_) => "business_has_active_promotions",
+            AppError::BusinessSuspended => "business_suspended",
+            AppError::AlreadySuspended => "registration_already_suspended",
+            AppError::NotSuspended => "registration_not_suspended",
+            AppError::NotPendingReview => "promotion_not_pending_review",
+            AppError::PromotionNotClaimable => "promotion_not_claimable",
+            AppError::ClaimLimitReached(_) => "promotion_claim_limit_reached",
+            AppError::ParentDeleted(_) => "parent_deleted",
+            AppError::VersionMismatch { .. } => "version_mismatch",
+            AppError::InvalidPrecondition => "invalid_if_match",
+            AppError::RateLimited { .. } => "rate_limited",
+            AppError::PostgresRequired => "postgres_required",
+            AppError::Database(err) => classify_db_error(err).1,
+            AppError::Internal(_) => "internal_error",
+        }
+    }
+
+    /// Message safe to show to clients; server-side failures stay generic
+    pub fn public_message(&self, locale: Locale) -> String {
+        let params: Vec<(&str, String)> = match self {
+            AppError::BadRequest { message, .. } => vec![("detail", message.clone())],
+            AppError::NotFound(resource) => vec![("resource", i18n::resource_name(locale, resource))],
+            AppError::ActivePromotions(count) => vec![("count", count.to_string())],
+            AppError::ClaimLimitReached(max) => vec![("max", max.to_string())],
+            AppError::InvalidImportRows(rows) => vec![("count", rows.len().to_string())],
+            AppError::ImportTooLarge { max } => vec![("max", max.to_string())],
+            AppError::ParentDeleted(parent) => vec![("parent", i18n::resource_name(locale, parent))],
+            AppError::RateLimited { retry_after_secs } => vec![("seconds", retry_after_secs.to_string())],
+            _ => Vec::new(),
+        };
+        i18n::error_message(locale, self.code(), &params).unwrap_or_else(|| match self {
+            AppError::Database(_) | AppError::Internal(_) => {
+                "Internal server error".into()
+            }
+            other => other.to_string(),
+        })
+    }
+
+    /// Structured details attached to the error body
+    pub fn details(&self, locale: Locale) -> Option<Value> {
+        match self {
+            AppError::Validation(errors) => {
+                let errors: Vec<FieldError> = errors.iter().map(|error| error.localized(locale)).collect();
+                Some(json!(errors))
+            }
+            AppError::BusinessRule(violation) => Some(json!({ "field": violation.field() })),
+            AppError::InvalidImportRows(rows) => Some(json!(localize_import_errors(rows, locale))),
+            AppError::NotFound(resource) => Some(json!({ "resource": resource })),
+            AppError::ActivePromotions(count) => Some(json!({ "active_promotions": count })),
+            AppError::VersionMismatch { current_version } => {
+                Some(json!({ "current_version": current_version }))
+            }
+            AppError::RateLimited { retry_after_secs } => Some(json!({ "retry_after": retry_after_secs })),
+            AppError::Database(sqlx::Error::Database(db)) => {
+                let column = db.try_downcast_ref::<PgDatabaseError>().and_then(PgDatabaseError::column);
+                let mut details = serde_json::Map::new();
+                for (key, value) in [("constraint", db.constraint()), ("column", column)] {
+                    if let Some(value) = value {
+                        details.insert(key.into(), json!(value));
+                    }
+                }
+                (!details.is_empty()).then_some(Value::Object(details))
+            }
+            _ => None,
+        }
+    }
+
+    fn problem(&self, status: StatusCode, locale: Locale, instance: &str) -> Value {
+        let mut problem = json!({
+            "type": format!("urn:crazytrip:error:{}", self.code()),
+            "title": status.canonical_reason().unwrap_or("Error"),
+            "status": status.as_u16(),
+            "detail": self.public_message(locale),
+            "instance": instance,
+            "code": self.code(),
+        });
+        if let Some(details) = self.details(locale) {
+            problem["errors"] = details;
+        }
+        problem
+    }
+
+    /// Full error response in `locale`; `problem_instance` switches to RFC 7807
+    fn render(&self, locale: Locale, problem_instance: Option<&str>) -> HttpResponse {
+        let status = self.status_code();
+        let mut response = HttpResponse::build(status);
+        response.insert_header((header::CONTENT_LANGUAGE, locale.as_str()));
+        if let AppError::VersionMismatch { current_version } = self {
+            response.insert_header(entity_tag(*current_version));
+        }
+        if let AppError::RateLimited { retry_after_secs } = self {
+            response.insert_header((header::RETRY_AFTER, *retry_after_secs));
+        }
+        match problem_instance {
+            Some(instance) => response
+                .content_type(PROBLEM_JSON)
+                .body(self.problem(status, locale, instance).to_string()),
+            None => response.json(ApiResponse::<()>::failure(
+                self.code(),
+                self.public_message(locale),
+                self.details(locale),
+            )),
+        }
+    }
+}
+
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        AppError::Validation(field_errors(&errors))
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(err: serde_json::Error) -> Self {
+        AppError::Internal(format!("JSON serialization failed: {err}"))
+    }
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest { .. } => StatusCode::BAD_REQUEST,
+            AppError::Validation(_) | AppError::BusinessRule(_) | AppError::InvalidImportRows(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            AppError::ImportTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::AdminRequired => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::ActivePromotions(_)
+            | AppError::ParentDeleted(_)
+            | AppError::BusinessSuspended
+            | AppError::AlreadySuspended
+            | AppError::NotSuspended
+            | AppError::NotPendingReview
+            | AppError::PromotionNotClaimable
+            | AppError::ClaimLimitReached(_) => StatusCode::CONFLICT,
+            AppError::VersionMismatch { .. } | AppError::InvalidPrecondition => {
+                StatusCode::PRECONDITION_FAILED
+            }
+            AppError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            AppError::PostgresRequired => StatusCode::NOT_IMPLEMENTED,
+            AppError::Database(err) => classify_db_error(err).0,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if self.status_code().is_server_error() {
+            log::error!("Request failed with {}: {self:?}", self.code());
+        }
+        self.render(Locale::fallback(), None)
+    }
+}
+
+// ============================================================================
+// POSTGRES ERROR MAPPING
+// ============================================================================
+
+/// SQLSTATE codes mapped to specific error codes
+mod sqlstate {
+    pub const UNIQUE_VIOLATION: &str = "23505";
+    pub const FOREIGN_KEY_VIOLATION: &str = "23503";
+    pub const NOT_NULL_VIOLATION: &str = "23502";
+    pub const CHECK_VIOLATION: &str = "23514";
+    pub const INVALID_TEXT_REPRESENTATION: &str = "22P02";
+    pub const STRING_DATA_RIGHT_TRUNCATION: &str = "22001";
+    pub const NUMERIC_VALUE_OUT_OF_RANGE: &str = "22003";
+    pub const SERIALIZATION_FAILURE: &str = "40001";
+    pub const DEADLOCK_DETECTED: &str = "40P01";
+}
+
+/// Status and code for a Postgres failure, keyed on SQLSTATE
+fn classify_db_error(err: &sqlx::Error) -> (StatusCode, &'static str) {
+    let db = match err {
+        sqlx::Error::Database(db) => db,
+        sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) => {
+            return (StatusCode::SERVICE_UNAVAILABLE, "database_unavailable");
+        }
+        _ => return (StatusCode::INTERNAL_SERVER_ERROR, "database_error"),
+    };
+    let Some(state) = db.code() else {
+        return (StatusCode::INTERNAL_SERVER_ERROR, "database_error");
+    };
+
+    match state.as_ref() {
+        sqlstate::UNIQUE_VIOLATION => (StatusCode::CONFLICT, "duplicate_resource"),
+        sqlstate::FOREIGN_KEY_VIOLATION => {
+            // Postgres reports a missing parent as "Key (...)=(...) is not present
+            // in table ..."; anything else means the row is still referenced.
+            let missing_parent = db
+                .try_downcast_ref::<PgDatabaseError>()
+                .and_then(PgDatabaseError::detail)
+                .is_some_and(|detail| detail.contains("is not present"));
+            if missing_parent {
+                (StatusCode::NOT_FOUND, "referenced_resource_not_found")
+            } else {
+                (StatusCode::CONFLICT, "resource_in_use")
+            }
+        }
+        sqlstate::NOT_NULL_VIOLATION => (StatusCode::UNPROCESSABLE_ENTITY, "missing_required_value"),
+        sqlstate::CHECK_VIOLATION => (StatusCode::UNPROCESSABLE_ENTITY, "constraint_violation"),
+        sqlstate::INVALID_TEXT_REPRESENTATION => (StatusCode::UNPROCESSABLE_ENTITY, "invalid_value"),
+        sqlstate::STRING_DATA_RIGHT_TRUNCATION => (StatusCode::UNPROCESSABLE_ENTITY, "value_too_long"),
+        sqlstate::NUMERIC_VALUE_OUT_OF_RANGE => (StatusCode::UNPROCESSABLE_ENTITY, "value_out_of_range"),
+        sqlstate::SERIALIZATION_FAILURE | sqlstate::DEADLOCK_DETECTED => (StatusCode::CONFLICT, "concurrent_update"),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "database_error"),
+    }
+}
+
+// ============================================================================
+// VALIDATION DETAILS
+// ============================================================================
+
+/// Flatten `validator` errors into one entry per failed constraint, by field
+pub fn field_errors(errors: &ValidationErrors) -> Vec<FieldError> {
+    let mut fields = Vec::new();
+    flatten_validation_errors(errors, "", &mut fields);
+    fields.sort_by(|a, b| a.field.cmp(&b.field));
+    fields
+}
+
+/// Per-row import errors with field messages in `locale`
+pub fn localize_import_errors(rows: &[LocationImportRowError], locale: Locale) -> Vec<LocationImportRowError> {
+    rows.iter()
+        .map(|row| LocationImportRowError {
+            errors: row.errors.iter().map(|error| error.localized(locale)).collect(),
+            ..row.clone()
+        })
+        .collect()
+}
+
+fn flatten_validation_errors(errors: &ValidationErrors, prefix: &str, out: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{prefix}.{field}")
+        };
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                out.extend(errors.iter().map(|error| {
+                    let params: BTreeMap<String, Value> = error
+                        .params
+                        .iter()
+                        .filter(|(key, _)| *key != "value")
+                        .map(|(key, value)| (key.to_string(), value.clone()))
+                        .collect();
+                    FieldError::new(path.clone(), error.code.clone(), params)
+                }));
+            }
+            ValidationErrorsKind::Struct(nested) => flatten_validation_errors(nested, &path, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    flatten_validation_errors(nested, &format!("{path}[{index}]"), out);
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// EXTRACTOR ERRORS
+// ============================================================================
+
+/// JSON body errors: malformed syntax is a 400, well-formed JSON with wrong
+/// field types or unknown enum values is a 422. PATCH bodies may also come as
+/// `application/merge-patch+json` (RFC 7396).
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default()
+        .content_type(|mime| mime.essence_str() == MERGE_PATCH_JSON)
+        .error_handler(|err, _req| {
+            let app_error = match &err {
+                JsonPayloadError::Deserialize(inner) if inner.is_data() => {
+                    let params = BTreeMap::from([("detail".to_string(), json!(inner.to_string()))]);
+                    AppError::Validation(vec![FieldError::new("body", "invalid_type", params)])
+                }
+                JsonPayloadError::ContentType => {
+                    let message = "Expected an application/json or application/merge-patch+json body";
+                    AppError::bad_request("unsupported_content_type", message)
+                }
+                JsonPayloadError::Deserialize(inner) => AppError::bad_request("malformed_json", inner.to_string()),
+                other => AppError::bad_request("malformed_json", other.to_string()),
+            };
+            app_error.into()
+        })
+}
+
+pub fn path_config() -> web::PathConfig {
+    web::PathConfig::default().error_handler(|err: PathError, _req| {
+        AppError::bad_request("invalid_path_parameter", err.to_string()).into()
+    })
+}
+
+pub fn query_config() -> web::QueryConfig {
+    web::QueryConfig::default().error_handler(|err: QueryPayloadError, _req| {
+        AppError::bad_request("invalid_query_parameter", err.to_string()).into()
+    })
+}
+
+// ============================================================================
+// LOCALE AND FORMAT NEGOTIATION
+// ============================================================================
+
+fn accepts_problem_json(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .filter_map(|value| value.to_str().ok())
+        .any(|value| value.contains(PROBLEM_JSON))
+}
+
+/// Re-render `AppError` responses for the caller: messages in the locale
+/// negotiated from `Accept-Language`, and `application/problem+json` for
+/// clients that list it in `Accept`. Other responses pass through untouched.
+pub async fn render_errors<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, actix_web::Error> {
+    let locale = Locale::from_headers(req.headers());
+    let wants_problem = accepts_problem_json(req.headers());
+    let res = next.call(req).await?;
+
+    let rendered = match res.response().error().and_then(|err| err.as_error::<AppError>()) {
+        Some(app_error) => {
+            let instance = wants_problem.then(|| res.request().path());
+            app_error.render(locale, instance)
+        }
+        None => return Ok(res.map_into_left_body()),
+    };
+
+    let (req, original) = res.into_parts();
+    let mut response = rendered;
+    for (name, value) in original.headers() {
+        if !response.headers().contains_key(name) && name != header::CONTENT_LENGTH {
+            response.headers_mut().append(name.clone(), value.clone());
+        }
+    }
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("Accept, Accept-Language"));
+    Ok(ServiceResponse::new(req, response).map_into_right_body())
+}
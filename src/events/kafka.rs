@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rdkafka::config::ClientConfig;
+use rdkafka::error::KafkaError;
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+
+use super::publisher::PublishError;
+use super::{DomainEvent, EventPublisher};
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Publishes every event to a single topic keyed by aggregate id, so all
+/// events of one business/location/promotion land on the same partition and
+/// keep their order.
+pub struct KafkaEventPublisher {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl KafkaEventPublisher {
+    pub fn new(brokers: &str, topic: String) -> Result<Self, KafkaError> {
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("enable.idempotence", "true")
+            .set("acks", "all")
+            .set("message.timeout.ms", DELIVERY_TIMEOUT.as_millis().to_string())
+            .create()?;
+
+        log::info!("Kafka producer ready for {} (topic {})", brokers, topic);
+        Ok(Self { producer, topic })
+    }
+}
+
+#[async_trait]
+impl EventPublisher for KafkaEventPublisher {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    async fn publish(&self, event: &DomainEvent) -> Result<(), PublishError> {
+        let body = serde_json::to_vec(event).map_err(|e| PublishError::new("kafka", e))?;
+        let key = event.aggregate_id.to_string();
+        let event_id = event.id.to_string();
+        let schema_version = event.schema_version.to_string();
+        let headers = OwnedHeaders::new()
+            .insert(Header { key: "event_id", value: Some(event_id.as_str()) })
+            .insert(Header { key: "event_type", value: Some(event.event_type()) })
+            .insert(Header { key: "schema_version", value: Some(schema_version.as_str()) });
+
+        let record = FutureRecord::to(&self.topic)
+            .key(&key)
+            .payload(&body)
+            .headers(headers);
+
+        self.producer
+            .send(record, DELIVERY_TIMEOUT)
+            .await
+            .map_err(|(err, _)| PublishError::new("kafka", err))?;
+
+        Ok(())
+    }
+}
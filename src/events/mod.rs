@@ -0,0 +1,265 @@
+//! Versioned domain events published to other CrazyTrip services.
+//!
+//! Events are written to the `event_outbox` table in the same transaction as
+//! the change that produced them and relayed to the broker (and to webhook
+//! subscribers) by `jobs::outbox_relay`, so a committed change never loses its
+//! event.
+
+pub mod publisher;
+
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "nats")]
+pub mod nats;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{
+    Business, BusinessLocation, BusinessPromotion, BusinessRegistration, BusinessSuspension, LocationAdmin,
+    ReviewAction, WebhookEvent, WebhookEventType,
+};
+
+pub use publisher::{publisher_from_config, EventBroker, EventPublisher};
+
+/// Version of the envelope and payload schema. Bump on breaking changes.
+pub const SCHEMA_VERSION: i32 = 1;
+
+/// Envelope shared by every domain event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainEvent {
+    pub id: Uuid,
+    pub schema_version: i32,
+    pub aggregate_type: String,
+    pub aggregate_id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub payload: DomainEventPayload,
+}
+
+/// Event body, serialized as `{"type": "...", "data": {...}}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum DomainEventPayload {
+    #[serde(rename = "registration.submitted")]
+    RegistrationSubmitted(BusinessRegistration),
+    #[serde(rename = "business.approved")]
+    BusinessApproved(BusinessRegistration),
+    #[serde(rename = "business.rejected")]
+    BusinessRejected(BusinessRegistration),
+    #[serde(rename = "business.more_info_requested")]
+    BusinessMoreInfoRequested(BusinessRegistration),
+    #[serde(rename = "business.suspended")]
+    BusinessSuspended(BusinessRegistration),
+    #[serde(rename = "business.resumed")]
+    BusinessResumed(BusinessRegistration),
+    /// Tells the owner and location admins that their business was suspended
+    /// or resumed
+    #[serde(rename = "business.suspension_notice")]
+    SuspensionNotice(SuspensionNotice),
+    #[serde(rename = "business.created")]
+    BusinessCreated(Business),
+    #[serde(rename = "business.updated")]
+    BusinessUpdated(Business),
+    #[serde(rename = "business.deleted")]
+    BusinessDeleted(EntityDeleted),
+    #[serde(rename = "business.restored")]
+    BusinessRestored(Business),
+    #[serde(rename = "location.created")]
+    LocationCreated(BusinessLocation),
+    #[serde(rename = "location.updated")]
+    LocationUpdated(BusinessLocation),
+    #[serde(rename = "location.deleted")]
+    LocationDeleted(EntityDeleted),
+    #[serde(rename = "location.restored")]
+    LocationRestored(BusinessLocation),
+    #[serde(rename = "promotion.created")]
+    PromotionCreated(BusinessPromotion),
+    #[serde(rename = "promotion.updated")]
+    PromotionUpdated(BusinessPromotion),
+    #[serde(rename = "promotion.activated")]
+    PromotionActivated(BusinessPromotion),
+    #[serde(rename = "promotion.deleted")]
+    PromotionDeleted(EntityDeleted),
+    #[serde(rename = "promotion.restored")]
+    PromotionRestored(BusinessPromotion),
+    #[serde(rename = "location_admin.added")]
+    LocationAdminAdded(LocationAdmin),
+    #[serde(rename = "location_admin.removed")]
+    LocationAdminRemoved(LocationAdminRemoved),
+}
+
+/// Payload for deletions: the removed id and its parent, when there is one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityDeleted {
+    pub id: Uuid,
+    pub parent_id: Option<Uuid>,
+}
+
+/// Suspension or resumption of a business, addressed to the people who run it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspensionNotice {
+    pub suspension: BusinessSuspension,
+    /// Owner and active location admins to notify
+    pub recipient_user_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationAdminRemoved {
+    pub location_id: Uuid,
+    pub user_id: Uuid,
+}
+
+impl DomainEventPayload {
+    /// Wire name, also used as the broker subject/topic suffix
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            DomainEventPayload::RegistrationSubmitted(_) => "registration.submitted",
+            DomainEventPayload::BusinessApproved(_) => "business.approved",
+            DomainEventPayload::BusinessRejected(_) => "business.rejected",
+            DomainEventPayload::BusinessMoreInfoRequested(_) => "business.more_info_requested",
+            DomainEventPayload::BusinessSuspended(_) => "business.suspended",
+            DomainEventPayload::BusinessResumed(_) => "business.resumed",
+            DomainEventPayload::SuspensionNotice(_) => "business.suspension_notice",
+            DomainEventPayload::BusinessCreated(_) => "business.created",
+            DomainEventPayload::BusinessUpdated(_) => "business.updated",
+            DomainEventPayload::BusinessDeleted(_) => "business.deleted",
+            DomainEventPayload::BusinessRestored(_) => "business.restored",
+            DomainEventPayload::LocationCreated(_) => "location.created",
+            DomainEventPayload::LocationUpdated(_) => "location.updated",
+            DomainEventPayload::LocationDeleted(_) => "location.deleted",
+            DomainEventPayload::LocationRestored(_) => "location.restored",
+            DomainEventPayload::PromotionCreated(_) => "promotion.created",
+            DomainEventPayload::PromotionUpdated(_) => "promotion.updated",
+            DomainEventPayload::PromotionActivated(_) => "promotion.activated",
+            DomainEventPayload::PromotionDeleted(_) => "promotion.deleted",
+            DomainEventPayload::PromotionRestored(_) => "promotion.restored",
+            DomainEventPayload::LocationAdminAdded(_) => "location_admin.added",
+            DomainEventPayload::LocationAdminRemoved(_) => "location_admin.removed",
+        }
+    }
+
+    fn aggregate(&self) -> (&'static str, Uuid) {
+        match self {
+            DomainEventPayload::RegistrationSubmitted(r)
+            | DomainEventPayload::BusinessApproved(r)
+            | DomainEventPayload::BusinessRejected(r)
+            | DomainEventPayload::BusinessMoreInfoRequested(r)
+            | DomainEventPayload::BusinessSuspended(r)
+            | DomainEventPayload::BusinessResumed(r) => ("registration", r.id),
+            DomainEventPayload::SuspensionNotice(n) => ("registration", n.suspension.registration_id),
+            DomainEventPayload::BusinessCreated(b)
+            | DomainEventPayload::BusinessUpdated(b)
+            | DomainEventPayload::BusinessRestored(b) => ("business", b.id),
+            DomainEventPayload::BusinessDeleted(d) => ("business", d.id),
+            DomainEventPayload::LocationCreated(l)
+            | DomainEventPayload::LocationUpdated(l)
+            | DomainEventPayload::LocationRestored(l) => ("location", l.id),
+            DomainEventPayload::LocationDeleted(d) => ("location", d.id),
+            DomainEventPayload::PromotionCreated(p)
+            | DomainEventPayload::PromotionUpdated(p)
+            | DomainEventPayload::PromotionActivated(p)
+            | DomainEventPayload::PromotionRestored(p) => ("promotion", p.id),
+            DomainEventPayload::PromotionDeleted(d) => ("promotion", d.id),
+            DomainEventPayload::LocationAdminAdded(a) => ("location", a.location_id),
+            DomainEventPayload::LocationAdminRemoved(r) => ("location", r.location_id),
+        }
+    }
+
+    /// Webhook event partners receive for this domain event, if any
+    pub fn webhook_event_type(&self) -> Option<WebhookEventType> {
+        match self {
+            DomainEventPayload::BusinessApproved(_) => Some(WebhookEventType::BusinessApproved),
+            DomainEventPayload::BusinessRejected(_) => Some(WebhookEventType::BusinessRejected),
+            DomainEventPayload::BusinessMoreInfoRequested(_) => {
+                Some(WebhookEventType::BusinessMoreInfoRequested)
+            }
+            DomainEventPayload::BusinessSuspended(_) => Some(WebhookEventType::BusinessSuspended),
+            DomainEventPayload::BusinessResumed(_) => Some(WebhookEventType::BusinessResumed),
+            DomainEventPayload::PromotionCreated(_) => Some(WebhookEventType::PromotionCreated),
+            DomainEventPayload::PromotionUpdated(_) => Some(WebhookEventType::PromotionUpdated),
+            DomainEventPayload::PromotionActivated(_) => {
+                Some(WebhookEventType::PromotionPublished)
+            }
+            DomainEventPayload::LocationCreated(_) => Some(WebhookEventType::LocationCreated),
+            DomainEventPayload::LocationUpdated(_) => Some(WebhookEventType::LocationUpdated),
+            DomainEventPayload::LocationDeleted(_) => Some(WebhookEventType::LocationDeleted),
+            DomainEventPayload::LocationRestored(_) => Some(WebhookEventType::LocationRestored),
+            DomainEventPayload::LocationAdminAdded(_) => Some(WebhookEventType::LocationAdminAdded),
+            DomainEventPayload::LocationAdminRemoved(_) => {
+                Some(WebhookEventType::LocationAdminRemoved)
+            }
+            DomainEventPayload::RegistrationSubmitted(_)
+            | DomainEventPayload::SuspensionNotice(_)
+            | DomainEventPayload::BusinessCreated(_)
+            | DomainEventPayload::BusinessUpdated(_)
+            | DomainEventPayload::BusinessDeleted(_)
+            | DomainEventPayload::BusinessRestored(_)
+            | DomainEventPayload::PromotionDeleted(_)
+            | DomainEventPayload::PromotionRestored(_) => None,
+        }
+    }
+
+    /// Payload without the type tag, as embedded in webhook bodies
+    pub fn data(&self) -> serde_json::Value {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|mut value| value.get_mut("data").map(serde_json::Value::take))
+            .unwrap_or(serde_json::Value::Null)
+    }
+
+    /// Event produced when a reviewer applies `action`; comments produce none
+    pub fn from_review_action(
+        action: ReviewAction,
+        registration: BusinessRegistration,
+    ) -> Option<Self> {
+        match action {
+            ReviewAction::Approve => Some(DomainEventPayload::BusinessApproved(registration)),
+            ReviewAction::Reject => Some(DomainEventPayload::BusinessRejected(registration)),
+            ReviewAction::RequestMoreInfo => {
+                Some(DomainEventPayload::BusinessMoreInfoRequested(registration))
+            }
+            ReviewAction::Suspend => Some(DomainEventPayload::BusinessSuspended(registration)),
+            ReviewAction::Resume => Some(DomainEventPayload::BusinessResumed(registration)),
+            ReviewAction::Comment => None,
+        }
+    }
+}
+
+impl DomainEvent {
+    pub fn new(payload: DomainEventPayload) -> Self {
+        let (aggregate_type, aggregate_id) = payload.aggregate();
+        Self {
+            id: Uuid::new_v4(),
+            schema_version: SCHEMA_VERSION,
+            aggregate_type: aggregate_type.to_string(),
+            aggregate_id,
+            occurred_at: Utc::now(),
+            payload,
+        }
+    }
+
+    pub fn event_type(&self) -> &'static str {
+        self.payload.event_type()
+    }
+
+    /// Webhook envelope for this event; shares the event id so partners can
+    /// deduplicate across retries
+    pub fn to_webhook_event(&self) -> Option<WebhookEvent> {
+        self.payload
+            .webhook_event_type()
+            .map(|event_type| WebhookEvent {
+                id: self.id,
+                event_type,
+                created_at: self.occurred_at,
+                data: self.payload.data(),
+            })
+    }
+}
+
+impl From<DomainEventPayload> for DomainEvent {
+    fn from(payload: DomainEventPayload) -> Self {
+        DomainEvent::new(payload)
+    }
+}
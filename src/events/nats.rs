@@ -0,0 +1,57 @@
+use async_nats::jetstream;
+use async_trait::async_trait;
+
+use super::publisher::PublishError;
+use super::{DomainEvent, EventPublisher};
+
+/// Subjects are `crazytrip.business_review.v{schema}.{event_type}`; a JetStream
+/// stream must be configured to capture `crazytrip.business_review.>`.
+const SUBJECT_PREFIX: &str = "crazytrip.business_review";
+
+/// Publishes through JetStream and waits for the server ack, so an event is
+/// only considered published once it has been persisted by the stream.
+pub struct NatsEventPublisher {
+    context: jetstream::Context,
+}
+
+impl NatsEventPublisher {
+    pub async fn connect(url: &str) -> Result<Self, async_nats::ConnectError> {
+        let client = async_nats::connect(url).await?;
+        log::info!("Connected to NATS at {}", url);
+        Ok(Self {
+            context: jetstream::new(client),
+        })
+    }
+}
+
+#[async_trait]
+impl EventPublisher for NatsEventPublisher {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    async fn publish(&self, event: &DomainEvent) -> Result<(), PublishError> {
+        let subject = format!(
+            "{}.v{}.{}",
+            SUBJECT_PREFIX,
+            event.schema_version,
+            event.event_type()
+        );
+        let body = serde_json::to_vec(event).map_err(|e| PublishError::new("nats", e))?;
+
+        // Nats-Msg-Id lets JetStream drop duplicates when the relay retries
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("Nats-Msg-Id", event.id.to_string().as_str());
+        headers.insert("Event-Type", event.event_type());
+        headers.insert("Schema-Version", event.schema_version.to_string().as_str());
+
+        self.context
+            .publish_with_headers(subject, headers, body.into())
+            .await
+            .map_err(|e| PublishError::new("nats", e))?
+            .await
+            .map_err(|e| PublishError::new("nats", e))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use super::DomainEvent;
+use crate::config::EventsConfig;
+
+/// Failure to hand an event to the broker. The outbox relay retries it.
+#[derive(Debug, thiserror::Error)]
+#[error("{publisher} publish failed: {message}")]
+pub struct PublishError {
+    pub publisher: &'static str,
+    pub message: String,
+}
+
+impl PublishError {
+    pub fn new(publisher: &'static str, message: impl ToString) -> Self {
+        Self {
+            publisher,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Destination of the domain event stream.
+///
+/// `publish` must only return `Ok` once the broker has durably accepted the
+/// event; the outbox row is marked as published right after.
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn publish(&self, event: &DomainEvent) -> Result<(), PublishError>;
+}
+
+/// Keeps published events in memory. Used for tests and local development.
+#[derive(Default)]
+pub struct InMemoryEventPublisher {
+    events: Mutex<Vec<DomainEvent>>,
+}
+
+impl InMemoryEventPublisher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[allow(dead_code)] // inspected by tests
+    pub fn events(&self) -> Vec<DomainEvent> {
+        self.events.lock().map(|e| e.clone()).unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl EventPublisher for InMemoryEventPublisher {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn publish(&self, event: &DomainEvent) -> Result<(), PublishError> {
+        self.events
+            .lock()
+            .map_err(|e| PublishError::new("memory", e))?
+            .push(event.clone());
+        Ok(())
+    }
+}
+
+/// Broker the outbox relay publishes domain events to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventBroker {
+    /// Events stay in the outbox; webhooks are still dispatched
+    #[default]
+    None,
+    Memory,
+    /// Needs the `nats` cargo feature
+    Nats,
+    /// Needs the `kafka` cargo feature
+    Kafka,
+}
+
+impl EventBroker {
+    /// Whether this build includes the broker's client
+    pub fn is_available(self) -> bool {
+        match self {
+            EventBroker::None | EventBroker::Memory => true,
+            EventBroker::Nats => cfg!(feature = "nats"),
+            EventBroker::Kafka => cfg!(feature = "kafka"),
+        }
+    }
+}
+
+impl fmt::Display for EventBroker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EventBroker::None => "none",
+            EventBroker::Memory => "memory",
+            EventBroker::Nats => "nats",
+            EventBroker::Kafka => "kafka",
+        })
+    }
+}
+
+impl FromStr for EventBroker {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(EventBroker::None),
+            "memory" => Ok(EventBroker::Memory),
+            "nats" => Ok(EventBroker::Nats),
+            "kafka" => Ok(EventBroker::Kafka),
+            other => Err(format!("event broker '{other}' is not supported (use none, memory, nats or kafka)")),
+        }
+    }
+}
+
+/// Build the publisher of `config.broker`.
+///
+/// Returns `Ok(None)` without a broker; events then stay in the outbox
+/// (webhooks are still dispatched) until one is configured.
+pub async fn publisher_from_config(config: &EventsConfig) -> Result<Option<Arc<dyn EventPublisher>>, String> {
+    match config.broker {
+        EventBroker::None => Ok(None),
+        EventBroker::Memory => Ok(Some(Arc::new(InMemoryEventPublisher::new()))),
+        #[cfg(feature = "nats")]
+        EventBroker::Nats => {
+            let publisher = super::nats::NatsEventPublisher::connect(&config.nats_url)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(Some(Arc::new(publisher)))
+        }
+        #[cfg(feature = "kafka")]
+        EventBroker::Kafka => {
+            let publisher = super::kafka::KafkaEventPublisher::new(&config.kafka_brokers, config.kafka_topic.clone())
+                .map_err(|e| e.to_string())?;
+            Ok(Some(Arc::new(publisher)))
+        }
+        #[allow(unreachable_patterns)]
+        broker => Err(format!(
+            "event broker '{broker}' is not supported by this build (enable the matching cargo feature)"
+        )),
+    }
+}
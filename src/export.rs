@@ -0,0 +1,79 @@
+//! Streaming encoders for the export endpoints.
+//!
+//! Rows are encoded as they arrive from the database; rows that are already
+//! available are batched into one chunk to keep the number of writes down.
+//! Once the response has started an error can no longer change the status, so
+//! it is logged and the body is cut short.
+
+use actix_web::web::Bytes;
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::errors::AppError;
+use crate::models::LocationExportRow;
+
+const BATCH_ROWS: usize = 256;
+
+/// CSV body: the header line, then one line per row
+pub fn csv<T: Serialize>(
+    headers: &'static [&'static str],
+    rows: impl Stream<Item = Result<T, AppError>> + 'static,
+) -> impl Stream<Item = Result<Bytes, AppError>> + 'static {
+    let header = encode_csv(|writer| writer.write_record(headers));
+    let body = rows.ready_chunks(BATCH_ROWS).map(|batch| {
+        let batch = batch.into_iter().collect::<Result<Vec<_>, _>>()?;
+        encode_csv(|writer| batch.iter().try_for_each(|row| writer.serialize(row)))
+    });
+    log_failures(stream::once(async move { header }).chain(body))
+}
+
+/// GeoJSON `FeatureCollection` with one `Point` feature per location.
+/// Locations without coordinates get a `null` geometry.
+pub fn geojson(
+    rows: impl Stream<Item = Result<LocationExportRow, AppError>> + 'static,
+) -> impl Stream<Item = Result<Bytes, AppError>> + 'static {
+    let open = Ok(Bytes::from_static(br#"{"type":"FeatureCollection","features":["#));
+    let close = Ok(Bytes::from_static(b"]}"));
+    let mut first = true;
+    let features = rows.ready_chunks(BATCH_ROWS).map(move |batch| {
+        let mut buffer = Vec::new();
+        for row in batch {
+            if !std::mem::take(&mut first) {
+                buffer.push(b',');
+            }
+            serde_json::to_writer(&mut buffer, &feature(&row?))?;
+        }
+        Ok(Bytes::from(buffer))
+    });
+    log_failures(stream::once(async { open }).chain(features).chain(stream::once(async { close })))
+}
+
+fn feature(location: &LocationExportRow) -> serde_json::Value {
+    let geometry = match (location.longitude, location.latitude) {
+        (Some(longitude), Some(latitude)) => json!({ "type": "Point", "coordinates": [longitude, latitude] }),
+        _ => serde_json::Value::Null,
+    };
+    json!({ "type": "Feature", "id": location.id, "geometry": geometry, "properties": location })
+}
+
+fn encode_csv(
+    write: impl FnOnce(&mut csv::Writer<Vec<u8>>) -> Result<(), csv::Error>,
+) -> Result<Bytes, AppError> {
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+    write(&mut writer).map_err(|err| AppError::Internal(format!("CSV encoding failed: {err}")))?;
+    let buffer = writer
+        .into_inner()
+        .map_err(|err| AppError::Internal(format!("CSV encoding failed: {err}")))?;
+    Ok(Bytes::from(buffer))
+}
+
+fn log_failures(
+    body: impl Stream<Item = Result<Bytes, AppError>> + 'static,
+) -> impl Stream<Item = Result<Bytes, AppError>> + 'static {
+    body.inspect(|chunk| {
+        if let Err(err) = chunk {
+            log::error!("Export aborted: {err}");
+        }
+    })
+}
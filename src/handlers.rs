@@ -1,46 +1,251 @@
-use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+use std::collections::HashSet;
+use std::future::{ready, Ready};
+use std::ops::Deref;
+
+use actix_web::dev::Payload;
+use actix_web::http::header::{self, ETag, EntityTag, Header, IfMatch};
+use actix_web::{delete, get, patch, post, put, web, FromRequest, HttpRequest, HttpResponse, Responder};
 use serde::Deserialize;
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::audit::AuditContext;
 use crate::clients::stories::{SharePromotionRequest, StoriesClient};
-use crate::database::DatabaseService;
+use crate::config::HealthConfig;
+use crate::database::{DatabaseService, SuspensionActor};
+use crate::errors::{localize_import_errors, AppError, FieldError};
+use crate::export;
+use crate::health;
+use crate::i18n::Locale;
+use crate::location_import::{self, ImportFormat};
+use crate::content_policy::ContentPolicy;
+use crate::merge_patch;
+use crate::metrics;
+use crate::moderation::PromotionModeration;
 use crate::models::{
-    AddLocationAdminRequest, ApiResponse, BusinessRegistration, CreateBusinessRequest,
-    CreateBusinessRegistrationRequest, CreateLocationRequest, CreatePromotionRequest,
-    ReviewAction, ReviewActionRequest, UpdateLocationRequest,
-    UpdatePromotionRequest, BusinessPromotionStatus,
+    ActivityStatus, AddLocationAdminRequest, ApiResponse, AuditLogFilter, BusinessExportRow,
+    BusinessPromotion, BusinessPromotionStatus, ClaimPromotionRequest, ClonePromotionRequest,
+    CreateBusinessRegistrationRequest, CreateBusinessRequest, CreateLocationRequest,
+    CreatePromotionRequest, CreatePromotionTemplateRequest, CreateWebhookSubscriptionRequest,
+    CreatedWebhookSubscription, ExportFilter, LocationImportReport, PromotionExportRow,
+    PromotionReviewActionRequest, PromotionTargetScope, PromotionTrustRequest, PromotionWithReviewHistory,
+    ReviewAction, ReviewActionRequest, UpdateBusinessRequest, UpdateLocationRequest,
+    UpdatePromotionRequest, UpdateWebhookSubscriptionRequest,
+};
+use crate::recurrence::Recurrence;
+use crate::repository::{
+    AuditLogRepository, BusinessRepository, LocationAdminRepository, LocationRepository, PromotionRepository,
+    RegistrationRepository,
 };
+use crate::shutdown::Shutdown;
+use crate::telemetry;
+
+type HandlerResult = Result<HttpResponse, AppError>;
+
+/// The Postgres service, for the features that have no repository yet. On
+/// the memory storage backend extracting it fails with `postgres_required`.
+pub struct Postgres(web::Data<DatabaseService>);
+
+impl FromRequest for Postgres {
+    type Error = AppError;
+    type Future = Ready<Result<Self, AppError>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            req.app_data::<web::Data<DatabaseService>>()
+                .cloned()
+                .map(Postgres)
+                .ok_or(AppError::PostgresRequired),
+        )
+    }
+}
+
+impl Deref for Postgres {
+    type Target = DatabaseService;
+
+    fn deref(&self) -> &DatabaseService {
+        &self.0
+    }
+}
 
-fn extract_actor_headers(req: &HttpRequest) -> Result<(Uuid, String), String> {
+fn extract_actor_headers(req: &HttpRequest) -> Result<(Uuid, String), AppError> {
     let actor_id = req
         .headers()
         .get("X-Actor-Id")
         .and_then(|h| h.to_str().ok())
         .and_then(|s| Uuid::parse_str(s).ok())
-        .ok_or_else(|| "Missing or invalid X-Actor-Id header".to_string())?;
+        .ok_or_else(|| AppError::bad_request("invalid_actor_id", "Missing or invalid X-Actor-Id header"))?;
 
     let actor_name = req
         .headers()
         .get("X-Actor-Name")
         .and_then(|h| h.to_str().ok())
         .map(|s| s.to_string())
-        .ok_or_else(|| "Missing X-Actor-Name header".to_string())?;
+        .ok_or_else(|| AppError::bad_request("missing_actor_name", "Missing X-Actor-Name header"))?;
 
     Ok((actor_id, actor_name))
 }
 
+/// Admin callers are identified by the gateway through `X-Actor-Role: admin`
+fn is_admin(req: &HttpRequest) -> bool {
+    req.headers()
+        .get("X-Actor-Role")
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|role| role.eq_ignore_ascii_case("admin"))
+}
+
+fn require_admin(req: &HttpRequest) -> Result<(), AppError> {
+    if is_admin(req) {
+        Ok(())
+    } else {
+        Err(AppError::AdminRequired)
+    }
+}
+
+/// ETag for an entity version
+pub(crate) fn entity_tag(version: i32) -> ETag {
+    ETag(EntityTag::new_strong(version.to_string()))
+}
+
+/// Version required by `If-Match`, or `None` when the header is absent or `*`.
+/// Anything that cannot match a strong version tag is a failed precondition.
+fn if_match_version(req: &HttpRequest) -> Result<Option<i32>, AppError> {
+    if !req.headers().contains_key(header::IF_MATCH) {
+        return Ok(None);
+    }
+    match IfMatch::parse(req) {
+        Ok(IfMatch::Any) => Ok(None),
+        Ok(IfMatch::Items(tags)) => match tags.as_slice() {
+            [tag] if !tag.weak => tag.tag().parse().map(Some).map_err(|_| AppError::InvalidPrecondition),
+            _ => Err(AppError::InvalidPrecondition),
+        },
+        Err(_) => Err(AppError::InvalidPrecondition),
+    }
+}
+
+/// Fail early when the caller's `If-Match` is already stale
+fn check_version(expected: Option<i32>, current_version: i32) -> Result<(), AppError> {
+    match expected {
+        Some(version) if version != current_version => Err(AppError::VersionMismatch { current_version }),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ListQuery {
+    /// Include soft-deleted rows; only honoured for admins
+    pub include_deleted: Option<bool>,
+}
+
+impl ListQuery {
+    fn include_deleted(&self, req: &HttpRequest) -> bool {
+        self.include_deleted.unwrap_or(false) && is_admin(req)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DeleteBusinessQuery {
+    /// Delete even if the business still has active promotions
+    pub force: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct LocationImportQuery {
+    /// Validate and simulate the import without writing anything
+    pub dry_run: Option<bool>,
+}
+
+/// Refuse to publish anything for a business while it is suspended
+async fn ensure_not_suspended(businesses: &dyn BusinessRepository, business_id: Uuid) -> Result<(), AppError> {
+    if businesses.is_business_suspended(business_id).await? {
+        return Err(AppError::BusinessSuspended);
+    }
+    Ok(())
+}
+
+/// Publish an active promotion to the stories service in the background; a
+/// share still running at shutdown gets until the deadline to finish
+fn share_promotion_to_stories(
+    req: &HttpRequest,
+    stories_client: &StoriesClient,
+    actor_id: Uuid,
+    actor_name: String,
+    promotion: &BusinessPromotion,
+) {
+    let share_req = SharePromotionRequest {
+        author_id: actor_id,
+        author_name: Some(actor_name),
+        promotion_id: promotion.id,
+        business_id: Some(promotion.business_id),
+        location_id: promotion.location_id,
+        title: promotion.title.clone(),
+        description: promotion.description.clone(),
+        cover_url: promotion.image_url.clone(),
+        expires_at: Some(promotion.ends_at),
+        media_urls: promotion.image_url.clone().map(|url| vec![url]).unwrap_or_default(),
+        latitude: None,
+        longitude: None,
+        location_name: None,
+        metadata: Some(promotion.metadata.clone()),
+    };
+
+    let client = stories_client.clone();
+    let share = telemetry::inherit(async move {
+        if let Err(e) = client.share_promotion(share_req).await {
+            log::error!("Failed to share promotion to stories: {}", e);
+        }
+    });
+    match req.app_data::<web::Data<Shutdown>>() {
+        Some(shutdown) => shutdown.spawn("share_promotion", share),
+        None => {
+            actix_rt::spawn(share);
+        }
+    }
+}
+
 // ============================================================================
 // HEALTH CHECK
 // ============================================================================
 
 #[get("/health")]
 pub async fn health_check() -> impl Responder {
-    HttpResponse::Ok().json(serde_json::json!({
-        "status": "ok",
-        "service": "business-review-service",
-        "timestamp": chrono::Utc::now()
-    }))
+    HttpResponse::Ok().json(health::liveness())
+}
+
+/// Liveness: the process answers, whatever the state of its dependencies
+#[get("/health/live")]
+pub async fn health_live() -> impl Responder {
+    HttpResponse::Ok().json(health::liveness())
+}
+
+/// Readiness: 503 while Postgres is unreachable or migrations are missing
+#[get("/health/ready")]
+pub async fn health_ready(
+    db: Option<web::Data<DatabaseService>>,
+    stories_client: web::Data<StoriesClient>,
+    config: web::Data<HealthConfig>,
+) -> impl Responder {
+    let report = health::readiness(db.as_ref().map(|db| db.get_ref()), &stories_client, &config).await;
+    if report.is_ready() {
+        HttpResponse::Ok().json(report)
+    } else {
+        HttpResponse::ServiceUnavailable().json(report)
+    }
+}
+
+// ============================================================================
+// METRICS
+// ============================================================================
+
+/// Prometheus scrape endpoint, served outside `/api/v1`
+#[get("/metrics")]
+pub async fn prometheus_metrics(db: Option<web::Data<DatabaseService>>) -> HandlerResult {
+    let metrics = metrics::metrics();
+    if let Some(db) = db {
+        metrics.set_pool_status(db.pool_status());
+    }
+    let body = metrics.render().map_err(AppError::Internal)?;
+    Ok(HttpResponse::Ok().content_type(metrics.content_type()).body(body))
 }
 
 // ============================================================================
@@ -49,78 +254,52 @@ pub async fn health_check() -> impl Responder {
 
 #[post("/registrations")]
 pub async fn submit_registration(
-    db: web::Data<DatabaseService>,
+    req: HttpRequest,
+    registrations: web::Data<dyn RegistrationRepository>,
+    content_policy: web::Data<ContentPolicy>,
     payload: web::Json<CreateBusinessRegistrationRequest>,
-) -> impl Responder {
+) -> HandlerResult {
     let body = payload.into_inner();
-    if let Err(e) = body.validate() {
-        return HttpResponse::BadRequest()
-            .json(ApiResponse::<()>::error(format!("Validation failed: {}", e)));
-    }
+    body.validate()?;
 
-    let new_registration = body.into_new_registration();
-    match db.create_registration(new_registration).await {
-        Ok(registration) => HttpResponse::Created().json(ApiResponse::success(registration)),
-        Err(err) => {
-            log::error!("Failed to create registration: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to create registration".into()))
-        }
-    }
+    let ctx = AuditContext::from_request(&req, Some(body.user_id), Some(body.owner_username.clone()));
+    let mut registration = body.into_new_registration();
+    registration.content_findings = content_policy.check(&registration);
+    let registration = registrations.create_registration(registration, &ctx).await?;
+    Ok(HttpResponse::Created().json(ApiResponse::success(registration)))
 }
 
 #[get("/registrations/{registration_id}")]
 pub async fn get_registration(
-    db: web::Data<DatabaseService>,
+    registrations: web::Data<dyn RegistrationRepository>,
     registration_id: web::Path<Uuid>,
-) -> impl Responder {
-    let registration_id = registration_id.into_inner();
-    match db.get_registration_by_id(registration_id).await {
-        Ok(Some(details)) => HttpResponse::Ok().json(ApiResponse::success(details)),
-        Ok(None) => {
-            HttpResponse::NotFound().json(ApiResponse::<()>::error("Registration not found".into()))
-        }
-        Err(err) => {
-            log::error!("Failed to fetch registration: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to fetch registration".into()))
-        }
-    }
+) -> HandlerResult {
+    let details = registrations
+        .get_registration_by_id(registration_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Registration"))?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(details)))
 }
 
 #[get("/registrations/users/{user_id}/latest")]
 pub async fn get_latest_registration_for_user(
-    db: web::Data<DatabaseService>,
+    registrations: web::Data<dyn RegistrationRepository>,
     user_id: web::Path<Uuid>,
-) -> impl Responder {
-    let user_id = user_id.into_inner();
-    match db.get_latest_registration_for_user(user_id).await {
-        Ok(Some(registration)) => HttpResponse::Ok().json(ApiResponse::success(registration)),
-        Ok(None) => HttpResponse::NotFound()
-            .json(ApiResponse::<()>::error("No registrations for user".into())),
-        Err(err) => {
-            log::error!("Failed to fetch latest registration: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to fetch latest registration".into()))
-        }
-    }
+) -> HandlerResult {
+    let registration = registrations
+        .get_latest_registration_for_user(user_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Registration"))?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(registration)))
 }
 
 #[get("/registrations/users/{user_id}")]
 pub async fn list_registrations_for_user(
-    db: web::Data<DatabaseService>,
+    registrations: web::Data<dyn RegistrationRepository>,
     user_id: web::Path<Uuid>,
-) -> impl Responder {
-    let user_id = user_id.into_inner();
-    match db.list_registrations_for_user(user_id).await {
-        Ok(registrations) => HttpResponse::Ok().json(ApiResponse::success(registrations)),
-        Err(err) => {
-            log::error!("Failed to list registrations: {err:?}");
-            HttpResponse::InternalServerError().json(ApiResponse::<Vec<BusinessRegistration>>::error(
-                "Failed to list registrations".into(),
-            ))
-        }
-    }
+) -> HandlerResult {
+    let registrations = registrations.list_registrations_for_user(user_id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(registrations)))
 }
 
 // ============================================================================
@@ -135,61 +314,42 @@ pub struct PaginationQuery {
 
 #[get("/reviews/pending")]
 pub async fn list_pending_reviews(
-    db: web::Data<DatabaseService>,
+    registrations: web::Data<dyn RegistrationRepository>,
     query: web::Query<PaginationQuery>,
-) -> impl Responder {
+) -> HandlerResult {
     let limit = query.limit.unwrap_or(50).clamp(1, 100);
     let offset = query.offset.unwrap_or(0).max(0);
 
-    match db.list_pending_reviews(limit, offset).await {
-        Ok(records) => HttpResponse::Ok().json(ApiResponse::success(records)),
-        Err(err) => {
-            log::error!("Failed to list pending reviews: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to list pending reviews".into()))
-        }
-    }
+    let records = registrations.list_pending_reviews(limit, offset).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(records)))
 }
 
 #[get("/reviews/{registration_id}")]
 pub async fn get_business_review(
-    db: web::Data<DatabaseService>,
+    registrations: web::Data<dyn RegistrationRepository>,
     registration_id: web::Path<Uuid>,
-) -> impl Responder {
-    let registration_id = registration_id.into_inner();
-    match db.get_registration_by_id(registration_id).await {
-        Ok(Some(details)) => HttpResponse::Ok().json(ApiResponse::success(details)),
-        Ok(None) => {
-            HttpResponse::NotFound().json(ApiResponse::<()>::error("Registration not found".into()))
-        }
-        Err(err) => {
-            log::error!("Failed to fetch registration: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to fetch registration".into()))
-        }
-    }
+) -> HandlerResult {
+    let details = registrations
+        .get_registration_by_id(registration_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Registration"))?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(details)))
 }
 
 #[post("/reviews/{registration_id}/action")]
 pub async fn submit_review_action(
-    db: web::Data<DatabaseService>,
+    req: HttpRequest,
+    registrations: web::Data<dyn RegistrationRepository>,
+    db: Option<Postgres>,
     registration_id: web::Path<Uuid>,
     payload: web::Json<ReviewActionRequest>,
-) -> impl Responder {
+) -> HandlerResult {
     let registration_id = registration_id.into_inner();
 
-    let existing = match db.get_registration_by_id(registration_id).await {
-        Ok(Some(reg)) => reg,
-        Ok(None) => {
-            return HttpResponse::NotFound()
-                .json(ApiResponse::<()>::error("Registration not found".into()))
-        }
-        Err(err) => {
-            log::error!("Failed to fetch registration: {err:?}");
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to process review".into()));
-        }
-    };
+    let existing = registrations
+        .get_registration_by_id(registration_id)
+        .await?
+        .ok_or(AppError::NotFound("Registration"))?;
 
     let payload = payload.into_inner();
     let ReviewActionRequest {
@@ -198,71 +358,92 @@ pub async fn submit_review_action(
         rejection_reason,
         reviewer_id,
         reviewer_name,
+        suspended_until,
+        cancel_promotions,
     } = payload;
 
     if matches!(action, ReviewAction::Reject) && rejection_reason.is_none() {
-        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(
-            "Rejection reason is required when rejecting a registration".into(),
-        ));
+        return Err(AppError::Validation(vec![FieldError::new(
+            "rejection_reason",
+            "required",
+            Default::default(),
+        )]));
+    }
+    if suspended_until.is_some_and(|until| until <= chrono::Utc::now()) {
+        return Err(AppError::Validation(vec![FieldError::new(
+            "suspended_until",
+            "must_be_future",
+            Default::default(),
+        )]));
     }
 
     // Use default reviewer name if not provided
     let final_reviewer_name = reviewer_name
         .filter(|s| !s.trim().is_empty())
         .or_else(|| Some("Admin".to_string()));
+    let ctx = AuditContext::from_request(&req, reviewer_id, final_reviewer_name.clone());
 
+    let actor = SuspensionActor {
+        id: reviewer_id,
+        name: final_reviewer_name.clone(),
+    };
     let new_status = match action {
         ReviewAction::Approve => crate::models::BusinessVerificationStatus::Approved,
         ReviewAction::Reject => crate::models::BusinessVerificationStatus::Rejected,
         ReviewAction::RequestMoreInfo => crate::models::BusinessVerificationStatus::UnderReview,
-        ReviewAction::Suspend => crate::models::BusinessVerificationStatus::Suspended,
-        ReviewAction::Resume => crate::models::BusinessVerificationStatus::UnderReview,
+        ReviewAction::Suspend => {
+            let db = db.ok_or(AppError::PostgresRequired)?;
+            db.suspend_registration(registration_id, actor, notes, suspended_until, cancel_promotions, &ctx)
+                .await?;
+            return review_details(registrations.get_ref(), registration_id).await;
+        }
+        ReviewAction::Resume => {
+            let db = db.ok_or(AppError::PostgresRequired)?;
+            db.resume_registration(registration_id, actor, notes, &ctx).await?;
+            return review_details(registrations.get_ref(), registration_id).await;
+        }
         ReviewAction::Comment => existing.status,
     };
 
-    match db
-        .record_review_event(
-            registration_id,
-            reviewer_id,
-            final_reviewer_name,
-            action,
-            notes,
-            rejection_reason,
-            new_status,
-        )
-        .await
-    {
-        Ok(_updated_registration) => {
-            match db.get_registration_by_id(registration_id).await {
-                Ok(Some(details)) => HttpResponse::Ok().json(ApiResponse::success(details)),
-                Ok(None) => HttpResponse::NotFound()
-                    .json(ApiResponse::<()>::error("Registration not found".into())),
-                Err(err) => {
-                    log::error!("Failed to load registration details: {err:?}");
-                    HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                        "Failed to load registration details".into(),
-                    ))
-                }
-            }
-        }
-        Err(err) => {
-            log::error!("Failed to record review event: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to record review event".into()))
-        }
-    }
+    registrations.record_review_event(
+        registration_id,
+        reviewer_id,
+        final_reviewer_name,
+        action,
+        notes,
+        rejection_reason,
+        new_status,
+        &ctx,
+    )
+    .await?;
+
+    review_details(registrations.get_ref(), registration_id).await
+}
+
+/// Registration with its review history, as returned by the review endpoints
+async fn review_details(registrations: &dyn RegistrationRepository, registration_id: Uuid) -> HandlerResult {
+    let details = registrations
+        .get_registration_by_id(registration_id)
+        .await?
+        .ok_or(AppError::NotFound("Registration"))?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(details)))
+}
+
+#[get("/reviews/{registration_id}/suspensions")]
+pub async fn list_registration_suspensions(
+    req: HttpRequest,
+    db: Postgres,
+    registration_id: web::Path<Uuid>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let suspensions = db.list_suspensions(registration_id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(suspensions)))
 }
 
 #[get("/reviews/stats")]
-pub async fn get_review_stats(db: web::Data<DatabaseService>) -> impl Responder {
-    match db.get_review_stats().await {
-        Ok(stats) => HttpResponse::Ok().json(ApiResponse::success(stats)),
-        Err(err) => {
-            log::error!("Failed to fetch review stats: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to fetch review stats".into()))
-        }
-    }
+pub async fn get_review_stats(registrations: web::Data<dyn RegistrationRepository>) -> HandlerResult {
+    let stats = registrations.get_review_stats().await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(stats)))
 }
 
 // ============================================================================
@@ -272,139 +453,129 @@ pub async fn get_review_stats(db: web::Data<DatabaseService>) -> impl Responder
 #[post("/businesses")]
 pub async fn create_business(
     req: HttpRequest,
-    db: web::Data<DatabaseService>,
+    businesses: web::Data<dyn BusinessRepository>,
     payload: web::Json<CreateBusinessRequest>,
-) -> impl Responder {
-    let (_actor_id, _actor_name) = match extract_actor_headers(&req) {
-        Ok(headers) => headers,
-        Err(err) => {
-            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(err));
-        }
-    };
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
 
     let body = payload.into_inner();
-    if let Err(e) = body.validate() {
-        return HttpResponse::BadRequest()
-            .json(ApiResponse::<()>::error(format!("Validation failed: {}", e)));
-    }
+    body.validate()?;
 
-    let new_business = body.into_new_business();
-    match db.create_business(new_business).await {
-        Ok(business) => HttpResponse::Created().json(ApiResponse::success(business)),
-        Err(err) => {
-            log::error!("Failed to create business: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to create business".into()))
-        }
-    }
+    let business = businesses.create_business(body.into_new_business(), &ctx).await?;
+    Ok(HttpResponse::Created().json(ApiResponse::success(business)))
 }
 
 #[get("/businesses/{business_id}")]
-pub async fn get_business(db: web::Data<DatabaseService>, business_id: web::Path<Uuid>) -> impl Responder {
-    let business_id = business_id.into_inner();
-    match db.get_business(business_id).await {
-        Ok(Some(business)) => HttpResponse::Ok().json(ApiResponse::success(business)),
-        Ok(None) => {
-            HttpResponse::NotFound().json(ApiResponse::<()>::error("Business not found".into()))
-        }
-        Err(err) => {
-            log::error!("Failed to get business: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to get business".into()))
-        }
-    }
+pub async fn get_business(businesses: web::Data<dyn BusinessRepository>, business_id: web::Path<Uuid>) -> HandlerResult {
+    let business = businesses
+        .get_business(business_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Business"))?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(business.version))
+        .json(ApiResponse::success(business)))
 }
 
 #[get("/businesses/users/{user_id}")]
 pub async fn list_businesses_for_user(
-    db: web::Data<DatabaseService>,
+    req: HttpRequest,
+    businesses: web::Data<dyn BusinessRepository>,
     user_id: web::Path<Uuid>,
-) -> impl Responder {
-    let user_id = user_id.into_inner();
-    match db.list_businesses_for_user(user_id).await {
-        Ok(businesses) => HttpResponse::Ok().json(ApiResponse::success(businesses)),
-        Err(err) => {
-            log::error!("Failed to list businesses: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to list businesses".into()))
-        }
-    }
+    query: web::Query<ListQuery>,
+) -> HandlerResult {
+    let businesses = businesses
+        .list_businesses_for_user(user_id.into_inner(), query.include_deleted(&req))
+        .await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(businesses)))
 }
 
 #[put("/businesses/{business_id}")]
 pub async fn update_business(
     req: HttpRequest,
-    db: web::Data<DatabaseService>,
+    businesses: web::Data<dyn BusinessRepository>,
     business_id: web::Path<Uuid>,
-    payload: web::Json<CreateBusinessRequest>,
-) -> impl Responder {
-    let (_actor_id, _actor_name) = match extract_actor_headers(&req) {
-        Ok(headers) => headers,
-        Err(err) => {
-            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(err));
-        }
-    };
+    payload: web::Json<UpdateBusinessRequest>,
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+    let expected_version = if_match_version(&req)?;
 
-    let business_id = business_id.into_inner();
     let body = payload.into_inner();
+    body.validate()?;
 
-    if let Err(e) = body.validate() {
-        return HttpResponse::BadRequest()
-            .json(ApiResponse::<()>::error(format!("Validation failed: {}", e)));
-    }
+    let mut existing_business = businesses
+        .get_business(business_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Business"))?;
+    check_version(expected_version, existing_business.version)?;
 
-    let mut existing_business = match db.get_business(business_id).await {
-        Ok(Some(biz)) => biz,
-        Ok(None) => {
-            return HttpResponse::NotFound()
-                .json(ApiResponse::<()>::error("Business not found".into()));
-        }
-        Err(err) => {
-            log::error!("Failed to fetch business: {err:?}");
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to load business".into()));
-        }
-    };
+    body.apply_to_existing(&mut existing_business);
 
-    existing_business.business_name = body.business_name;
-    existing_business.tax_id = body.tax_id;
-    existing_business.category = body.category;
-    existing_business.description = body.description;
-    existing_business.website = body.website;
-    existing_business.logo_url = body.logo_url;
-
-    match db.update_business(existing_business).await {
-        Ok(updated) => HttpResponse::Ok().json(ApiResponse::success(updated)),
-        Err(err) => {
-            log::error!("Failed to update business: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to update business".into()))
-        }
-    }
+    let updated = businesses.update_business(existing_business, &ctx).await?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(updated.version))
+        .json(ApiResponse::success(updated)))
+}
+
+/// Partial update with JSON Merge Patch (RFC 7396) semantics
+#[patch("/businesses/{business_id}")]
+pub async fn patch_business(
+    req: HttpRequest,
+    businesses: web::Data<dyn BusinessRepository>,
+    business_id: web::Path<Uuid>,
+    payload: web::Json<serde_json::Value>,
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+    let expected_version = if_match_version(&req)?;
+
+    let mut existing = businesses
+        .get_business(business_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Business"))?;
+    check_version(expected_version, existing.version)?;
+
+    let body = merge_patch::apply(&UpdateBusinessRequest::from(&existing), &payload)
+        .map_err(|message| AppError::bad_request("invalid_patch", message))?;
+    body.validate()?;
+
+    body.apply_to_existing(&mut existing);
+
+    let updated = businesses.update_business(existing, &ctx).await?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(updated.version))
+        .json(ApiResponse::success(updated)))
 }
 
 #[delete("/businesses/{business_id}")]
 pub async fn delete_business(
     req: HttpRequest,
-    db: web::Data<DatabaseService>,
+    businesses: web::Data<dyn BusinessRepository>,
     business_id: web::Path<Uuid>,
-) -> impl Responder {
-    let (_actor_id, _actor_name) = match extract_actor_headers(&req) {
-        Ok(headers) => headers,
-        Err(err) => {
-            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(err));
-        }
-    };
+    query: web::Query<DeleteBusinessQuery>,
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+    let expected_version = if_match_version(&req)?;
+
+    let force = query.force.unwrap_or(false);
+    businesses.delete_business(business_id.into_inner(), expected_version, force, &ctx).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
 
-    let business_id = business_id.into_inner();
-    match db.delete_business(business_id).await {
-        Ok(()) => HttpResponse::NoContent().finish(),
-        Err(err) => {
-            log::error!("Failed to delete business: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to delete business".into()))
-        }
-    }
+#[post("/businesses/{business_id}/restore")]
+pub async fn restore_business(
+    req: HttpRequest,
+    businesses: web::Data<dyn BusinessRepository>,
+    business_id: web::Path<Uuid>,
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    require_admin(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+
+    let business = businesses.restore_business(business_id.into_inner(), &ctx).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(business)))
 }
 
 // ============================================================================
@@ -414,362 +585,712 @@ pub async fn delete_business(
 #[post("/businesses/{business_id}/locations")]
 pub async fn create_location(
     req: HttpRequest,
-    db: web::Data<DatabaseService>,
+    businesses: web::Data<dyn BusinessRepository>,
+    locations: web::Data<dyn LocationRepository>,
     business_id: web::Path<Uuid>,
     payload: web::Json<CreateLocationRequest>,
-) -> impl Responder {
-    let (_actor_id, _actor_name) = match extract_actor_headers(&req) {
-        Ok(headers) => headers,
-        Err(err) => {
-            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(err));
-        }
-    };
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
 
-    let business_id = business_id.into_inner();
     let body = payload.into_inner();
+    body.validate()?;
 
-    if let Err(e) = body.validate() {
-        return HttpResponse::BadRequest()
-            .json(ApiResponse::<()>::error(format!("Validation failed: {}", e)));
-    }
-
+    let business_id = business_id.into_inner();
+    ensure_not_suspended(businesses.get_ref(), business_id).await?;
     let new_location = body.into_new_location(business_id);
-    match db.create_location(new_location).await {
-        Ok(location) => HttpResponse::Created().json(ApiResponse::success(location)),
-        Err(err) => {
-            log::error!("Failed to create location: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to create location".into()))
+    let location = locations.create_location(new_location, &ctx).await?;
+    Ok(HttpResponse::Created().json(ApiResponse::success(location)))
+}
+
+/// Bulk create or update locations from a CSV or JSON array body. Any
+/// invalid row rejects the whole import; with `dry_run=true` the per-row
+/// errors (or the would-be result) are returned instead.
+#[post("/businesses/{business_id}/locations/import")]
+pub async fn import_locations(
+    req: HttpRequest,
+    db: Postgres,
+    business_id: web::Path<Uuid>,
+    query: web::Query<LocationImportQuery>,
+    body: web::Bytes,
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+    let dry_run = query.dry_run.unwrap_or(false);
+    let business_id = business_id.into_inner();
+    ensure_not_suspended(&*db, business_id).await?;
+
+    let format = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+        .and_then(ImportFormat::from_content_type)
+        .ok_or_else(|| {
+            AppError::bad_request("unsupported_import_format", "Send the import as text/csv or application/json")
+        })?;
+    let parsed = location_import::parse(format, &body)?;
+
+    if !parsed.errors.is_empty() {
+        if !dry_run {
+            return Err(AppError::InvalidImportRows(parsed.errors));
         }
+        let report = LocationImportReport {
+            dry_run,
+            total_rows: parsed.total_rows,
+            created: 0,
+            updated: 0,
+            errors: localize_import_errors(&parsed.errors, Locale::from_headers(req.headers())),
+            locations: Vec::new(),
+        };
+        return Ok(HttpResponse::Ok().json(ApiResponse::success(report)));
+    }
+
+    let report = db
+        .import_locations(business_id, parsed.rows, dry_run, &ctx)
+        .await?;
+    if dry_run {
+        Ok(HttpResponse::Ok().json(ApiResponse::success(report)))
+    } else {
+        Ok(HttpResponse::Created().json(ApiResponse::success(report)))
     }
 }
 
 #[get("/locations/{location_id}")]
-pub async fn get_location(db: web::Data<DatabaseService>, location_id: web::Path<Uuid>) -> impl Responder {
-    let location_id = location_id.into_inner();
-    match db.get_location(location_id).await {
-        Ok(Some(location)) => HttpResponse::Ok().json(ApiResponse::success(location)),
-        Ok(None) => {
-            HttpResponse::NotFound().json(ApiResponse::<()>::error("Location not found".into()))
-        }
-        Err(err) => {
-            log::error!("Failed to get location: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to get location".into()))
-        }
-    }
+pub async fn get_location(locations: web::Data<dyn LocationRepository>, location_id: web::Path<Uuid>) -> HandlerResult {
+    let location = locations
+        .get_location(location_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Location"))?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(location.version))
+        .json(ApiResponse::success(location)))
 }
 
 #[get("/businesses/{business_id}/locations")]
 pub async fn list_locations_for_business(
-    db: web::Data<DatabaseService>,
+    req: HttpRequest,
+    locations: web::Data<dyn LocationRepository>,
     business_id: web::Path<Uuid>,
-) -> impl Responder {
-    let business_id = business_id.into_inner();
-    match db.list_locations_for_business(business_id).await {
-        Ok(locations) => HttpResponse::Ok().json(ApiResponse::success(locations)),
-        Err(err) => {
-            log::error!("Failed to list locations: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to list locations".into()))
-        }
-    }
+    query: web::Query<ListQuery>,
+) -> HandlerResult {
+    let locations = locations
+        .list_locations_for_business(business_id.into_inner(), query.include_deleted(&req))
+        .await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(locations)))
 }
 
 #[put("/locations/{location_id}")]
 pub async fn update_location(
     req: HttpRequest,
-    db: web::Data<DatabaseService>,
+    businesses: web::Data<dyn BusinessRepository>,
+    locations: web::Data<dyn LocationRepository>,
     location_id: web::Path<Uuid>,
     payload: web::Json<UpdateLocationRequest>,
-) -> impl Responder {
-    let (_actor_id, _actor_name) = match extract_actor_headers(&req) {
-        Ok(headers) => headers,
-        Err(err) => {
-            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(err));
-        }
-    };
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+    let expected_version = if_match_version(&req)?;
 
-    let location_id = location_id.into_inner();
     let body = payload.into_inner();
+    body.validate()?;
 
-    if let Err(e) = body.validate() {
-        return HttpResponse::BadRequest()
-            .json(ApiResponse::<()>::error(format!("Validation failed: {}", e)));
-    }
-
-    let mut existing_location = match db.get_location(location_id).await {
-        Ok(Some(loc)) => loc,
-        Ok(None) => {
-            return HttpResponse::NotFound()
-                .json(ApiResponse::<()>::error("Location not found".into()));
-        }
-        Err(err) => {
-            log::error!("Failed to fetch location: {err:?}");
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to load location".into()));
-        }
-    };
+    let mut existing_location = locations
+        .get_location(location_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Location"))?;
+    check_version(expected_version, existing_location.version)?;
 
+    let was_active = existing_location.is_active;
     body.apply_to_existing(&mut existing_location);
+    if existing_location.is_active && !was_active {
+        ensure_not_suspended(businesses.get_ref(), existing_location.business_id).await?;
+    }
 
-    match db.update_location(existing_location).await {
-        Ok(updated) => HttpResponse::Ok().json(ApiResponse::success(updated)),
-        Err(err) => {
-            log::error!("Failed to update location: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to update location".into()))
-        }
+    let updated = locations.update_location(existing_location, &ctx).await?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(updated.version))
+        .json(ApiResponse::success(updated)))
+}
+
+/// Partial update with JSON Merge Patch (RFC 7396) semantics
+#[patch("/locations/{location_id}")]
+pub async fn patch_location(
+    req: HttpRequest,
+    businesses: web::Data<dyn BusinessRepository>,
+    locations: web::Data<dyn LocationRepository>,
+    location_id: web::Path<Uuid>,
+    payload: web::Json<serde_json::Value>,
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+    let expected_version = if_match_version(&req)?;
+
+    let mut existing = locations
+        .get_location(location_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Location"))?;
+    check_version(expected_version, existing.version)?;
+
+    let body = merge_patch::apply(&UpdateLocationRequest::from(&existing), &payload)
+        .map_err(|message| AppError::bad_request("invalid_patch", message))?;
+    body.validate()?;
+
+    let was_active = existing.is_active;
+    body.apply_to_existing(&mut existing);
+    if existing.is_active && !was_active {
+        ensure_not_suspended(businesses.get_ref(), existing.business_id).await?;
     }
+
+    let updated = locations.update_location(existing, &ctx).await?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(updated.version))
+        .json(ApiResponse::success(updated)))
 }
 
 #[delete("/locations/{location_id}")]
 pub async fn delete_location(
     req: HttpRequest,
-    db: web::Data<DatabaseService>,
+    locations: web::Data<dyn LocationRepository>,
     location_id: web::Path<Uuid>,
-) -> impl Responder {
-    let (_actor_id, _actor_name) = match extract_actor_headers(&req) {
-        Ok(headers) => headers,
-        Err(err) => {
-            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(err));
-        }
-    };
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+    let expected_version = if_match_version(&req)?;
 
-    let location_id = location_id.into_inner();
-    match db.delete_location(location_id).await {
-        Ok(()) => HttpResponse::NoContent().finish(),
-        Err(err) => {
-            log::error!("Failed to delete location: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to delete location".into()))
-        }
-    }
+    locations.delete_location(location_id.into_inner(), expected_version, &ctx).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/locations/{location_id}/restore")]
+pub async fn restore_location(
+    req: HttpRequest,
+    locations: web::Data<dyn LocationRepository>,
+    location_id: web::Path<Uuid>,
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    require_admin(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+
+    let location = locations.restore_location(location_id.into_inner(), &ctx).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(location)))
 }
 
 // ============================================================================
 // BUSINESS PROMOTIONS
 // ============================================================================
 
+/// Create a promotion for a location and, through `location_ids`, for other
+/// locations of the same business
 #[post("/locations/{location_id}/promotions")]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_promotion(
     req: HttpRequest,
-    db: web::Data<DatabaseService>,
+    businesses: web::Data<dyn BusinessRepository>,
+    locations: web::Data<dyn LocationRepository>,
+    promotions: web::Data<dyn PromotionRepository>,
     stories_client: web::Data<StoriesClient>,
+    moderation: web::Data<PromotionModeration>,
+    content_policy: web::Data<ContentPolicy>,
     location_id: web::Path<Uuid>,
     payload: web::Json<CreatePromotionRequest>,
-) -> impl Responder {
-    let (actor_id, actor_name) = match extract_actor_headers(&req) {
-        Ok(headers) => headers,
-        Err(err) => {
-            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(err));
-        }
-    };
-
-    let location_id = location_id.into_inner();
-    let body = payload.into_inner();
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+
+    let mut body = payload.into_inner();
+    body.validate()?;
+    body.validate_business_rules()?;
+
+    let location = locations
+        .get_location(location_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Location"))?;
+    let business = businesses
+        .get_business(location.business_id)
+        .await?
+        .ok_or(AppError::NotFound("Business"))?;
+    ensure_not_suspended(businesses.get_ref(), business.id).await?;
+    let location_ids = [location.id].into_iter().chain(std::mem::take(&mut body.location_ids)).collect();
+    let mut new_promotion = body.into_new_promotion(business.id, location_ids, Some(actor_id));
+    new_promotion.content_findings = content_policy.check(&new_promotion);
+    new_promotion.status = moderation.gate_new(business.promotions_trusted, new_promotion.status);
+    let promotion = promotions.create_promotion(new_promotion, &ctx).await?;
+
+    // If promotion is active, share to stories
+    if matches!(promotion.status, BusinessPromotionStatus::Active) {
+        share_promotion_to_stories(&req, &stories_client, actor_id, actor_name, &promotion);
+    }
+    Ok(HttpResponse::Created().json(ApiResponse::success(promotion)))
+}
 
-    if let Err(e) = body.validate() {
-        return HttpResponse::BadRequest()
-            .json(ApiResponse::<()>::error(format!("Validation failed: {}", e)));
+/// Create a promotion for the locations in `location_ids`, or for every
+/// location of the business when there are none
+#[post("/businesses/{business_id}/promotions")]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_business_promotion(
+    req: HttpRequest,
+    businesses: web::Data<dyn BusinessRepository>,
+    promotions: web::Data<dyn PromotionRepository>,
+    stories_client: web::Data<StoriesClient>,
+    moderation: web::Data<PromotionModeration>,
+    content_policy: web::Data<ContentPolicy>,
+    business_id: web::Path<Uuid>,
+    payload: web::Json<CreatePromotionRequest>,
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+
+    let mut body = payload.into_inner();
+    body.validate()?;
+    body.validate_business_rules()?;
+
+    let business = businesses
+        .get_business(business_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Business"))?;
+    ensure_not_suspended(businesses.get_ref(), business.id).await?;
+    let location_ids = std::mem::take(&mut body.location_ids);
+    let mut new_promotion = body.into_new_promotion(business.id, location_ids, Some(actor_id));
+    new_promotion.content_findings = content_policy.check(&new_promotion);
+    new_promotion.status = moderation.gate_new(business.promotions_trusted, new_promotion.status);
+    let promotion = promotions.create_promotion(new_promotion, &ctx).await?;
+
+    // If promotion is active, share to stories
+    if matches!(promotion.status, BusinessPromotionStatus::Active) {
+        share_promotion_to_stories(&req, &stories_client, actor_id, actor_name, &promotion);
     }
+    Ok(HttpResponse::Created().json(ApiResponse::success(promotion)))
+}
 
-    if let Err(message) = body.validate_business_rules() {
-        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(message));
+/// Copy a promotion to its own location or to other locations of the same
+/// business, all or nothing
+#[post("/promotions/{promotion_id}/clone")]
+#[allow(clippy::too_many_arguments)]
+pub async fn clone_promotion(
+    req: HttpRequest,
+    businesses: web::Data<dyn BusinessRepository>,
+    promotions: web::Data<dyn PromotionRepository>,
+    stories_client: web::Data<StoriesClient>,
+    moderation: web::Data<PromotionModeration>,
+    content_policy: web::Data<ContentPolicy>,
+    promotion_id: web::Path<Uuid>,
+    payload: web::Json<ClonePromotionRequest>,
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+
+    let body = payload.into_inner();
+    body.validate()?;
+
+    let source = promotions
+        .get_promotion(promotion_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Promotion"))?;
+    let (starts_at, ends_at) = body.schedule(&source)?;
+    let business = businesses
+        .get_business(source.business_id)
+        .await?
+        .ok_or(AppError::NotFound("Business"))?;
+    ensure_not_suspended(businesses.get_ref(), business.id).await?;
+    let mut clones: Vec<_> = if body.location_ids.is_empty() {
+        let targets = match source.target_scope {
+            PromotionTargetScope::Business => Vec::new(),
+            PromotionTargetScope::Location | PromotionTargetScope::Locations => promotions
+                .list_promotion_locations(source.id)
+                .await?
+                .into_iter()
+                .map(|target| target.location_id)
+                .collect(),
+        };
+        vec![source.clone_to(targets, body.title.as_deref(), starts_at, ends_at, Some(actor_id))]
+    } else {
+        let mut seen = HashSet::new();
+        let mut targets = body.location_ids.clone();
+        targets.retain(|location_id| seen.insert(*location_id));
+        targets
+            .into_iter()
+            .map(|location_id| source.clone_to(vec![location_id], body.title.as_deref(), starts_at, ends_at, Some(actor_id)))
+            .collect()
+    };
+    for clone in &mut clones {
+        clone.content_findings = content_policy.check(&*clone);
+        clone.status = moderation.gate_new(business.promotions_trusted, clone.status);
     }
+    let promotions = promotions.clone_promotion(business.id, clones, &ctx).await?;
 
-    let new_promotion = body.into_new_promotion(location_id, Some(actor_id));
-    match db.create_promotion(new_promotion).await {
-        Ok(promotion) => {
-            // If promotion is active, share to stories
-            if matches!(promotion.status, crate::models::BusinessPromotionStatus::Active) {
-                let share_req = SharePromotionRequest {
-                    author_id: actor_id,
-                    author_name: Some(actor_name),
-                    promotion_id: promotion.id,
-                    business_id: None,
-                    location_id: Some(location_id),
-                    title: promotion.title.clone(),
-                    description: promotion.description.clone(),
-                    cover_url: promotion.image_url.clone(),
-                    expires_at: Some(promotion.ends_at),
-                    media_urls: promotion.image_url.clone().map(|url| vec![url]).unwrap_or_default(),
-                    latitude: None,
-                    longitude: None,
-                    location_name: None,
-                    metadata: Some(promotion.metadata.clone()),
-                };
-
-                let client = stories_client.get_ref().clone();
-                actix_rt::spawn(async move {
-                    if let Err(e) = client.share_promotion(share_req).await {
-                        log::error!("Failed to share promotion to stories: {}", e);
-                    }
-                });
-            }
-            HttpResponse::Created().json(ApiResponse::success(promotion))
-        }
-        Err(err) => {
-            log::error!("Failed to create promotion: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to create promotion".into()))
-        }
+    for promotion in promotions.iter().filter(|p| p.status == BusinessPromotionStatus::Active) {
+        share_promotion_to_stories(&req, &stories_client, actor_id, actor_name.clone(), promotion);
     }
+    Ok(HttpResponse::Created().json(ApiResponse::success(promotions)))
 }
 
 #[get("/promotions/{promotion_id}")]
 pub async fn get_promotion(
-    db: web::Data<DatabaseService>,
+    promotions: web::Data<dyn PromotionRepository>,
     promotion_id: web::Path<Uuid>,
-) -> impl Responder {
-    let promotion_id = promotion_id.into_inner();
-    match db.get_promotion(promotion_id).await {
-        Ok(Some(promotion)) => HttpResponse::Ok().json(ApiResponse::success(promotion)),
-        Ok(None) => {
-            HttpResponse::NotFound().json(ApiResponse::<()>::error("Promotion not found".into()))
-        }
-        Err(err) => {
-            log::error!("Failed to get promotion: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to get promotion".into()))
-        }
-    }
+) -> HandlerResult {
+    let promotion = promotions
+        .get_promotion(promotion_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Promotion"))?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(promotion.version))
+        .json(ApiResponse::success(promotion)))
+}
+
+/// Locations a promotion applies to, with the claims recorded at each
+#[get("/promotions/{promotion_id}/locations")]
+pub async fn list_promotion_locations(
+    promotions: web::Data<dyn PromotionRepository>,
+    promotion_id: web::Path<Uuid>,
+) -> HandlerResult {
+    let promotion = promotions
+        .get_promotion(promotion_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Promotion"))?;
+    let locations = promotions.list_promotion_locations(promotion.id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(locations)))
+}
+
+/// Record a claim of an active promotion at one of its locations
+#[post("/promotions/{promotion_id}/claims")]
+pub async fn claim_promotion(
+    req: HttpRequest,
+    promotions: web::Data<dyn PromotionRepository>,
+    promotion_id: web::Path<Uuid>,
+    payload: web::Json<ClaimPromotionRequest>,
+) -> HandlerResult {
+    extract_actor_headers(&req)?;
+    let claim = promotions
+        .claim_promotion(promotion_id.into_inner(), payload.location_id)
+        .await?;
+    Ok(HttpResponse::Created().json(ApiResponse::success(claim)))
 }
 
+/// Promotions of the location itself plus the ones it inherits from
+/// multi-location and business-wide promotions
 #[get("/locations/{location_id}/promotions")]
 pub async fn list_promotions_for_location(
-    db: web::Data<DatabaseService>,
+    req: HttpRequest,
+    promotions: web::Data<dyn PromotionRepository>,
     location_id: web::Path<Uuid>,
-) -> impl Responder {
-    let location_id = location_id.into_inner();
-    match db.list_promotions_for_location(location_id).await {
-        Ok(promotions) => HttpResponse::Ok().json(ApiResponse::success(promotions)),
-        Err(err) => {
-            log::error!("Failed to list promotions: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to list promotions".into()))
-        }
-    }
+    query: web::Query<ListQuery>,
+) -> HandlerResult {
+    let promotions = promotions
+        .list_promotions_for_location(location_id.into_inner(), query.include_deleted(&req))
+        .await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(promotions)))
 }
 
 #[get("/businesses/{business_id}/promotions")]
 pub async fn list_promotions_for_business(
-    db: web::Data<DatabaseService>,
+    req: HttpRequest,
+    promotions: web::Data<dyn PromotionRepository>,
     business_id: web::Path<Uuid>,
-) -> impl Responder {
-    let business_id = business_id.into_inner();
-    match db.list_promotions_for_business(business_id).await {
-        Ok(promotions) => HttpResponse::Ok().json(ApiResponse::success(promotions)),
-        Err(err) => {
-            log::error!("Failed to list promotions: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to list promotions".into()))
-        }
-    }
+    query: web::Query<ListQuery>,
+) -> HandlerResult {
+    let promotions = promotions
+        .list_promotions_for_business(business_id.into_inner(), query.include_deleted(&req))
+        .await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(promotions)))
 }
 
 #[put("/promotions/{promotion_id}")]
+#[allow(clippy::too_many_arguments)]
 pub async fn update_promotion(
     req: HttpRequest,
-    db: web::Data<DatabaseService>,
+    businesses: web::Data<dyn BusinessRepository>,
+    promotions: web::Data<dyn PromotionRepository>,
     stories_client: web::Data<StoriesClient>,
+    moderation: web::Data<PromotionModeration>,
+    content_policy: web::Data<ContentPolicy>,
     promotion_id: web::Path<Uuid>,
     payload: web::Json<UpdatePromotionRequest>,
-) -> impl Responder {
-    let (actor_id, actor_name) = match extract_actor_headers(&req) {
-        Ok(headers) => headers,
-        Err(err) => {
-            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(err));
-        }
-    };
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+    let expected_version = if_match_version(&req)?;
 
-    let promotion_id = promotion_id.into_inner();
     let body = payload.into_inner();
-
-    if let Err(e) = body.validate() {
-        return HttpResponse::BadRequest()
-            .json(ApiResponse::<()>::error(format!("Validation failed: {}", e)));
+    body.validate()?;
+    body.validate_business_rules()?;
+
+    let mut existing_promotion = promotions
+        .get_promotion(promotion_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Promotion"))?;
+    check_version(expected_version, existing_promotion.version)?;
+    let business = businesses
+        .get_business(existing_promotion.business_id)
+        .await?
+        .ok_or(AppError::NotFound("Business"))?;
+
+    let before = existing_promotion.clone();
+    body.apply_to_existing(&mut existing_promotion, Some(actor_id));
+    existing_promotion.content_findings = content_policy.check(&existing_promotion);
+    moderation.gate_update(business.promotions_trusted, &before, &mut existing_promotion)?;
+    if existing_promotion.status.is_published() || existing_promotion.status == BusinessPromotionStatus::PendingReview {
+        ensure_not_suspended(businesses.get_ref(), business.id).await?;
     }
 
-    if let Err(message) = body.validate_business_rules() {
-        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(message));
+    let updated = promotions.update_promotion(existing_promotion, &ctx).await?;
+    // If promotion is active, share to stories
+    if matches!(updated.status, BusinessPromotionStatus::Active) {
+        share_promotion_to_stories(&req, &stories_client, actor_id, actor_name, &updated);
     }
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(updated.version))
+        .json(ApiResponse::success(updated)))
+}
 
-    let mut existing_promotion = match db.get_promotion(promotion_id).await {
-        Ok(Some(promo)) => promo,
-        Ok(None) => {
-            return HttpResponse::NotFound()
-                .json(ApiResponse::<()>::error("Promotion not found".into()));
-        }
-        Err(err) => {
-            log::error!("Failed to fetch promotion: {err:?}");
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to load promotion".into()));
-        }
-    };
-
-    body.apply_to_existing(&mut existing_promotion, Some(actor_id));
+/// Partial update with JSON Merge Patch (RFC 7396) semantics
+#[patch("/promotions/{promotion_id}")]
+#[allow(clippy::too_many_arguments)]
+pub async fn patch_promotion(
+    req: HttpRequest,
+    businesses: web::Data<dyn BusinessRepository>,
+    promotions: web::Data<dyn PromotionRepository>,
+    stories_client: web::Data<StoriesClient>,
+    moderation: web::Data<PromotionModeration>,
+    content_policy: web::Data<ContentPolicy>,
+    promotion_id: web::Path<Uuid>,
+    payload: web::Json<serde_json::Value>,
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+    let expected_version = if_match_version(&req)?;
+
+    let mut existing = promotions
+        .get_promotion(promotion_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Promotion"))?;
+    check_version(expected_version, existing.version)?;
+
+    let body = merge_patch::apply(&UpdatePromotionRequest::from(&existing), &payload)
+        .map_err(|message| AppError::bad_request("invalid_patch", message))?;
+    body.validate()?;
+    body.validate_business_rules()?;
+    let business = businesses
+        .get_business(existing.business_id)
+        .await?
+        .ok_or(AppError::NotFound("Business"))?;
+
+    let before = existing.clone();
+    body.apply_to_existing(&mut existing, Some(actor_id));
+    existing.content_findings = content_policy.check(&existing);
+    moderation.gate_update(business.promotions_trusted, &before, &mut existing)?;
+    if existing.status.is_published() || existing.status == BusinessPromotionStatus::PendingReview {
+        ensure_not_suspended(businesses.get_ref(), business.id).await?;
+    }
 
-    match db.update_promotion(existing_promotion).await {
-        Ok(updated) => {
-            // If promotion is active, share to stories
-            if matches!(updated.status, crate::models::BusinessPromotionStatus::Active) {
-                let share_req = SharePromotionRequest {
-                    author_id: actor_id,
-                    author_name: Some(actor_name),
-                    promotion_id: updated.id,
-                    business_id: None,
-                    location_id: Some(updated.location_id),
-                    title: updated.title.clone(),
-                    description: updated.description.clone(),
-                    cover_url: updated.image_url.clone(),
-                    expires_at: Some(updated.ends_at),
-                    media_urls: updated.image_url.clone().map(|url| vec![url]).unwrap_or_default(),
-                    latitude: None,
-                    longitude: None,
-                    location_name: None,
-                    metadata: Some(updated.metadata.clone()),
-                };
-
-                let client = stories_client.get_ref().clone();
-                actix_rt::spawn(async move {
-                    if let Err(e) = client.share_promotion(share_req).await {
-                        log::error!("Failed to share promotion to stories: {}", e);
-                    }
-                });
-            }
-            HttpResponse::Ok().json(ApiResponse::success(updated))
-        },
-        Err(err) => {
-            log::error!("Failed to update promotion: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to update promotion".into()))
-        }
+    let updated = promotions.update_promotion(existing, &ctx).await?;
+    if matches!(updated.status, BusinessPromotionStatus::Active) {
+        share_promotion_to_stories(&req, &stories_client, actor_id, actor_name, &updated);
     }
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(updated.version))
+        .json(ApiResponse::success(updated)))
 }
 
 #[delete("/promotions/{promotion_id}")]
 pub async fn delete_promotion(
     req: HttpRequest,
-    db: web::Data<DatabaseService>,
+    promotions: web::Data<dyn PromotionRepository>,
     promotion_id: web::Path<Uuid>,
-) -> impl Responder {
-    let (_actor_id, _actor_name) = match extract_actor_headers(&req) {
-        Ok(headers) => headers,
-        Err(err) => {
-            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(err));
-        }
-    };
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+    let expected_version = if_match_version(&req)?;
 
-    let promotion_id = promotion_id.into_inner();
-    match db.delete_promotion(promotion_id).await {
-        Ok(()) => HttpResponse::NoContent().finish(),
-        Err(err) => {
-            log::error!("Failed to delete promotion: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to delete promotion".into()))
-        }
+    promotions.delete_promotion(promotion_id.into_inner(), expected_version, &ctx).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/promotions/{promotion_id}/restore")]
+pub async fn restore_promotion(
+    req: HttpRequest,
+    promotions: web::Data<dyn PromotionRepository>,
+    promotion_id: web::Path<Uuid>,
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    require_admin(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+
+    let promotion = promotions.restore_promotion(promotion_id.into_inner(), &ctx).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(promotion)))
+}
+
+// ============================================================================
+// PROMOTION MODERATION
+// ============================================================================
+
+#[get("/promotion-reviews/pending")]
+pub async fn list_pending_promotion_reviews(
+    req: HttpRequest,
+    db: Postgres,
+    query: web::Query<PaginationQuery>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let reviews = db.list_pending_promotion_reviews(limit, offset).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(reviews)))
+}
+
+#[get("/promotion-reviews/{promotion_id}")]
+pub async fn get_promotion_review(
+    req: HttpRequest,
+    db: Postgres,
+    promotion_id: web::Path<Uuid>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let promotion = db
+        .get_promotion(promotion_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Promotion"))?;
+    let history = db.list_promotion_review_events(promotion.id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(PromotionWithReviewHistory { promotion, history })))
+}
+
+/// Approve, reject or comment on a promotion waiting for review
+#[post("/promotion-reviews/{promotion_id}/action")]
+pub async fn submit_promotion_review_action(
+    req: HttpRequest,
+    db: Postgres,
+    stories_client: web::Data<StoriesClient>,
+    promotion_id: web::Path<Uuid>,
+    payload: web::Json<PromotionReviewActionRequest>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+
+    let PromotionReviewActionRequest { action, notes, rejection_reason } = payload.into_inner();
+    if !matches!(action, ReviewAction::Approve | ReviewAction::Reject | ReviewAction::Comment) {
+        return Err(AppError::Validation(vec![FieldError::new("action", "invalid", Default::default())]));
+    }
+    if matches!(action, ReviewAction::Reject) && rejection_reason.is_none() {
+        return Err(AppError::Validation(vec![FieldError::new(
+            "rejection_reason",
+            "required",
+            Default::default(),
+        )]));
     }
+
+    let promotion = db
+        .review_promotion(
+            promotion_id.into_inner(),
+            Some(actor_id),
+            Some(actor_name.clone()),
+            action,
+            notes,
+            rejection_reason,
+            &ctx,
+        )
+        .await?;
+
+    if action == ReviewAction::Approve && promotion.status == BusinessPromotionStatus::Active {
+        share_promotion_to_stories(&req, &stories_client, actor_id, actor_name, &promotion);
+    }
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(promotion.version))
+        .json(ApiResponse::success(promotion)))
+}
+
+/// Let a business publish promotions without moderation
+#[put("/businesses/{business_id}/promotion-trust")]
+pub async fn set_business_promotion_trust(
+    req: HttpRequest,
+    db: Postgres,
+    business_id: web::Path<Uuid>,
+    payload: web::Json<PromotionTrustRequest>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name));
+
+    let business = db
+        .set_promotions_trusted(business_id.into_inner(), payload.trusted, &ctx)
+        .await?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(business.version))
+        .json(ApiResponse::success(business)))
+}
+
+// ============================================================================
+// PROMOTION TEMPLATES
+// ============================================================================
+
+#[post("/locations/{location_id}/promotion-templates")]
+pub async fn create_promotion_template(
+    req: HttpRequest,
+    db: Postgres,
+    location_id: web::Path<Uuid>,
+    payload: web::Json<CreatePromotionTemplateRequest>,
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name));
+
+    let body = payload.into_inner();
+    body.validate()?;
+    body.validate_business_rules()?;
+    if let Err(detail) = Recurrence::parse(&body.rrule) {
+        return Err(AppError::Validation(vec![FieldError::with_detail("rrule", "invalid_rrule", detail)]));
+    }
+
+    let location = db
+        .get_location(location_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Location"))?;
+    let timezone = body
+        .timezone
+        .clone()
+        .or(location.timezone)
+        .unwrap_or_else(|| "UTC".to_string());
+    if let Err(err) = timezone.parse::<chrono_tz::Tz>() {
+        return Err(AppError::Validation(vec![FieldError::with_detail("timezone", "invalid_timezone", err.to_string())]));
+    }
+
+    let new_template = body.into_new_template(location.id, timezone, Some(actor_id));
+    let template = db.create_promotion_template(new_template, &ctx).await?;
+    Ok(HttpResponse::Created().json(ApiResponse::success(template)))
+}
+
+#[get("/locations/{location_id}/promotion-templates")]
+pub async fn list_promotion_templates_for_location(
+    db: Postgres,
+    location_id: web::Path<Uuid>,
+) -> HandlerResult {
+    let templates = db
+        .list_promotion_templates_for_location(location_id.into_inner())
+        .await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(templates)))
+}
+
+#[get("/promotion-templates/{template_id}")]
+pub async fn get_promotion_template(
+    db: Postgres,
+    template_id: web::Path<Uuid>,
+) -> HandlerResult {
+    let template = db
+        .get_promotion_template(template_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Promotion template"))?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(template)))
+}
+
+#[delete("/promotion-templates/{template_id}")]
+pub async fn delete_promotion_template(
+    req: HttpRequest,
+    db: Postgres,
+    template_id: web::Path<Uuid>,
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name));
+
+    db.delete_promotion_template(template_id.into_inner(), &ctx).await?;
+    Ok(HttpResponse::NoContent().finish())
 }
 
 // ============================================================================
@@ -779,72 +1300,215 @@ pub async fn delete_promotion(
 #[post("/locations/{location_id}/admins")]
 pub async fn add_location_admin(
     req: HttpRequest,
-    db: web::Data<DatabaseService>,
+    admins: web::Data<dyn LocationAdminRepository>,
     location_id: web::Path<Uuid>,
     payload: web::Json<AddLocationAdminRequest>,
-) -> impl Responder {
-    let (actor_id, actor_name) = match extract_actor_headers(&req) {
-        Ok(headers) => headers,
-        Err(err) => {
-            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(err));
-        }
-    };
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
 
-    let location_id = location_id.into_inner();
     let body = payload.into_inner();
+    body.validate()?;
 
-    if let Err(e) = body.validate() {
-        return HttpResponse::BadRequest()
-            .json(ApiResponse::<()>::error(format!("Validation failed: {}", e)));
-    }
-
-    let new_admin = body.into_new_admin(location_id, Some(actor_id), Some(actor_name));
-    match db.add_location_admin(new_admin).await {
-        Ok(admin) => HttpResponse::Created().json(ApiResponse::success(admin)),
-        Err(err) => {
-            log::error!("Failed to add location admin: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to add location admin".into()))
-        }
-    }
+    let new_admin = body.into_new_admin(location_id.into_inner(), Some(actor_id), Some(actor_name));
+    let admin = admins.add_location_admin(new_admin, &ctx).await?;
+    Ok(HttpResponse::Created().json(ApiResponse::success(admin)))
 }
 
 #[get("/locations/{location_id}/admins")]
 pub async fn list_location_admins(
-    db: web::Data<DatabaseService>,
+    admins: web::Data<dyn LocationAdminRepository>,
     location_id: web::Path<Uuid>,
-) -> impl Responder {
-    let location_id = location_id.into_inner();
-    match db.list_location_admins(location_id).await {
-        Ok(admins) => HttpResponse::Ok().json(ApiResponse::success(admins)),
-        Err(err) => {
-            log::error!("Failed to list location admins: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to list location admins".into()))
-        }
-    }
+) -> HandlerResult {
+    let admins = admins.list_location_admins(location_id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(admins)))
 }
 
 #[delete("/locations/{location_id}/admins/{user_id}")]
 pub async fn remove_location_admin(
     req: HttpRequest,
-    db: web::Data<DatabaseService>,
+    admins: web::Data<dyn LocationAdminRepository>,
     path: web::Path<(Uuid, Uuid)>,
-) -> impl Responder {
-    let (_actor_id, _actor_name) = match extract_actor_headers(&req) {
-        Ok(headers) => headers,
-        Err(err) => {
-            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(err));
-        }
-    };
+) -> HandlerResult {
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
 
     let (location_id, user_id) = path.into_inner();
-    match db.remove_location_admin(location_id, user_id).await {
-        Ok(()) => HttpResponse::NoContent().finish(),
-        Err(err) => {
-            log::error!("Failed to remove location admin: {err:?}");
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to remove location admin".into()))
-        }
+    admins.remove_location_admin(location_id, user_id, &ctx).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// ============================================================================
+// WEBHOOK SUBSCRIPTIONS
+// ============================================================================
+
+#[post("/webhooks")]
+pub async fn create_webhook_subscription(
+    req: HttpRequest,
+    db: Postgres,
+    payload: web::Json<CreateWebhookSubscriptionRequest>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+
+    let body = payload.into_inner();
+    body.validate()?;
+
+    let subscription = db
+        .create_webhook_subscription(body.into_new_subscription(Some(actor_id)), &ctx)
+        .await?;
+    let secret = subscription.secret.clone();
+    Ok(HttpResponse::Created().json(ApiResponse::success(CreatedWebhookSubscription {
+        subscription,
+        secret,
+    })))
+}
+
+#[get("/webhooks")]
+pub async fn list_webhook_subscriptions(req: HttpRequest, db: Postgres) -> HandlerResult {
+    require_admin(&req)?;
+    let subscriptions = db.list_webhook_subscriptions().await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(subscriptions)))
+}
+
+#[get("/webhooks/{subscription_id}")]
+pub async fn get_webhook_subscription(
+    req: HttpRequest,
+    db: Postgres,
+    subscription_id: web::Path<Uuid>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let subscription = db
+        .get_webhook_subscription(subscription_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Webhook subscription"))?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(subscription)))
+}
+
+#[put("/webhooks/{subscription_id}")]
+pub async fn update_webhook_subscription(
+    req: HttpRequest,
+    db: Postgres,
+    subscription_id: web::Path<Uuid>,
+    payload: web::Json<UpdateWebhookSubscriptionRequest>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+
+    let body = payload.into_inner();
+    body.validate()?;
+
+    let mut existing_subscription = db
+        .get_webhook_subscription(subscription_id.into_inner())
+        .await?
+        .ok_or(AppError::NotFound("Webhook subscription"))?;
+
+    body.apply_to_existing(&mut existing_subscription);
+
+    let updated = db.update_webhook_subscription(existing_subscription, &ctx).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(updated)))
+}
+
+#[delete("/webhooks/{subscription_id}")]
+pub async fn delete_webhook_subscription(
+    req: HttpRequest,
+    db: Postgres,
+    subscription_id: web::Path<Uuid>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let (actor_id, actor_name) = extract_actor_headers(&req)?;
+    let ctx = AuditContext::from_request(&req, Some(actor_id), Some(actor_name.clone()));
+
+    db.delete_webhook_subscription(subscription_id.into_inner(), &ctx).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/webhooks/{subscription_id}/deliveries")]
+pub async fn list_webhook_deliveries(
+    req: HttpRequest,
+    db: Postgres,
+    subscription_id: web::Path<Uuid>,
+    query: web::Query<PaginationQuery>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let deliveries = db
+        .list_webhook_deliveries(subscription_id.into_inner(), limit, offset)
+        .await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(deliveries)))
+}
+
+// ============================================================================
+// AUDIT TRAIL
+// ============================================================================
+
+#[get("/audit-logs")]
+pub async fn list_audit_logs(
+    req: HttpRequest,
+    audit_logs: web::Data<dyn AuditLogRepository>,
+    query: web::Query<AuditLogFilter>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let entries = audit_logs.list_audit_logs(&query).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(entries)))
+}
+
+// ============================================================================
+// EXPORTS
+// ============================================================================
+
+fn attachment(filename: &str) -> header::ContentDisposition {
+    header::ContentDisposition {
+        disposition: header::DispositionType::Attachment,
+        parameters: vec![header::DispositionParam::Filename(filename.to_string())],
     }
 }
+
+#[get("/exports/businesses.csv")]
+pub async fn export_businesses(
+    req: HttpRequest,
+    db: Postgres,
+    shutdown: web::Data<Shutdown>,
+    query: web::Query<ExportFilter<ActivityStatus>>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let rows = db.export_businesses(&query, &shutdown).await?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(attachment("businesses.csv"))
+        .streaming(export::csv(BusinessExportRow::CSV_HEADERS, rows)))
+}
+
+#[get("/exports/promotions.csv")]
+pub async fn export_promotions(
+    req: HttpRequest,
+    db: Postgres,
+    shutdown: web::Data<Shutdown>,
+    query: web::Query<ExportFilter<BusinessPromotionStatus>>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let rows = db.export_promotions(&query, &shutdown).await?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(attachment("promotions.csv"))
+        .streaming(export::csv(PromotionExportRow::CSV_HEADERS, rows)))
+}
+
+#[get("/exports/locations.geojson")]
+pub async fn export_locations(
+    req: HttpRequest,
+    db: Postgres,
+    shutdown: web::Data<Shutdown>,
+    query: web::Query<ExportFilter<ActivityStatus>>,
+) -> HandlerResult {
+    require_admin(&req)?;
+    let rows = db.export_locations(&query, &shutdown).await?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/geo+json")
+        .insert_header(attachment("locations.geojson"))
+        .streaming(export::geojson(rows)))
+}
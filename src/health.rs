@@ -0,0 +1,195 @@
+//! Liveness and readiness probes.
+//!
+//! Liveness only says the process is serving requests. Readiness checks what
+//! the service needs to do useful work: a Postgres round-trip, every
+//! migration shipped with this build applied, a connection pool that is not
+//! exhausted and, when enabled, the stories service. Each dependency reports
+//! its own status and latency. On the memory storage backend the database
+//! check only names the backend.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::clients::stories::StoriesClient;
+use crate::config::HealthConfig;
+use crate::database::{DatabaseService, PoolStatus};
+
+include!(concat!(env!("OUT_DIR"), "/migrations.rs"));
+
+pub const SERVICE_NAME: &str = "business-review-service";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Up,
+    /// Working, but close to failing or missing something optional
+    Degraded,
+    Down,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyCheck {
+    pub status: CheckStatus,
+    /// Whether the service is unready while this check is down
+    pub critical: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Readiness {
+    pub status: CheckStatus,
+    pub service: &'static str,
+    pub timestamp: DateTime<Utc>,
+    pub checks: BTreeMap<&'static str, DependencyCheck>,
+}
+
+impl Readiness {
+    /// Ready unless a critical dependency is down
+    pub fn is_ready(&self) -> bool {
+        self.status != CheckStatus::Down
+    }
+}
+
+pub fn liveness() -> Value {
+    json!({
+        "status": "ok",
+        "service": SERVICE_NAME,
+        "timestamp": Utc::now()
+    })
+}
+
+/// Without `db` the app runs on the memory storage backend, which is always
+/// up and has neither migrations nor a pool to check
+pub async fn readiness(db: Option<&DatabaseService>, stories: &StoriesClient, config: &HealthConfig) -> Readiness {
+    let timeout = config.check_timeout();
+    let stories_check = async {
+        if config.probe_stories {
+            Some(timed(timeout, stories.health()).await)
+        } else {
+            None
+        }
+    };
+
+    let mut checks = BTreeMap::new();
+    let stories = match db {
+        Some(db) => {
+            let (database, migrations, stories) =
+                futures_util::join!(timed(timeout, db.ping()), timed(timeout, db.applied_migrations()), stories_check);
+            checks.insert("database", check(true, database, |_| (CheckStatus::Up, None)));
+            checks.insert("migrations", check(true, migrations, migration_status));
+            checks.insert("pool", pool_check(db.pool_status()));
+            stories
+        }
+        None => {
+            checks.insert("database", memory_check());
+            stories_check.await
+        }
+    };
+    if let Some(stories) = stories {
+        checks.insert("stories", check(false, stories, |_| (CheckStatus::Up, None)));
+    }
+
+    let status = checks
+        .values()
+        .map(|check| match check.status {
+            CheckStatus::Down if !check.critical => CheckStatus::Degraded,
+            status => status,
+        })
+        .max()
+        .unwrap_or(CheckStatus::Up);
+    Readiness {
+        status,
+        service: SERVICE_NAME,
+        timestamp: Utc::now(),
+        checks,
+    }
+}
+
+/// Outcome of a probe and how long it took; a probe that outlives `timeout`
+/// is a failure
+async fn timed<T, E: std::fmt::Display>(
+    timeout: Duration,
+    probe: impl Future<Output = Result<T, E>>,
+) -> (Result<T, String>, Duration) {
+    let started = Instant::now();
+    let result = match actix_rt::time::timeout(timeout, probe).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(err)) => Err(err.to_string()),
+        Err(_) => Err(format!("timed out after {}ms", timeout.as_millis())),
+    };
+    (result, started.elapsed())
+}
+
+fn check<T>(
+    critical: bool,
+    (result, elapsed): (Result<T, String>, Duration),
+    assess: impl FnOnce(T) -> (CheckStatus, Option<Value>),
+) -> DependencyCheck {
+    let latency_ms = Some((elapsed.as_secs_f64() * 1000.0 * 100.0).round() / 100.0);
+    match result {
+        Ok(value) => {
+            let (status, details) = assess(value);
+            DependencyCheck { status, critical, latency_ms, error: None, details }
+        }
+        Err(error) => DependencyCheck {
+            status: CheckStatus::Down,
+            critical,
+            latency_ms,
+            error: Some(error),
+            details: None,
+        },
+    }
+}
+
+/// Down while a migration shipped with this build is not applied. Applied
+/// migrations this build does not know about (a newer release migrated the
+/// database) are only reported.
+fn migration_status(applied: Vec<String>) -> (CheckStatus, Option<Value>) {
+    let missing: Vec<&str> = MIGRATIONS.iter().copied().filter(|name| !applied.iter().any(|a| a == name)).collect();
+    let unknown: Vec<&String> = applied.iter().filter(|name| !MIGRATIONS.contains(&name.as_str())).collect();
+    let status = if missing.is_empty() { CheckStatus::Up } else { CheckStatus::Down };
+    let details = json!({
+        "expected": MIGRATIONS.len(),
+        "applied": applied.len(),
+        "missing": missing,
+        "unknown": unknown,
+    });
+    (status, Some(details))
+}
+
+fn memory_check() -> DependencyCheck {
+    DependencyCheck {
+        status: CheckStatus::Up,
+        critical: true,
+        latency_ms: None,
+        error: None,
+        details: Some(json!({ "backend": "memory" })),
+    }
+}
+
+/// Degraded when every connection is in use, so requests queue for one
+fn pool_check(status: PoolStatus) -> DependencyCheck {
+    let saturated = status.available == 0 && status.size >= status.max_size;
+    DependencyCheck {
+        status: if saturated { CheckStatus::Degraded } else { CheckStatus::Up },
+        critical: false,
+        latency_ms: None,
+        error: None,
+        details: Some(json!({
+            "max_size": status.max_size,
+            "size": status.size,
+            "available": status.available,
+            "in_use": status.size.saturating_sub(status.available),
+        })),
+    }
+}
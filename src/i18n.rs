@@ -0,0 +1,295 @@
+//! Locale negotiation and the message catalog used for error responses.
+//!
+//! Messages are keyed by the stable error code (`errors::AppError::code`) or
+//! by the `validator` code for field errors. `{name}` placeholders are filled
+//! from the error parameters.
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use actix_web::http::header::{HeaderMap, ACCEPT_LANGUAGE};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    Es,
+}
+
+impl Locale {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+        }
+    }
+
+    /// Match a language tag (`es`, `es-CR`, `en_US`) on its primary subtag
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?;
+        if primary.eq_ignore_ascii_case("es") {
+            Some(Locale::Es)
+        } else if primary.eq_ignore_ascii_case("en") {
+            Some(Locale::En)
+        } else {
+            None
+        }
+    }
+
+    /// Locale used when the client expresses no supported preference,
+    /// from `DEFAULT_LOCALE` (default `es`)
+    pub fn fallback() -> Self {
+        static FALLBACK: OnceLock<Locale> = OnceLock::new();
+        *FALLBACK.get_or_init(|| {
+            std::env::var("DEFAULT_LOCALE")
+                .ok()
+                .and_then(|tag| Locale::from_tag(&tag))
+                .unwrap_or(Locale::Es)
+        })
+    }
+
+    /// Best supported locale for an `Accept-Language` value, honouring q-values.
+    /// Ties keep the order the client listed them in.
+    pub fn negotiate(accept_language: &str) -> Self {
+        let mut best: Option<(Locale, f32)> = None;
+        for range in accept_language.split(',') {
+            let mut parts = range.split(';');
+            let tag = parts.next().unwrap_or_default().trim();
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality <= 0.0 {
+                continue;
+            }
+            let locale = if tag == "*" {
+                Some(Locale::fallback())
+            } else {
+                Locale::from_tag(tag)
+            };
+            if let Some(locale) = locale {
+                if best.is_none_or(|(_, best_quality)| quality > best_quality) {
+                    best = Some((locale, quality));
+                }
+            }
+        }
+        best.map_or_else(Locale::fallback, |(locale, _)| locale)
+    }
+
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .map_or_else(Locale::fallback, Locale::negotiate)
+    }
+}
+
+// ============================================================================
+// ERROR MESSAGES
+// ============================================================================
+
+/// (code, English, Spanish)
+const ERROR_MESSAGES: &[(&str, &str, &str)] = &[
+    // 400
+    ("invalid_actor_id", "Missing or invalid X-Actor-Id header", "Falta la cabecera X-Actor-Id o no es válida"),
+    ("missing_actor_name", "Missing X-Actor-Name header", "Falta la cabecera X-Actor-Name"),
+    ("malformed_json", "Request body is not valid JSON: {detail}", "El cuerpo de la petición no es JSON válido: {detail}"),
+    ("unsupported_content_type", "Expected an application/json or application/merge-patch+json body", "Se esperaba un cuerpo application/json o application/merge-patch+json"),
+    ("invalid_path_parameter", "Invalid path parameter: {detail}", "Parámetro de ruta no válido: {detail}"),
+    ("invalid_query_parameter", "Invalid query parameter: {detail}", "Parámetro de consulta no válido: {detail}"),
+    ("invalid_patch", "Invalid patch: {detail}", "El parche no es válido: {detail}"),
+    ("malformed_csv", "Request body is not valid CSV: {detail}", "El cuerpo de la petición no es CSV válido: {detail}"),
+    ("empty_import", "The import contains no rows", "La importación no contiene filas"),
+    ("unsupported_import_format", "Send the import as text/csv or application/json", "Envía la importación como text/csv o application/json"),
+    // 403
+    ("admin_required", "This action requires an admin", "Esta acción requiere un administrador"),
+    // 404
+    ("not_found", "{resource} not found", "No se encontró {resource}"),
+    ("referenced_resource_not_found", "Referenced resource does not exist", "El recurso referenciado no existe"),
+    // 409
+    ("duplicate_resource", "A resource with the same unique value already exists", "Ya existe un recurso con el mismo valor único"),
+    ("resource_in_use", "Resource is still referenced by other records", "El recurso sigue referenciado por otros registros"),
+    ("concurrent_update", "Resource is being modified concurrently; retry the request", "El recurso se está modificando a la vez; reintenta la petición"),
+    (
+        "business_has_active_promotions",
+        "Business has {count} active promotion(s); pass force=true to delete anyway",
+        "El negocio tiene {count} promoción(es) activa(s); usa force=true para borrarlo igualmente",
+    ),
+    ("parent_deleted", "{parent} must be restored first", "Primero hay que restaurar {parent}"),
+    ("business_suspended", "Business is suspended", "El negocio está suspendido"),
+    ("registration_already_suspended", "Registration is already suspended", "La solicitud ya está suspendida"),
+    ("registration_not_suspended", "Registration is not suspended", "La solicitud no está suspendida"),
+    ("promotion_not_pending_review", "Promotion is not waiting for review", "La promoción no está pendiente de revisión"),
+    ("promotion_not_claimable", "Promotion is not active right now", "La promoción no está activa en este momento"),
+    (
+        "promotion_claim_limit_reached",
+        "Promotion has reached its limit of {max} claim(s)",
+        "La promoción alcanzó su límite de {max} canje(s)",
+    ),
+    // 412
+    (
+        "version_mismatch",
+        "Resource was modified by someone else; reload it and retry with the new ETag",
+        "Otra persona modificó el recurso; vuelve a cargarlo y reintenta con el nuevo ETag",
+    ),
+    ("invalid_if_match", "If-Match must carry a single strong version ETag", "If-Match debe contener un único ETag fuerte de versión"),
+    // 429
+    (
+        "rate_limited",
+        "Too many requests; retry in {seconds} second(s)",
+        "Demasiadas solicitudes; reintenta en {seconds} segundo(s)",
+    ),
+    // 501
+    (
+        "postgres_required",
+        "This feature needs the Postgres storage backend",
+        "Esta función necesita el almacenamiento Postgres",
+    ),
+    // 422
+    ("validation_failed", "Validation failed", "La validación falló"),
+    ("promotion_ends_before_start", "End date must be after the start date", "La fecha de finalización debe ser posterior a la fecha de inicio"),
+    (
+        "discount_requires_discount_type",
+        "Discount percentage only applies to discount promotions",
+        "El porcentaje de descuento solo aplica para promociones de tipo discount",
+    ),
+    ("discount_out_of_range", "Discount must be between 0 and 100", "El descuento debe estar entre 0 y 100"),
+    ("contest_requires_prize", "Contest promotions require a prize", "Las promociones de tipo concurso requieren especificar un premio"),
+    ("status_set_by_moderation", "Only reviewers can reject a promotion", "Solo un revisor puede rechazar una promoción"),
+    ("status_set_by_suspension", "Promotions are only paused and resumed with their business", "Las promociones solo se pausan y se reanudan junto con su negocio"),
+    ("missing_required_value", "A required value is missing", "Falta un valor obligatorio"),
+    ("constraint_violation", "A value violates a data constraint", "Un valor incumple una restricción de datos"),
+    ("invalid_value", "A value has an invalid format", "Un valor tiene un formato no válido"),
+    ("value_too_long", "A value is too long", "Un valor es demasiado largo"),
+    ("value_out_of_range", "A value is out of range", "Un valor está fuera de rango"),
+    (
+        "invalid_import_rows",
+        "{count} row(s) failed validation; nothing was imported",
+        "{count} fila(s) no superaron la validación; no se importó nada",
+    ),
+    // 413
+    ("import_too_large", "Imports are limited to {max} rows", "Las importaciones están limitadas a {max} filas"),
+    // 5xx
+    ("internal_error", "Internal server error", "Error interno del servidor"),
+    ("database_error", "Internal server error", "Error interno del servidor"),
+    ("database_unavailable", "Database is temporarily unavailable", "La base de datos no está disponible temporalmente"),
+];
+
+/// (resource, English, Spanish with article)
+const RESOURCE_NAMES: &[(&str, &str, &str)] = &[
+    ("Registration", "Registration", "la solicitud de registro"),
+    ("Business", "Business", "el negocio"),
+    ("Location", "Location", "la ubicación"),
+    ("Promotion", "Promotion", "la promoción"),
+    ("Promotion template", "Promotion template", "la plantilla de promoción"),
+    ("Webhook subscription", "Webhook subscription", "la suscripción de webhook"),
+];
+
+pub fn resource_name(locale: Locale, resource: &str) -> String {
+    RESOURCE_NAMES
+        .iter()
+        .find(|(key, _, _)| *key == resource)
+        .map(|(_, en, es)| pick(locale, en, es).to_string())
+        .unwrap_or_else(|| resource.to_string())
+}
+
+/// Catalog message for an error code, or `None` for unknown codes
+pub fn error_message(locale: Locale, code: &str, params: &[(&str, String)]) -> Option<String> {
+    let (_, en, es) = ERROR_MESSAGES.iter().find(|(key, _, _)| *key == code)?;
+    Some(interpolate(pick(locale, en, es), |name| {
+        params.iter().find(|(key, _)| *key == name).map(|(_, value)| value.clone())
+    }))
+}
+
+// ============================================================================
+// FIELD MESSAGES
+// ============================================================================
+
+/// (validator code and bound variant, English, Spanish)
+const FIELD_MESSAGES: &[(&str, &str, &str)] = &[
+    ("length.equal", "must be exactly {equal} characters long", "debe tener exactamente {equal} caracteres"),
+    ("length.between", "must be between {min} and {max} characters long", "debe tener entre {min} y {max} caracteres"),
+    ("length.min", "must be at least {min} characters long", "debe tener al menos {min} caracteres"),
+    ("length.max", "must be at most {max} characters long", "debe tener como máximo {max} caracteres"),
+    ("range.between", "must be between {min} and {max}", "debe estar entre {min} y {max}"),
+    ("range.min", "must be at least {min}", "debe ser como mínimo {min}"),
+    ("range.max", "must be at most {max}", "debe ser como máximo {max}"),
+    ("email", "must be a valid email address", "debe ser un correo electrónico válido"),
+    ("url", "must be a valid URL", "debe ser una URL válida"),
+    ("public_https_url", "must be an https URL of a public host", "debe ser una URL https de un host público"),
+    ("required", "is required", "es obligatorio"),
+    ("duplicate", "is repeated in row {first_row}", "está repetido en la fila {first_row}"),
+    ("not_in_business", "includes location {location_id}, which does not belong to the same business", "incluye la ubicación {location_id}, que no pertenece al mismo negocio"),
+    ("not_targeted", "is not a location this promotion applies to", "no es una ubicación a la que aplique esta promoción"),
+    ("invalid_rrule", "is not a supported recurrence rule: {detail}", "no es una regla de recurrencia soportada: {detail}"),
+    ("invalid_timezone", "is not a known IANA time zone: {detail}", "no es una zona horaria IANA conocida: {detail}"),
+    ("must_be_future", "must be in the future", "debe ser una fecha futura"),
+    ("invalid_type", "has an invalid value: {detail}", "tiene un valor no válido: {detail}"),
+    ("invalid", "is invalid", "no es válido"),
+];
+
+/// Message for one failed `validator` constraint
+pub fn field_message(locale: Locale, code: &str, params: &BTreeMap<String, Value>) -> String {
+    let has = |key: &str| params.contains_key(key);
+    let key = match code {
+        "length" if has("equal") => "length.equal".to_string(),
+        "length" | "range" => match (has("min"), has("max")) {
+            (true, true) => format!("{code}.between"),
+            (true, false) => format!("{code}.min"),
+            (false, true) => format!("{code}.max"),
+            (false, false) => "invalid".to_string(),
+        },
+        other => other.to_string(),
+    };
+    let (_, en, es) = FIELD_MESSAGES
+        .iter()
+        .find(|(candidate, _, _)| *candidate == key)
+        .or_else(|| FIELD_MESSAGES.iter().find(|(candidate, _, _)| *candidate == "invalid"))
+        .expect("catalog has a generic field message");
+    interpolate(pick(locale, en, es), |name| params.get(name).map(display_param))
+}
+
+fn display_param(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        // validator stores range bounds as f64; show 100 rather than 100.0
+        Value::Number(number) => match number.as_f64() {
+            Some(float) if float.fract() == 0.0 && number.is_f64() => format!("{float:.0}"),
+            _ => number.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+fn pick<'a>(locale: Locale, en: &'a str, es: &'a str) -> &'a str {
+    match locale {
+        Locale::En => en,
+        Locale::Es => es,
+    }
+}
+
+/// Replace `{name}` placeholders; unknown names are left untouched
+fn interpolate(template: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                match lookup(name) {
+                    Some(value) => out.push_str(&value),
+                    None => out.push_str(&rest[start..start + end + 2]),
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,49 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::database::DatabaseService;
+use crate::shutdown::Shutdown;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuditRetentionConfig {
+    /// Rows older than this many days are purged; `0` keeps them forever
+    pub retention_days: i32,
+    pub purge_interval_secs: u64,
+}
+
+impl Default for AuditRetentionConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: 365,
+            purge_interval_secs: 86_400,
+        }
+    }
+}
+
+impl AuditRetentionConfig {
+    pub fn purge_interval(&self) -> Duration {
+        Duration::from_secs(self.purge_interval_secs)
+    }
+}
+
+/// Periodically drop audit entries older than the retention window.
+pub fn spawn(db: Arc<DatabaseService>, config: AuditRetentionConfig, shutdown: &Shutdown) {
+    if config.retention_days <= 0 {
+        log::info!("AUDIT_RETENTION_DAYS <= 0; audit logs are kept forever");
+        return;
+    }
+
+    let mut ticker = shutdown.ticker(config.purge_interval());
+    shutdown.spawn("audit_retention", async move {
+        while ticker.tick().await {
+            match db.purge_audit_logs(config.retention_days).await {
+                Ok(0) => {}
+                Ok(count) => log::info!("Purged {} audit log entries", count),
+                Err(err) => log::error!("Failed to purge audit logs: {err:?}"),
+            }
+        }
+    });
+}
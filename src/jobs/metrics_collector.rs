@@ -0,0 +1,41 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::database::DatabaseService;
+use crate::metrics::metrics;
+use crate::shutdown::Shutdown;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsCollectorConfig {
+    pub interval_secs: u64,
+}
+
+impl Default for MetricsCollectorConfig {
+    fn default() -> Self {
+        Self { interval_secs: 30 }
+    }
+}
+
+impl MetricsCollectorConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+}
+
+/// Refresh the domain gauges (review queues, active promotions, outbox
+/// backlog). A failed refresh keeps the previous values; the refresh
+/// timestamp gauge shows how stale they are.
+pub fn spawn(db: Arc<DatabaseService>, config: MetricsCollectorConfig, shutdown: &Shutdown) {
+    let mut ticker = shutdown.ticker(config.interval());
+    shutdown.spawn("metrics_collector", async move {
+        while ticker.tick().await {
+            match db.domain_snapshot().await {
+                Ok(snapshot) => metrics().set_domain(&snapshot),
+                Err(err) => log::warn!("Failed to refresh domain metrics: {err:?}"),
+            }
+        }
+    });
+}
@@ -0,0 +1,10 @@
+//! Background jobs spawned alongside the HTTP server.
+
+pub mod audit_retention;
+pub mod metrics_collector;
+pub mod outbox_relay;
+pub mod promotion_scheduler;
+pub mod soft_delete_purge;
+pub mod suspension_expiry;
+pub mod webhook_dispatcher;
+
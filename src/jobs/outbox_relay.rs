@@ -0,0 +1,82 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::events::EventPublisher;
+use crate::repository::OutboxRepository;
+use crate::shutdown::Shutdown;
+
+const PURGE_EVERY: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutboxRelayConfig {
+    pub poll_interval_secs: u64,
+    pub batch_size: i64,
+    /// Days relayed events are kept before they are purged
+    pub retention_days: i32,
+}
+
+impl Default for OutboxRelayConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 2,
+            batch_size: 100,
+            retention_days: 7,
+        }
+    }
+}
+
+impl OutboxRelayConfig {
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval_secs)
+    }
+}
+
+/// Relay committed outbox events to webhook subscribers and, when a broker is
+/// configured, to the domain event stream.
+pub fn spawn(
+    outbox: Arc<dyn OutboxRepository>,
+    publisher: Option<Arc<dyn EventPublisher>>,
+    config: OutboxRelayConfig,
+    shutdown: &Shutdown,
+) {
+    match &publisher {
+        Some(publisher) => log::info!("Relaying domain events via {}", publisher.name()),
+        None => log::warn!("No EVENT_BROKER configured; domain events stay in the outbox"),
+    }
+
+    let mut ticker = shutdown.ticker(config.poll_interval());
+    shutdown.spawn("outbox_relay", async move {
+        let mut last_purge = Instant::now();
+        while ticker.tick().await {
+            if let Err(err) = outbox.dispatch_outbox_webhooks(config.batch_size).await {
+                log::error!("Failed to fan out outbox events to webhooks: {err:?}");
+            }
+
+            if let Some(publisher) = &publisher {
+                match outbox
+                    .publish_outbox_events(publisher.as_ref(), config.batch_size)
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(count) => log::debug!("Published {} domain events", count),
+                    Err(err) => log::error!("Outbox relay cycle failed: {err:?}"),
+                }
+            }
+
+            if last_purge.elapsed() >= PURGE_EVERY {
+                last_purge = Instant::now();
+                match outbox
+                    .purge_outbox(config.retention_days, publisher.is_some())
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(count) => log::info!("Purged {} relayed outbox events", count),
+                    Err(err) => log::error!("Failed to purge outbox: {err:?}"),
+                }
+            }
+        }
+    });
+}
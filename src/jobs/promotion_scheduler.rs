@@ -0,0 +1,77 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::audit::AuditContext;
+use crate::content_policy::ContentPolicy;
+use crate::database::DatabaseService;
+use crate::moderation::PromotionModeration;
+use crate::shutdown::Shutdown;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PromotionSchedulerConfig {
+    /// How far ahead occurrences of recurring templates are materialized;
+    /// `0` turns the scheduler off
+    pub horizon_days: i64,
+    pub interval_secs: u64,
+}
+
+impl Default for PromotionSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            horizon_days: 14,
+            interval_secs: 900,
+        }
+    }
+}
+
+impl PromotionSchedulerConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+}
+
+/// Keep a rolling window of concrete promotions ahead of every active
+/// recurring template.
+pub fn spawn(
+    db: Arc<DatabaseService>,
+    config: PromotionSchedulerConfig,
+    moderation: PromotionModeration,
+    content_policy: Arc<ContentPolicy>,
+    shutdown: &Shutdown,
+) {
+    if config.horizon_days <= 0 {
+        log::info!("PROMOTION_SCHEDULE_HORIZON_DAYS <= 0; recurring promotions are not materialized");
+        return;
+    }
+
+    let mut ticker = shutdown.ticker(config.interval());
+    let stopping = shutdown.clone();
+    shutdown.spawn("promotion_scheduler", async move {
+        let ctx = AuditContext::system("promotion_scheduler");
+        while ticker.tick().await {
+            let until = Utc::now() + chrono::Duration::days(config.horizon_days);
+            let template_ids = match db.promotion_templates_due(until).await {
+                Ok(ids) => ids,
+                Err(err) => {
+                    log::error!("Failed to list promotion templates: {err:?}");
+                    continue;
+                }
+            };
+            for template_id in template_ids {
+                // Each template commits on its own; the rest wait for the next run
+                if stopping.is_shutting_down() {
+                    break;
+                }
+                match db.materialize_promotion_template(template_id, until, moderation, &content_policy, &ctx).await {
+                    Ok(0) => {}
+                    Ok(count) => log::info!("Created {} promotions from template {}", count, template_id),
+                    Err(err) => log::error!("Failed to materialize promotion template {template_id}: {err:?}"),
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::audit::AuditContext;
+use crate::database::DatabaseService;
+use crate::shutdown::Shutdown;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SoftDeletePurgeConfig {
+    /// Rows older than this many days are purged; `0` keeps them forever
+    pub retention_days: i32,
+    pub purge_interval_secs: u64,
+}
+
+impl Default for SoftDeletePurgeConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: 30,
+            purge_interval_secs: 3600,
+        }
+    }
+}
+
+impl SoftDeletePurgeConfig {
+    pub fn purge_interval(&self) -> Duration {
+        Duration::from_secs(self.purge_interval_secs)
+    }
+}
+
+/// Hard-delete businesses, locations and promotions once they have been
+/// soft-deleted for longer than the retention window.
+pub fn spawn(db: Arc<DatabaseService>, config: SoftDeletePurgeConfig, shutdown: &Shutdown) {
+    if config.retention_days <= 0 {
+        log::info!("SOFT_DELETE_RETENTION_DAYS <= 0; soft-deleted rows are kept forever");
+        return;
+    }
+
+    let mut ticker = shutdown.ticker(config.purge_interval());
+    shutdown.spawn("soft_delete_purge", async move {
+        let ctx = AuditContext::system("soft_delete_purge");
+        while ticker.tick().await {
+            match db.purge_soft_deleted(config.retention_days, &ctx).await {
+                Ok(0) => {}
+                Ok(count) => log::info!("Purged {} soft-deleted rows", count),
+                Err(err) => log::error!("Failed to purge soft-deleted rows: {err:?}"),
+            }
+        }
+    });
+}
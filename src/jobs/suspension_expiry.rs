@@ -0,0 +1,61 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::audit::AuditContext;
+use crate::database::{DatabaseService, SuspensionActor};
+use crate::shutdown::Shutdown;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SuspensionExpiryConfig {
+    pub interval_secs: u64,
+    pub batch_size: i64,
+}
+
+impl Default for SuspensionExpiryConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 60,
+            batch_size: 100,
+        }
+    }
+}
+
+impl SuspensionExpiryConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+}
+
+/// Resume businesses whose temporary suspension has run out, restoring what
+/// the suspension took down.
+pub fn spawn(db: Arc<DatabaseService>, config: SuspensionExpiryConfig, shutdown: &Shutdown) {
+    let mut ticker = shutdown.ticker(config.interval());
+    let stopping = shutdown.clone();
+    shutdown.spawn("suspension_expiry", async move {
+        let ctx = AuditContext::system("suspension_expiry");
+        while ticker.tick().await {
+            let registration_ids = match db.expired_suspensions(config.batch_size).await {
+                Ok(ids) => ids,
+                Err(err) => {
+                    log::error!("Failed to load expired suspensions: {err:?}");
+                    continue;
+                }
+            };
+            for registration_id in registration_ids {
+                // Each resume commits on its own; the rest wait for the next run
+                if stopping.is_shutting_down() {
+                    break;
+                }
+                let actor = SuspensionActor { id: None, name: None };
+                let notes = Some("Suspension expired".to_string());
+                match db.resume_registration(registration_id, actor, notes, &ctx).await {
+                    Ok(_) => log::info!("Resumed registration {} after its suspension expired", registration_id),
+                    Err(err) => log::error!("Failed to resume registration {}: {err:?}", registration_id),
+                }
+            }
+        }
+    });
+}
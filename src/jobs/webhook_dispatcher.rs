@@ -0,0 +1,164 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::future::join_all;
+use serde::Deserialize;
+
+use crate::clients::webhooks::WebhookClient;
+use crate::database::DatabaseService;
+use crate::models::PendingWebhookDelivery;
+use crate::shutdown::Shutdown;
+
+/// Longest wait between two attempts of the same delivery
+const MAX_BACKOFF_SECS: i64 = 3600;
+const BASE_BACKOFF_SECS: i64 = 30;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebhookDispatcherConfig {
+    pub poll_interval_secs: u64,
+    pub batch_size: i64,
+    pub max_attempts: i32,
+    /// Consecutive failures after which a subscription is disabled
+    pub disable_after_failures: i32,
+    pub request_timeout_secs: u64,
+}
+
+impl Default for WebhookDispatcherConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 5,
+            batch_size: 50,
+            max_attempts: 8,
+            disable_after_failures: 20,
+            request_timeout_secs: 10,
+        }
+    }
+}
+
+impl WebhookDispatcherConfig {
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval_secs)
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout_secs)
+    }
+}
+
+/// Poll for due webhook deliveries and send them until shutdown. A batch in
+/// flight is finished; claimed deliveries abandoned at the deadline are
+/// retried once their lease runs out.
+pub fn spawn(db: Arc<DatabaseService>, config: WebhookDispatcherConfig, shutdown: &Shutdown) {
+    let client = WebhookClient::new(config.request_timeout());
+
+    let mut ticker = shutdown.ticker(config.poll_interval());
+    shutdown.spawn("webhook_dispatcher", async move {
+        while ticker.tick().await {
+            if let Err(err) = dispatch_due(&db, &client, &config).await {
+                log::error!("Webhook dispatch cycle failed: {err:?}");
+            }
+        }
+    });
+}
+
+async fn dispatch_due(
+    db: &DatabaseService,
+    client: &WebhookClient,
+    config: &WebhookDispatcherConfig,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // The lease must outlive the HTTP call, otherwise another replica could
+    // claim the same delivery while it is still in flight.
+    let lease_secs = config.request_timeout_secs as i64 * 2 + 30;
+    let deliveries = db
+        .claim_due_webhook_deliveries(config.batch_size, lease_secs)
+        .await?;
+
+    if deliveries.is_empty() {
+        return Ok(());
+    }
+
+    log::debug!("Dispatching {} webhook deliveries", deliveries.len());
+    join_all(
+        deliveries
+            .iter()
+            .map(|delivery| deliver_one(db, client, config, delivery)),
+    )
+    .await;
+
+    Ok(())
+}
+
+async fn deliver_one(
+    db: &DatabaseService,
+    client: &WebhookClient,
+    config: &WebhookDispatcherConfig,
+    delivery: &PendingWebhookDelivery,
+) {
+    let body = match serde_json::to_vec(&delivery.payload) {
+        Ok(body) => body,
+        Err(err) => {
+            log::error!("Failed to serialize webhook payload {}: {err}", delivery.id);
+            return;
+        }
+    };
+
+    let result = client
+        .deliver(
+            &delivery.url,
+            &delivery.secret,
+            &delivery.event_type,
+            delivery.id,
+            body,
+        )
+        .await;
+
+    match result {
+        Ok(status) => {
+            if let Err(err) = db
+                .mark_webhook_delivery_succeeded(delivery, i32::from(status))
+                .await
+            {
+                log::error!("Failed to record webhook delivery {}: {err:?}", delivery.id);
+            }
+        }
+        Err(failure) => {
+            let attempt = delivery.attempts + 1;
+            let retry_in = (attempt < config.max_attempts).then(|| backoff_secs(attempt));
+            log::warn!(
+                "Webhook delivery {} to {} failed (attempt {}/{}): {}",
+                delivery.id,
+                delivery.url,
+                attempt,
+                config.max_attempts,
+                failure.message
+            );
+
+            match db
+                .mark_webhook_delivery_failed(
+                    delivery,
+                    failure.status.map(i32::from),
+                    &failure.message,
+                    retry_in,
+                    config.disable_after_failures,
+                )
+                .await
+            {
+                Ok(true) => log::warn!(
+                    "Webhook subscription {} disabled after repeated failures",
+                    delivery.subscription_id
+                ),
+                Ok(false) => {}
+                Err(err) => {
+                    log::error!("Failed to record webhook delivery {}: {err:?}", delivery.id)
+                }
+            }
+        }
+    }
+}
+
+/// Exponential backoff: 30s, 60s, 120s, ... capped at one hour.
+fn backoff_secs(attempt: i32) -> i64 {
+    let exponent = (attempt - 1).clamp(0, 16) as u32;
+    (BASE_BACKOFF_SECS * 2_i64.pow(exponent)).min(MAX_BACKOFF_SECS)
+}
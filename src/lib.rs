@@ -0,0 +1,36 @@
+//! CrazyTrip business review service: verification of business
+//! registrations and management of businesses, locations and promotions.
+//!
+//! The `crazytrip-business-review-service` binary serves [`app::build`];
+//! other binaries and the integration tests reuse the same pieces:
+//!
+//! - [`configure`] registers every route on an actix `ServiceConfig`;
+//! - [`AppState`] holds what the handlers share, built with
+//!   [`AppState::builder`];
+//! - [`models`] has the request, response and row types.
+
+pub mod app;
+pub mod audit;
+pub mod clients;
+pub mod config;
+pub mod content_policy;
+pub mod database;
+pub mod errors;
+pub mod events;
+mod export;
+pub mod handlers;
+pub mod health;
+mod i18n;
+pub mod jobs;
+mod location_import;
+mod merge_patch;
+pub mod metrics;
+pub mod models;
+pub mod moderation;
+pub mod rate_limit;
+mod recurrence;
+pub mod repository;
+pub mod shutdown;
+pub mod telemetry;
+
+pub use app::{configure, AppState, AppStateBuilder};
@@ -0,0 +1,173 @@
+//! Parsing and row validation for bulk location imports.
+//!
+//! Both formats are turned into JSON objects first so CSV and JSON rows go
+//! through the same `LocationImportRow` deserializer and validators. CSV cells
+//! are coerced to the column types of `CreateLocationRequest`; empty cells
+//! count as absent.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::{json, Map, Value};
+use validator::Validate;
+
+use crate::errors::{field_errors, AppError, FieldError};
+use crate::models::{LocationImportRow, LocationImportRowError};
+
+pub const MAX_IMPORT_ROWS: usize = 1000;
+const MAX_EXTERNAL_REF_LEN: usize = 120;
+
+const FLOAT_COLUMNS: &[&str] = &["latitude", "longitude"];
+const BOOL_COLUMNS: &[&str] = &["is_primary"];
+const JSON_COLUMNS: &[&str] = &["operating_hours"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Csv,
+    Json,
+}
+
+impl ImportFormat {
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        match essence.to_ascii_lowercase().as_str() {
+            "text/csv" | "application/csv" => Some(ImportFormat::Csv),
+            "application/json" => Some(ImportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Rows that passed validation, and the errors of those that did not
+#[derive(Debug)]
+pub struct ParsedImport {
+    pub total_rows: usize,
+    pub rows: Vec<LocationImportRow>,
+    pub errors: Vec<LocationImportRowError>,
+}
+
+/// Parse and validate an import body. Fails as a whole only when the body
+/// itself is unreadable, empty or too large; row problems end up in `errors`.
+pub fn parse(format: ImportFormat, body: &[u8]) -> Result<ParsedImport, AppError> {
+    let raw_rows = match format {
+        ImportFormat::Csv => csv_rows(body)?,
+        ImportFormat::Json => json_rows(body)?,
+    };
+    if raw_rows.is_empty() {
+        return Err(AppError::bad_request("empty_import", "The import contains no rows"));
+    }
+    if raw_rows.len() > MAX_IMPORT_ROWS {
+        return Err(AppError::ImportTooLarge { max: MAX_IMPORT_ROWS });
+    }
+
+    let total_rows = raw_rows.len();
+    let mut rows = Vec::with_capacity(total_rows);
+    let mut errors = Vec::new();
+    let mut seen_refs: HashMap<String, usize> = HashMap::new();
+
+    for (index, raw) in raw_rows.into_iter().enumerate() {
+        let row_number = index + 1;
+        let parsed = raw.and_then(|value| {
+            serde_json::from_value::<LocationImportRow>(value).map_err(|err| vec![invalid_row(err.to_string())])
+        });
+        let row = match parsed {
+            Ok(row) => row,
+            Err(field_errors) => {
+                errors.push(LocationImportRowError { row: row_number, external_ref: None, errors: field_errors });
+                continue;
+            }
+        };
+
+        let mut row_errors = match row.location.validate() {
+            Ok(()) => Vec::new(),
+            Err(validation) => field_errors(&validation),
+        };
+        if let Some(external_ref) = &row.external_ref {
+            let length = external_ref.trim().chars().count();
+            if length == 0 || length > MAX_EXTERNAL_REF_LEN {
+                let params = BTreeMap::from([
+                    ("min".to_string(), json!(1)),
+                    ("max".to_string(), json!(MAX_EXTERNAL_REF_LEN)),
+                ]);
+                row_errors.push(FieldError::new("external_ref", "length", params));
+            } else if let Some(first_row) = seen_refs.get(external_ref.trim()) {
+                let params = BTreeMap::from([("first_row".to_string(), json!(first_row))]);
+                row_errors.push(FieldError::new("external_ref", "duplicate", params));
+            } else {
+                seen_refs.insert(external_ref.trim().to_string(), row_number);
+            }
+        }
+
+        if row_errors.is_empty() {
+            rows.push(LocationImportRow {
+                external_ref: row.external_ref.map(|external_ref| external_ref.trim().to_string()),
+                ..row
+            });
+        } else {
+            errors.push(LocationImportRowError { row: row_number, external_ref: row.external_ref, errors: row_errors });
+        }
+    }
+
+    Ok(ParsedImport { total_rows, rows, errors })
+}
+
+fn invalid_row(detail: String) -> FieldError {
+    FieldError::with_detail("row", "invalid_type", detail)
+}
+
+fn json_rows(body: &[u8]) -> Result<Vec<Result<Value, Vec<FieldError>>>, AppError> {
+    let value: Value = serde_json::from_slice(body)
+        .map_err(|err| AppError::bad_request("malformed_json", err.to_string()))?;
+    match value {
+        Value::Array(items) => Ok(items.into_iter().map(Ok).collect()),
+        _ => Err(AppError::bad_request("malformed_json", "expected a JSON array of locations")),
+    }
+}
+
+/// One JSON object per record, or every cell that could not be converted
+fn csv_rows(body: &[u8]) -> Result<Vec<Result<Value, Vec<FieldError>>>, AppError> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(body);
+    let headers = reader
+        .headers()
+        .map_err(|err| AppError::bad_request("malformed_csv", err.to_string()))?
+        .clone();
+
+    Ok(reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(|err| vec![invalid_row(err.to_string())])?;
+            let mut object = Map::new();
+            let mut cell_errors = Vec::new();
+            for (column, cell) in headers.iter().zip(record.iter()).filter(|(_, cell)| !cell.is_empty()) {
+                match csv_cell(column, cell) {
+                    Ok(value) => {
+                        object.insert(column.to_string(), value);
+                    }
+                    Err(err) => cell_errors.push(err),
+                }
+            }
+            if cell_errors.is_empty() {
+                Ok(Value::Object(object))
+            } else {
+                Err(cell_errors)
+            }
+        })
+        .collect())
+}
+
+/// Typed JSON value of a CSV cell, based on its column
+fn csv_cell(column: &str, cell: &str) -> Result<Value, FieldError> {
+    let invalid = |detail: String| FieldError::with_detail(column, "invalid_type", detail);
+    if FLOAT_COLUMNS.contains(&column) {
+        cell.parse::<f64>().map(|number| json!(number)).map_err(|err| invalid(err.to_string()))
+    } else if BOOL_COLUMNS.contains(&column) {
+        match cell.to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" | "si" | "sí" => Ok(Value::Bool(true)),
+            "false" | "0" | "no" => Ok(Value::Bool(false)),
+            other => Err(invalid(format!("expected true or false, got `{other}`"))),
+        }
+    } else if JSON_COLUMNS.contains(&column) {
+        serde_json::from_str(cell).map_err(|err| invalid(err.to_string()))
+    } else {
+        Ok(Value::String(cell.to_string()))
+    }
+}
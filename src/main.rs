@@ -1,107 +1,157 @@
-mod clients;
-mod database;
-mod handlers;
-mod models;
-
-use actix_cors::Cors;
-use actix_web::{middleware::Logger, web, App, HttpServer};
-use std::env;
+use actix_web::HttpServer;
 use std::sync::Arc;
+use std::time::Duration;
 
-use crate::clients::stories::StoriesClient;
-use crate::database::DatabaseService;
+use crazytrip_business_review_service::clients::stories::StoriesClient;
+use crazytrip_business_review_service::config::AppConfig;
+use crazytrip_business_review_service::content_policy::ContentPolicy;
+use crazytrip_business_review_service::database::DatabaseService;
+use crazytrip_business_review_service::jobs::{
+    audit_retention, metrics_collector, outbox_relay, promotion_scheduler, soft_delete_purge, suspension_expiry,
+    webhook_dispatcher,
+};
+use crazytrip_business_review_service::rate_limit::RateLimiter;
+use crazytrip_business_review_service::repository::{Repositories, StorageBackend};
+use crazytrip_business_review_service::shutdown::Shutdown;
+use crazytrip_business_review_service::{app, events, rate_limit, shutdown, telemetry, AppState};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // Load environment variables
     dotenv::dotenv().ok();
-    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
-
-    let host = env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
-    let port = env::var("PORT").unwrap_or_else(|_| "8082".to_string());
-    let bind_address = format!("{}:{}", host, port);
-    let stories_service_url = env::var("STORIES_SERVICE_URL")
-        .unwrap_or_else(|_| "http://localhost:8083".to_string());
-
-    let database_url = env::var("DATABASE_URL").map_err(|_| {
-        std::io::Error::new(
-            std::io::ErrorKind::InvalidInput,
-            "DATABASE_URL must be set in environment",
-        )
+    let telemetry = telemetry::init().map_err(|err| {
+        eprintln!("Failed to initialize logging: {err}");
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, err)
     })?;
 
-    let db = DatabaseService::new(&database_url).await.map_err(|err| {
-        log::error!("Failed to initialize database: {err:?}");
-        std::io::Error::new(std::io::ErrorKind::Other, err)
+    let config = AppConfig::load().map_err(|err| {
+        log::error!("Invalid configuration: {err}");
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, err)
     })?;
+    let bind_address = config.server.bind_address();
+
+    let db = match config.database.backend {
+        StorageBackend::Postgres => {
+            let db = DatabaseService::new(&config.database).await.map_err(|err| {
+                log::error!("Failed to initialize database: {err:?}");
+                std::io::Error::other(err)
+            })?;
+
+            // Initialize schema (though we use migrations, this ensures connection)
+            if let Err(e) = db.init_schema().await {
+                log::error!("Failed to initialize DB schema: {:#?}", e);
+            } else {
+                log::info!("DB schema ensured");
+            }
+            Some(Arc::new(db))
+        }
+        StorageBackend::Memory => {
+            log::warn!("Keeping data in memory: it is lost on restart and Postgres-only routes answer 501");
+            None
+        }
+    };
+
+    let content_policy = Arc::new(ContentPolicy::from_config(config.content_policy.clone()).map_err(|err| {
+        log::error!("Failed to load content policy: {err}");
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, err)
+    })?);
 
-    // Initialize schema (though we use migrations, this ensures connection)
-    if let Err(e) = db.init_schema().await {
-        log::error!("Failed to initialize DB schema: {:#?}", e);
-    } else {
-        log::info!("DB schema ensured");
+    let repositories = match &db {
+        Some(db) => Repositories::postgres(db.clone()),
+        None => Repositories::memory(),
+    };
+
+    let shutdown = Shutdown::new();
+    let event_publisher = events::publisher_from_config(&config.events).await.map_err(|err| {
+        log::error!("Failed to initialize event publisher: {err}");
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, err)
+    })?;
+    let jobs = &config.jobs;
+    outbox_relay::spawn(repositories.outbox.clone(), event_publisher, jobs.outbox_relay.clone(), &shutdown);
+    // The other background jobs work on Postgres tables
+    if let Some(db) = &db {
+        webhook_dispatcher::spawn(db.clone(), jobs.webhook_dispatcher.clone(), &shutdown);
+        audit_retention::spawn(db.clone(), jobs.audit_retention.clone(), &shutdown);
+        soft_delete_purge::spawn(db.clone(), jobs.soft_delete_purge.clone(), &shutdown);
+        suspension_expiry::spawn(db.clone(), jobs.suspension_expiry.clone(), &shutdown);
+        promotion_scheduler::spawn(
+            db.clone(),
+            jobs.promotion_scheduler.clone(),
+            config.moderation,
+            content_policy.clone(),
+            &shutdown,
+        );
+        metrics_collector::spawn(db.clone(), jobs.metrics_collector.clone(), &shutdown);
     }
 
-    let db_data = web::Data::new(Arc::new(db));
-    let stories_client = web::Data::new(StoriesClient::new(stories_service_url));
+    let rate_limiter = Arc::new(RateLimiter::from_config(config.rate_limit.clone(), db.clone()).map_err(|err| {
+        log::error!("Failed to configure rate limiting: {err}");
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, err)
+    })?);
+    rate_limit::spawn_pruner(rate_limiter.clone(), &shutdown);
+
+    let server_config = config.server.clone();
+    let tls_config = server_config
+        .tls
+        .as_ref()
+        .map(|tls| tls.server_config())
+        .transpose()
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+    let state = match &db {
+        Some(db) => AppState::builder(db.clone()),
+        None => AppState::in_memory(),
+    };
+    let state = state
+        .repositories(repositories)
+        .stories_client(StoriesClient::new(
+            config.clients.stories_url.clone(),
+            config.clients.request_timeout(),
+        ))
+        .moderation(config.moderation)
+        .content_policy(content_policy)
+        .rate_limiter(rate_limiter)
+        .health_config(config.health.clone())
+        .shutdown(shutdown.clone())
+        .cors(config.cors.clone())
+        .body_limits(&server_config)
+        .build();
 
     log::info!(
-        "🚀 Starting CrazyTrip Business Review Service on {}",
+        "🚀 Starting CrazyTrip Business Review Service on {}://{}",
+        if tls_config.is_some() { "https" } else { "http" },
         bind_address
     );
 
-    HttpServer::new(move || {
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allow_any_method()
-            .allow_any_header()
-            .max_age(3600);
+    let server = HttpServer::new(move || app::build(&state))
+    .keep_alive(Duration::from_secs(server_config.keep_alive_secs))
+    .client_request_timeout(Duration::from_secs(server_config.client_request_timeout_secs))
+    .shutdown_timeout(server_config.shutdown_timeout_secs)
+    .disable_signals();
+    let server = match server_config.workers {
+        Some(workers) => server.workers(workers),
+        None => server,
+    };
+    let server = match tls_config {
+        Some(tls_config) => server.bind_rustls_0_23(&bind_address, tls_config)?.run(),
+        None => server.bind(&bind_address)?.run(),
+    };
 
-        App::new()
-            .app_data(db_data.clone())
-            .app_data(stories_client.clone())
-            .wrap(cors)
-            .wrap(Logger::default())
-            .service(
-                web::scope("/api/v1")
-                    // Health
-                    .service(handlers::health_check)
-                    // Registrations (verification workflow)
-                    .service(handlers::submit_registration)
-                    .service(handlers::get_registration)
-                    .service(handlers::get_latest_registration_for_user)
-                    .service(handlers::list_registrations_for_user)
-                    // Review system
-                    .service(handlers::list_pending_reviews)
-                    .service(handlers::get_business_review)
-                    .service(handlers::submit_review_action)
-                    .service(handlers::get_review_stats)
-                    // Businesses
-                    .service(handlers::create_business)
-                    .service(handlers::get_business)
-                    .service(handlers::list_businesses_for_user)
-                    .service(handlers::update_business)
-                    .service(handlers::delete_business)
-                    // Locations
-                    .service(handlers::create_location)
-                    .service(handlers::get_location)
-                    .service(handlers::list_locations_for_business)
-                    .service(handlers::update_location)
-                    .service(handlers::delete_location)
-                    // Promotions
-                    .service(handlers::create_promotion)
-                    .service(handlers::get_promotion)
-                    .service(handlers::list_promotions_for_location)
-                    .service(handlers::list_promotions_for_business)
-                    .service(handlers::update_promotion)
-                    .service(handlers::delete_promotion)
-                    // Location Admins
-                    .service(handlers::add_location_admin)
-                    .service(handlers::list_location_admins)
-                    .service(handlers::remove_location_admin),
-            )
-    })
-    .bind(&bind_address)?
-    .run()
-    .await
+    // Signals start the shutdown of requests and background tasks together,
+    // so both share the deadline
+    let server_handle = server.handle();
+    let on_signal = shutdown.clone();
+    actix_rt::spawn(async move {
+        shutdown::signal().await;
+        log::info!("Shutting down; draining requests and background tasks");
+        on_signal.begin();
+        server_handle.stop(true).await;
+    });
+
+    let result = server.await;
+    shutdown.drain(Duration::from_secs(server_config.shutdown_timeout_secs)).await;
+    if let Some(db) = &db {
+        db.close().await;
+    }
+    telemetry.shutdown();
+    result
 }
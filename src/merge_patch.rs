@@ -0,0 +1,41 @@
+//! JSON Merge Patch (RFC 7396) for the PATCH endpoints.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Apply `patch` to `target` in place: objects merge recursively, `null`
+/// removes a member, anything else replaces the target value.
+pub fn merge(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// Patch the update document of an entity and read it back.
+///
+/// `current` is the full update request built from the stored entity, so
+/// members the patch leaves out keep their stored value and members it sets to
+/// `null` come back as `None` (or fail to deserialize when required). The
+/// error is the deserializer message for the merged document.
+pub fn apply<T>(current: &T, patch: &Value) -> Result<T, String>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let mut document = serde_json::to_value(current).map_err(|e| e.to_string())?;
+    merge(&mut document, patch);
+    serde_json::from_value(document).map_err(|e| e.to_string())
+}
@@ -0,0 +1,211 @@
+//! Prometheus metrics served at `GET /metrics`.
+//!
+//! HTTP traffic and calls to the stories service are recorded as they
+//! happen. Pool gauges are read on every scrape, and the domain gauges
+//! (review queues, active promotions, outbox backlog) are refreshed by the
+//! `metrics_collector` job so a scrape never waits on those queries.
+
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use prometheus::{
+    Encoder, Gauge, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use crate::database::{DomainSnapshot, PoolStatus};
+
+/// Prefix of every metric name
+const NAMESPACE: &str = "business_review";
+
+/// Route label of requests that matched no route, so scanners probing random
+/// paths cannot blow up the number of series
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_duration: HistogramVec,
+    http_in_flight: IntGauge,
+    pool_max_size: IntGauge,
+    pool_size: IntGauge,
+    pool_available: IntGauge,
+    stories_requests: IntCounterVec,
+    stories_duration: HistogramVec,
+    pending_registrations: IntGauge,
+    oldest_pending_registration: Gauge,
+    pending_promotion_reviews: IntGauge,
+    oldest_pending_promotion_review: Gauge,
+    active_promotions: IntGaugeVec,
+    unpublished_events: IntGauge,
+    domain_refreshed_at: Gauge,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some(NAMESPACE.to_string()), None).expect("valid metrics namespace");
+        let metrics = Self {
+            http_requests: IntCounterVec::new(
+                Opts::new("http_requests_total", "HTTP requests handled, by route and status"),
+                &["method", "route", "status"],
+            )
+            .expect("valid metric"),
+            http_duration: HistogramVec::new(
+                HistogramOpts::new("http_request_duration_seconds", "Time to produce the HTTP response")
+                    .buckets(vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
+                &["method", "route", "status"],
+            )
+            .expect("valid metric"),
+            http_in_flight: IntGauge::new("http_requests_in_flight", "HTTP requests being handled").expect("valid metric"),
+            pool_max_size: IntGauge::new("db_pool_max_size", "Largest number of Postgres connections the pool opens")
+                .expect("valid metric"),
+            pool_size: IntGauge::new("db_pool_size", "Postgres connections currently open").expect("valid metric"),
+            pool_available: IntGauge::new("db_pool_available", "Open Postgres connections not in use")
+                .expect("valid metric"),
+            stories_requests: IntCounterVec::new(
+                Opts::new("stories_client_requests_total", "Calls to the stories service, by outcome"),
+                &["operation", "outcome"],
+            )
+            .expect("valid metric"),
+            stories_duration: HistogramVec::new(
+                HistogramOpts::new("stories_client_request_duration_seconds", "Duration of calls to the stories service"),
+                &["operation", "outcome"],
+            )
+            .expect("valid metric"),
+            pending_registrations: IntGauge::new(
+                "registrations_pending",
+                "Business registrations waiting for review",
+            )
+            .expect("valid metric"),
+            oldest_pending_registration: Gauge::new(
+                "registrations_oldest_pending_age_seconds",
+                "Age of the oldest registration waiting for review",
+            )
+            .expect("valid metric"),
+            pending_promotion_reviews: IntGauge::new(
+                "promotions_pending_review",
+                "Promotions waiting in the moderation queue",
+            )
+            .expect("valid metric"),
+            oldest_pending_promotion_review: Gauge::new(
+                "promotions_oldest_pending_review_age_seconds",
+                "Time the oldest promotion has waited in the moderation queue",
+            )
+            .expect("valid metric"),
+            active_promotions: IntGaugeVec::new(
+                Opts::new("promotions_active", "Active promotions, by promotion type"),
+                &["promotion_type"],
+            )
+            .expect("valid metric"),
+            unpublished_events: IntGauge::new("outbox_unpublished_events", "Domain events not yet published")
+                .expect("valid metric"),
+            domain_refreshed_at: Gauge::new(
+                "domain_metrics_refreshed_timestamp_seconds",
+                "Unix time of the last successful refresh of the domain gauges",
+            )
+            .expect("valid metric"),
+            registry,
+        };
+        metrics.register_all();
+        metrics
+    }
+
+    fn register_all(&self) {
+        let collectors: Vec<Box<dyn prometheus::core::Collector>> = vec![
+            Box::new(self.http_requests.clone()),
+            Box::new(self.http_duration.clone()),
+            Box::new(self.http_in_flight.clone()),
+            Box::new(self.pool_max_size.clone()),
+            Box::new(self.pool_size.clone()),
+            Box::new(self.pool_available.clone()),
+            Box::new(self.stories_requests.clone()),
+            Box::new(self.stories_duration.clone()),
+            Box::new(self.pending_registrations.clone()),
+            Box::new(self.oldest_pending_registration.clone()),
+            Box::new(self.pending_promotion_reviews.clone()),
+            Box::new(self.oldest_pending_promotion_review.clone()),
+            Box::new(self.active_promotions.clone()),
+            Box::new(self.unpublished_events.clone()),
+            Box::new(self.domain_refreshed_at.clone()),
+        ];
+        for collector in collectors {
+            self.registry.register(collector).expect("metric registered once");
+        }
+    }
+
+    /// Every metric in the Prometheus text exposition format
+    pub fn render(&self) -> Result<String, String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .map_err(|err| err.to_string())?;
+        String::from_utf8(buffer).map_err(|err| err.to_string())
+    }
+
+    pub fn content_type(&self) -> String {
+        TextEncoder::new().format_type().to_string()
+    }
+
+    pub fn observe_http(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        let status = status.to_string();
+        let labels = [method, route, status.as_str()];
+        self.http_requests.with_label_values(&labels).inc();
+        self.http_duration.with_label_values(&labels).observe(elapsed.as_secs_f64());
+    }
+
+    pub fn observe_stories_call(&self, operation: &str, outcome: &str, elapsed: Duration) {
+        let labels = [operation, outcome];
+        self.stories_requests.with_label_values(&labels).inc();
+        self.stories_duration.with_label_values(&labels).observe(elapsed.as_secs_f64());
+    }
+
+    pub fn set_pool_status(&self, status: PoolStatus) {
+        self.pool_max_size.set(status.max_size as i64);
+        self.pool_size.set(status.size as i64);
+        self.pool_available.set(status.available as i64);
+    }
+
+    pub fn set_domain(&self, snapshot: &DomainSnapshot) {
+        self.pending_registrations.set(snapshot.pending_registrations);
+        self.oldest_pending_registration.set(snapshot.oldest_pending_registration_secs);
+        self.pending_promotion_reviews.set(snapshot.pending_promotion_reviews);
+        self.oldest_pending_promotion_review.set(snapshot.oldest_pending_promotion_review_secs);
+        for (promotion_type, active) in &snapshot.active_promotions_by_type {
+            self.active_promotions.with_label_values(&[promotion_type]).set(*active);
+        }
+        self.unpublished_events.set(snapshot.unpublished_events);
+        self.domain_refreshed_at.set(chrono::Utc::now().timestamp_millis() as f64 / 1000.0);
+    }
+}
+
+/// Middleware counting every response and timing it, labelled with the
+/// route pattern (e.g. `/api/v1/promotions/{promotion_id}`) rather than the
+/// raw path
+pub async fn track<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<B>, actix_web::Error> {
+    let metrics = metrics();
+    let method = req.method().to_string();
+    let route = req.match_pattern().unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let started = Instant::now();
+
+    metrics.http_in_flight.inc();
+    let result = next.call(req).await;
+    metrics.http_in_flight.dec();
+
+    let status = match &result {
+        Ok(res) => res.status(),
+        Err(err) => err.as_response_error().status_code(),
+    };
+    metrics.observe_http(&method, &route, status.as_u16(), started.elapsed());
+    result
+}
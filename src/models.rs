@@ -1,17 +1,25 @@
-use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueRef, Postgres};
+use sqlx::types::Json;
+use sqlx::{Decode, Encode};
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
+
+use crate::errors::FieldError;
 
 // ============================================================================
 // ENUMS
 // ============================================================================
 
 /// Business verification status (this is also a Postgres enum)
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, postgres_types::ToSql, postgres_types::FromSql)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
 #[sqlx(type_name = "business_verification_status", rename_all = "snake_case")]
-
 #[serde(rename_all = "snake_case")]
 pub enum BusinessVerificationStatus {
     Pending,
@@ -22,7 +30,7 @@ pub enum BusinessVerificationStatus {
 }
 
 /// Review actions applied by reviewers (also a Postgres enum)
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, postgres_types::ToSql, postgres_types::FromSql)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
 #[sqlx(type_name = "business_review_action", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum ReviewAction {
@@ -35,7 +43,7 @@ pub enum ReviewAction {
 }
 
 /// Promotion category type
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, postgres_types::ToSql, postgres_types::FromSql)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
 #[sqlx(type_name = "business_promotion_type", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum BusinessPromotionType {
@@ -46,7 +54,7 @@ pub enum BusinessPromotionType {
 }
 
 /// Promotion lifecycle status
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, postgres_types::ToSql, postgres_types::FromSql)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
 #[sqlx(type_name = "business_promotion_status", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum BusinessPromotionStatus {
@@ -55,10 +63,50 @@ pub enum BusinessPromotionStatus {
     Active,
     Expired,
     Cancelled,
+    /// Waiting for a reviewer before it can be scheduled or go live
+    PendingReview,
+    /// Turned down by a reviewer; editing it back to scheduled or active
+    /// submits it again
+    Rejected,
+    /// Taken down while its business is suspended; resuming the business
+    /// restores the previous status
+    Paused,
+}
+
+impl BusinessPromotionStatus {
+    /// Visible to customers now or once it starts
+    pub fn is_published(self) -> bool {
+        matches!(self, BusinessPromotionStatus::Scheduled | BusinessPromotionStatus::Active)
+    }
+}
+
+/// Which locations a promotion applies to (also a Postgres enum)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "promotion_target_scope", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PromotionTargetScope {
+    /// The single location in `location_id`
+    Location,
+    /// An explicit set of locations of the business
+    Locations,
+    /// Every location of the business, including ones added later
+    Business,
+}
+
+/// What `max_claims` caps (also a Postgres enum)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "promotion_limit_scope", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PromotionLimitScope {
+    /// Claims across all locations
+    #[default]
+    Global,
+    /// Claims at each location separately
+    PerLocation,
 }
 
 /// Location admin role
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, postgres_types::ToSql, postgres_types::FromSql)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
 #[sqlx(type_name = "location_admin_role", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum LocationAdminRole {
@@ -67,12 +115,180 @@ pub enum LocationAdminRole {
     Staff,
 }
 
+/// Webhook delivery lifecycle status (also a Postgres enum)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+/// Events that partners can subscribe to through webhooks
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum WebhookEventType {
+    #[serde(rename = "business.approved")]
+    BusinessApproved,
+    #[serde(rename = "business.rejected")]
+    BusinessRejected,
+    #[serde(rename = "business.more_info_requested")]
+    BusinessMoreInfoRequested,
+    #[serde(rename = "business.suspended")]
+    BusinessSuspended,
+    #[serde(rename = "business.resumed")]
+    BusinessResumed,
+    #[serde(rename = "promotion.created")]
+    PromotionCreated,
+    #[serde(rename = "promotion.updated")]
+    PromotionUpdated,
+    #[serde(rename = "promotion.published")]
+    PromotionPublished,
+    #[serde(rename = "location.created")]
+    LocationCreated,
+    #[serde(rename = "location.updated")]
+    LocationUpdated,
+    #[serde(rename = "location.deleted")]
+    LocationDeleted,
+    #[serde(rename = "location.restored")]
+    LocationRestored,
+    #[serde(rename = "location_admin.added")]
+    LocationAdminAdded,
+    #[serde(rename = "location_admin.removed")]
+    LocationAdminRemoved,
+}
+
+impl WebhookEventType {
+    pub const ALL: [WebhookEventType; 14] = [
+        WebhookEventType::BusinessApproved,
+        WebhookEventType::BusinessRejected,
+        WebhookEventType::BusinessMoreInfoRequested,
+        WebhookEventType::BusinessSuspended,
+        WebhookEventType::BusinessResumed,
+        WebhookEventType::PromotionCreated,
+        WebhookEventType::PromotionUpdated,
+        WebhookEventType::PromotionPublished,
+        WebhookEventType::LocationCreated,
+        WebhookEventType::LocationUpdated,
+        WebhookEventType::LocationDeleted,
+        WebhookEventType::LocationRestored,
+        WebhookEventType::LocationAdminAdded,
+        WebhookEventType::LocationAdminRemoved,
+    ];
+
+    /// Wire name used in payloads, headers and the `event_types` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::BusinessApproved => "business.approved",
+            WebhookEventType::BusinessRejected => "business.rejected",
+            WebhookEventType::BusinessMoreInfoRequested => "business.more_info_requested",
+            WebhookEventType::BusinessSuspended => "business.suspended",
+            WebhookEventType::BusinessResumed => "business.resumed",
+            WebhookEventType::PromotionCreated => "promotion.created",
+            WebhookEventType::PromotionUpdated => "promotion.updated",
+            WebhookEventType::PromotionPublished => "promotion.published",
+            WebhookEventType::LocationCreated => "location.created",
+            WebhookEventType::LocationUpdated => "location.updated",
+            WebhookEventType::LocationDeleted => "location.deleted",
+            WebhookEventType::LocationRestored => "location.restored",
+            WebhookEventType::LocationAdminAdded => "location_admin.added",
+            WebhookEventType::LocationAdminRemoved => "location_admin.removed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|event| event.as_str() == value)
+    }
+}
+
+/// Stored by wire name in the `text[]` column `event_types`
+impl sqlx::Type<Postgres> for WebhookEventType {
+    fn type_info() -> PgTypeInfo {
+        <&str as sqlx::Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as sqlx::Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl PgHasArrayType for WebhookEventType {
+    fn array_type_info() -> PgTypeInfo {
+        <&str as PgHasArrayType>::array_type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for WebhookEventType {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <&str as Encode<Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for WebhookEventType {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let value = <&str as Decode<Postgres>>::decode(value)?;
+        Self::parse(value).ok_or_else(|| format!("unknown webhook event type `{value}`").into())
+    }
+}
+
+// ============================================================================
+// CONTENT POLICY
+// ============================================================================
+
+/// How much a content policy finding should weigh in a review
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingSeverity {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+/// Something a content policy rule flagged in a text field
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContentFinding {
+    /// Stable code of the check, e.g. `banned_word` or `phone_number`
+    pub rule: String,
+    pub field: String,
+    pub severity: FindingSeverity,
+    /// The offending text, shortened
+    pub excerpt: Option<String>,
+}
+
+/// Findings stored with a promotion or registration, as a `jsonb` column
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ContentFindings(pub Vec<ContentFinding>);
+
+impl sqlx::Type<Postgres> for ContentFindings {
+    fn type_info() -> PgTypeInfo {
+        <Json<Vec<ContentFinding>> as sqlx::Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <Json<Vec<ContentFinding>> as sqlx::Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for ContentFindings {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        Json(&self.0).encode_by_ref(buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for ContentFindings {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Json::<Vec<ContentFinding>>::decode(value).map(|json| ContentFindings(json.0))
+    }
+}
+
 // ============================================================================
 // BUSINESS REGISTRATION (Verification Workflow)
 // ============================================================================
 
 /// Business registration request persisted in database
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BusinessRegistration {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -84,7 +300,7 @@ pub struct BusinessRegistration {
     pub phone: Option<String>,
     pub website: Option<String>,
     pub tax_id: Option<String>,
-    pub document_urls: Vec<String>,
+    pub document_urls: Json<Vec<String>>,
     pub is_multi_user_team: bool,
     pub status: BusinessVerificationStatus,
     pub owner_email: String,
@@ -93,6 +309,8 @@ pub struct BusinessRegistration {
     pub reviewer_notes: Option<String>,
     pub reviewer_id: Option<Uuid>,
     pub reviewer_name: Option<String>,
+    #[serde(default)]
+    pub content_findings: ContentFindings,
     pub submitted_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -110,7 +328,7 @@ pub struct NewBusinessRegistration {
     pub phone: Option<String>,
     pub website: Option<String>,
     pub tax_id: Option<String>,
-    pub document_urls: Vec<String>,
+    pub document_urls: Json<Vec<String>>,
     pub is_multi_user_team: bool,
     pub status: BusinessVerificationStatus,
     pub owner_email: String,
@@ -119,12 +337,14 @@ pub struct NewBusinessRegistration {
     pub reviewer_notes: Option<String>,
     pub reviewer_id: Option<Uuid>,
     pub reviewer_name: Option<String>,
+    #[serde(default)]
+    pub content_findings: ContentFindings,
     pub submitted_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 /// Historical review event for auditing purposes
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BusinessReviewEvent {
     pub id: Uuid,
     pub registration_id: Uuid,
@@ -137,17 +357,19 @@ pub struct BusinessReviewEvent {
 }
 
 /// Pending business registration for review dashboards
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PendingBusinessReview {
     pub id: Uuid,
     pub name: String,
     pub category: String,
     pub address: String,
     pub tax_id: Option<String>,
-    pub document_urls: Vec<String>,
+    pub document_urls: Json<Vec<String>>,
     pub submitted_at: DateTime<Utc>,
     pub owner_email: String,
     pub owner_username: String,
+    #[serde(default)]
+    pub content_findings: ContentFindings,
 }
 
 /// Aggregated statistics for review dashboards
@@ -159,12 +381,42 @@ pub struct ReviewStats {
     pub rejected_today: i64,
 }
 
+/// What suspending a registration changed, so resuming can undo exactly that
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusinessSuspension {
+    pub id: Uuid,
+    pub registration_id: Uuid,
+    pub business_id: Option<Uuid>,
+    /// Registration status to return to on resume
+    pub previous_status: BusinessVerificationStatus,
+    pub business_was_active: bool,
+    pub hidden_location_ids: Vec<Uuid>,
+    pub promotions: Vec<SuspendedPromotion>,
+    /// Promotions were cancelled rather than paused and are not restored
+    pub cancel_promotions: bool,
+    pub reason: Option<String>,
+    pub suspended_by: Option<Uuid>,
+    pub suspended_by_name: Option<String>,
+    pub suspended_at: DateTime<Utc>,
+    /// Resumed automatically at this time, when set
+    pub expires_at: Option<DateTime<Utc>>,
+    pub resumed_at: Option<DateTime<Utc>>,
+    pub resumed_by: Option<Uuid>,
+    pub resumed_by_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspendedPromotion {
+    pub promotion_id: Uuid,
+    pub previous_status: BusinessPromotionStatus,
+}
+
 // ============================================================================
 // APPROVED BUSINESSES
 // ============================================================================
 
 /// Approved business entity
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Business {
     pub id: Uuid,
     pub registration_id: Option<Uuid>,
@@ -176,9 +428,14 @@ pub struct Business {
     pub website: Option<String>,
     pub logo_url: Option<String>,
     pub is_active: bool,
+    /// Promotions of trusted businesses skip moderation
+    pub promotions_trusted: bool,
     pub metadata: Value,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+    pub version: i32,
 }
 
 /// Helper for creating new business
@@ -204,7 +461,7 @@ pub struct NewBusiness {
 // ============================================================================
 
 /// Business location/branch
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BusinessLocation {
     pub id: Uuid,
     pub business_id: Uuid,
@@ -228,6 +485,9 @@ pub struct BusinessLocation {
     pub metadata: Value,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+    pub version: i32,
 }
 
 /// Helper for creating new location
@@ -256,14 +516,18 @@ pub struct NewBusinessLocation {
 }
 
 // ============================================================================
-// BUSINESS PROMOTIONS (Per Location)
+// BUSINESS PROMOTIONS
 // ============================================================================
 
-/// Promotion for a specific location
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+/// Promotion for one location, a set of locations or a whole business
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BusinessPromotion {
     pub id: Uuid,
-    pub location_id: Uuid,
+    pub business_id: Uuid,
+    /// Set only for single-location promotions
+    pub location_id: Option<Uuid>,
+    pub target_scope: PromotionTargetScope,
+    pub limit_scope: PromotionLimitScope,
     pub title: String,
     pub subtitle: Option<String>,
     pub description: Option<String>,
@@ -280,6 +544,8 @@ pub struct BusinessPromotion {
     pub requires_purchase: bool,
     pub terms: Option<String>,
     pub metadata: Value,
+    #[serde(default)]
+    pub content_findings: ContentFindings,
     pub starts_at: DateTime<Utc>,
     pub ends_at: DateTime<Utc>,
     pub published_at: Option<DateTime<Utc>>,
@@ -287,13 +553,21 @@ pub struct BusinessPromotion {
     pub updated_by: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+    pub version: i32,
 }
 
 /// Helper for creating new promotion
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewBusinessPromotion {
     pub id: Uuid,
-    pub location_id: Uuid,
+    pub business_id: Uuid,
+    pub location_id: Option<Uuid>,
+    pub target_scope: PromotionTargetScope,
+    pub limit_scope: PromotionLimitScope,
+    /// Targeted locations; empty for business-wide promotions
+    pub location_ids: Vec<Uuid>,
     pub title: String,
     pub subtitle: Option<String>,
     pub description: Option<String>,
@@ -310,6 +584,8 @@ pub struct NewBusinessPromotion {
     pub requires_purchase: bool,
     pub terms: Option<String>,
     pub metadata: Value,
+    #[serde(default)]
+    pub content_findings: ContentFindings,
     pub starts_at: DateTime<Utc>,
     pub ends_at: DateTime<Utc>,
     pub published_at: Option<DateTime<Utc>>,
@@ -319,12 +595,137 @@ pub struct NewBusinessPromotion {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Moderation decision on a promotion, kept like `BusinessReviewEvent`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromotionReviewEvent {
+    pub id: Uuid,
+    pub promotion_id: Uuid,
+    pub reviewer_id: Option<Uuid>,
+    pub reviewer_name: Option<String>,
+    pub action: ReviewAction,
+    pub notes: Option<String>,
+    pub rejection_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Promotion waiting in the moderation queue
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingPromotionReview {
+    #[serde(flatten)]
+    pub promotion: BusinessPromotion,
+    pub business_name: String,
+}
+
+/// Recurring promotion; the scheduler materializes one `BusinessPromotion`
+/// per occurrence of `rrule`, a rolling window ahead
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromotionTemplate {
+    pub id: Uuid,
+    pub location_id: Uuid,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub description: Option<String>,
+    pub promotion_type: BusinessPromotionType,
+    pub image_url: Option<String>,
+    pub prize: Option<String>,
+    pub reward_points: i32,
+    pub discount_percent: Option<i32>,
+    pub max_claims: Option<i32>,
+    pub per_user_limit: Option<i32>,
+    pub requires_check_in: bool,
+    pub requires_purchase: bool,
+    pub terms: Option<String>,
+    pub metadata: Value,
+    pub rrule: String,
+    /// First occurrence, in local time of `timezone`
+    pub dtstart: NaiveDateTime,
+    pub duration_minutes: i32,
+    pub timezone: String,
+    pub is_active: bool,
+    pub materialized_until: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PromotionTemplate {
+    /// Promotion for the occurrence starting at `starts_at`, tagged with
+    /// `metadata.template_id`
+    pub fn occurrence(&self, business_id: Uuid, starts_at: DateTime<Utc>) -> NewBusinessPromotion {
+        let now = Utc::now();
+        let mut metadata = match &self.metadata {
+            Value::Object(map) => map.clone(),
+            _ => Default::default(),
+        };
+        metadata.insert("template_id".to_string(), Value::String(self.id.to_string()));
+
+        NewBusinessPromotion {
+            id: Uuid::new_v4(),
+            business_id,
+            location_id: Some(self.location_id),
+            target_scope: PromotionTargetScope::Location,
+            limit_scope: PromotionLimitScope::Global,
+            location_ids: vec![self.location_id],
+            title: self.title.clone(),
+            subtitle: self.subtitle.clone(),
+            description: self.description.clone(),
+            promotion_type: self.promotion_type,
+            status: BusinessPromotionStatus::Scheduled,
+            image_url: self.image_url.clone(),
+            prize: self.prize.clone(),
+            reward_points: self.reward_points,
+            discount_percent: self.discount_percent,
+            max_claims: self.max_claims,
+            per_user_limit: self.per_user_limit,
+            total_claims: 0,
+            requires_check_in: self.requires_check_in,
+            requires_purchase: self.requires_purchase,
+            terms: self.terms.clone(),
+            metadata: Value::Object(metadata),
+            content_findings: ContentFindings::default(),
+            starts_at,
+            ends_at: starts_at + Duration::minutes(i64::from(self.duration_minutes)),
+            published_at: None,
+            created_by: self.created_by,
+            updated_by: self.created_by,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Helper for creating new promotion template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewPromotionTemplate {
+    pub id: Uuid,
+    pub location_id: Uuid,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub description: Option<String>,
+    pub promotion_type: BusinessPromotionType,
+    pub image_url: Option<String>,
+    pub prize: Option<String>,
+    pub reward_points: i32,
+    pub discount_percent: Option<i32>,
+    pub max_claims: Option<i32>,
+    pub per_user_limit: Option<i32>,
+    pub requires_check_in: bool,
+    pub requires_purchase: bool,
+    pub terms: Option<String>,
+    pub metadata: Value,
+    pub rrule: String,
+    pub dtstart: NaiveDateTime,
+    pub duration_minutes: i32,
+    pub timezone: String,
+    pub created_by: Option<Uuid>,
+}
+
 // ============================================================================
 // LOCATION ADMINISTRATORS
 // ============================================================================
 
 /// Administrator for a specific location
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocationAdmin {
     pub id: Uuid,
     pub location_id: Uuid,
@@ -357,6 +758,220 @@ pub struct NewLocationAdmin {
     pub updated_at: DateTime<Utc>,
 }
 
+// ============================================================================
+// WEBHOOKS (Outbound integrations)
+// ============================================================================
+
+/// Partner endpoint subscribed to one or more event types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub event_types: Vec<WebhookEventType>,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub consecutive_failures: i32,
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub disabled_reason: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Helper for creating new webhook subscription
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewWebhookSubscription {
+    pub id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<WebhookEventType>,
+    pub description: Option<String>,
+    pub created_by: Option<Uuid>,
+}
+
+/// Single delivery (with retries) of an event to a subscription
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub payload: Value,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Delivery claimed by the dispatcher, joined with its subscription target
+#[derive(Debug, Clone)]
+pub struct PendingWebhookDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub event_type: String,
+    pub payload: Value,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+/// Envelope serialized as the body of every webhook request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: WebhookEventType,
+    pub created_at: DateTime<Utc>,
+    pub data: Value,
+}
+
+// ============================================================================
+// AUDIT TRAIL
+// ============================================================================
+
+/// Immutable record of a write to any entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditLog {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub actor_name: Option<String>,
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub action: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub diff: Value,
+    pub request_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Filters accepted by the audit log query endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuditLogFilter {
+    pub entity_type: Option<String>,
+    pub entity_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// ============================================================================
+// EXPORTS
+// ============================================================================
+
+/// `status` filter for exports of businesses and locations (`is_active`)
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityStatus {
+    Active,
+    Inactive,
+}
+
+impl ActivityStatus {
+    pub fn is_active(self) -> bool {
+        self == ActivityStatus::Active
+    }
+}
+
+/// Filters accepted by the export endpoints. `category` and `country` match
+/// case-insensitively; for businesses, `country` matches any live location.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportFilter<S> {
+    pub status: Option<S>,
+    pub category: Option<String>,
+    pub country: Option<String>,
+}
+
+/// One line of the businesses CSV export
+#[derive(Debug, Clone, Serialize)]
+pub struct BusinessExportRow {
+    pub id: Uuid,
+    pub business_name: String,
+    pub category: String,
+    pub owner_user_id: Uuid,
+    pub tax_id: Option<String>,
+    pub website: Option<String>,
+    pub is_active: bool,
+    pub location_count: i64,
+    pub active_promotion_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BusinessExportRow {
+    /// Header line, in field order
+    pub const CSV_HEADERS: &'static [&'static str] = &[
+        "id", "business_name", "category", "owner_user_id", "tax_id", "website", "is_active",
+        "location_count", "active_promotion_count", "created_at", "updated_at",
+    ];
+}
+
+/// One line of the promotions CSV export
+#[derive(Debug, Clone, Serialize)]
+pub struct PromotionExportRow {
+    pub id: Uuid,
+    pub title: String,
+    pub promotion_type: BusinessPromotionType,
+    pub status: BusinessPromotionStatus,
+    pub discount_percent: Option<i32>,
+    pub reward_points: i32,
+    pub max_claims: Option<i32>,
+    pub total_claims: i32,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub target_scope: PromotionTargetScope,
+    pub location_id: Option<Uuid>,
+    pub location_name: Option<String>,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub business_id: Uuid,
+    pub business_name: String,
+    pub category: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PromotionExportRow {
+    /// Header line, in field order
+    pub const CSV_HEADERS: &'static [&'static str] = &[
+        "id", "title", "promotion_type", "status", "discount_percent", "reward_points", "max_claims",
+        "total_claims", "starts_at", "ends_at", "published_at", "target_scope", "location_id", "location_name", "city",
+        "country", "business_id", "business_name", "category", "created_at",
+    ];
+}
+
+/// A location with its promotion counts, exported as a GeoJSON feature
+#[derive(Debug, Clone, Serialize)]
+pub struct LocationExportRow {
+    pub id: Uuid,
+    pub business_id: Uuid,
+    pub business_name: String,
+    pub category: String,
+    pub location_name: String,
+    pub formatted_address: String,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    #[serde(skip)]
+    pub latitude: Option<f64>,
+    #[serde(skip)]
+    pub longitude: Option<f64>,
+    pub is_active: bool,
+    pub is_primary: bool,
+    pub promotion_count: i64,
+    pub active_promotion_count: i64,
+}
+
 // ============================================================================
 // REQUEST/RESPONSE DTOs
 // ============================================================================
@@ -367,6 +982,11 @@ pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    /// Stable error code, see `errors::AppError::code`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
     pub timestamp: DateTime<Utc>,
 }
 
@@ -376,15 +996,19 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            code: None,
+            details: None,
             timestamp: Utc::now(),
         }
     }
 
-    pub fn error(message: String) -> Self {
+    pub fn failure(code: &'static str, message: String, details: Option<Value>) -> Self {
         Self {
             success: false,
             data: None,
             error: Some(message),
+            code: Some(code),
+            details,
             timestamp: Utc::now(),
         }
     }
@@ -429,7 +1053,7 @@ impl CreateBusinessRegistrationRequest {
             phone: self.phone,
             website: self.website,
             tax_id: self.tax_id,
-            document_urls: self.document_urls,
+            document_urls: Json(self.document_urls),
             is_multi_user_team: self.is_multi_user_team,
             status: BusinessVerificationStatus::Pending,
             owner_email: self.owner_email,
@@ -438,6 +1062,7 @@ impl CreateBusinessRegistrationRequest {
             reviewer_notes: None,
             reviewer_id: None,
             reviewer_name: None,
+            content_findings: ContentFindings::default(),
             submitted_at: now,
             updated_at: now,
         }
@@ -452,6 +1077,27 @@ pub struct ReviewActionRequest {
     pub rejection_reason: Option<String>,
     pub reviewer_id: Option<Uuid>,
     pub reviewer_name: Option<String>,
+    /// `suspend` only: resume automatically at this time
+    pub suspended_until: Option<DateTime<Utc>>,
+    /// `suspend` only: cancel published promotions instead of pausing them
+    #[serde(default)]
+    pub cancel_promotions: bool,
+}
+
+/// Moderation decision on a promotion; the reviewer comes from the actor headers
+#[derive(Debug, Deserialize, Validate)]
+pub struct PromotionReviewActionRequest {
+    pub action: ReviewAction,
+    #[validate(length(max = 4000))]
+    pub notes: Option<String>,
+    #[validate(length(max = 4000))]
+    pub rejection_reason: Option<String>,
+}
+
+/// Request to let a business publish promotions without moderation
+#[derive(Debug, Deserialize)]
+pub struct PromotionTrustRequest {
+    pub trusted: bool,
 }
 
 /// Request to create a business
@@ -490,6 +1136,44 @@ impl CreateBusinessRequest {
     }
 }
 
+/// Request to update a business; also the document JSON Merge Patch applies to
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateBusinessRequest {
+    #[validate(length(min = 3, max = 120))]
+    pub business_name: String,
+    #[validate(length(min = 3, max = 120))]
+    pub category: String,
+    pub tax_id: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub logo_url: Option<String>,
+}
+
+impl UpdateBusinessRequest {
+    pub fn apply_to_existing(&self, existing: &mut Business) {
+        existing.business_name = self.business_name.clone();
+        existing.category = self.category.clone();
+        existing.tax_id = self.tax_id.clone();
+        existing.description = self.description.clone();
+        existing.website = self.website.clone();
+        existing.logo_url = self.logo_url.clone();
+        existing.updated_at = Utc::now();
+    }
+}
+
+impl From<&Business> for UpdateBusinessRequest {
+    fn from(business: &Business) -> Self {
+        Self {
+            business_name: business.business_name.clone(),
+            category: business.category.clone(),
+            tax_id: business.tax_id.clone(),
+            description: business.description.clone(),
+            website: business.website.clone(),
+            logo_url: business.logo_url.clone(),
+        }
+    }
+}
+
 /// Request to create a location
 #[derive(Debug, Deserialize, Validate)]
 pub struct CreateLocationRequest {
@@ -540,8 +1224,8 @@ impl CreateLocationRequest {
     }
 }
 
-/// Request to update a location
-#[derive(Debug, Deserialize, Validate)]
+/// Request to update a location; also the document JSON Merge Patch applies to
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct UpdateLocationRequest {
     #[validate(length(min = 2, max = 120))]
     pub location_name: String,
@@ -587,6 +1271,104 @@ impl UpdateLocationRequest {
     }
 }
 
+impl From<&BusinessLocation> for UpdateLocationRequest {
+    fn from(location: &BusinessLocation) -> Self {
+        Self {
+            location_name: location.location_name.clone(),
+            formatted_address: location.formatted_address.clone(),
+            street: location.street.clone(),
+            city: location.city.clone(),
+            state_region: location.state_region.clone(),
+            postal_code: location.postal_code.clone(),
+            country: location.country.clone(),
+            latitude: location.latitude,
+            longitude: location.longitude,
+            google_place_id: location.google_place_id.clone(),
+            timezone: location.timezone.clone(),
+            phone: location.phone.clone(),
+            email: location.email.clone(),
+            is_primary: location.is_primary,
+            is_active: location.is_active,
+            operating_hours: location.operating_hours.clone(),
+            notes: location.notes.clone(),
+        }
+    }
+}
+
+/// One row of a bulk location import. Rows with an `external_ref` update the
+/// live location of the business that carries the same reference in its
+/// `metadata`, or create it with that reference.
+#[derive(Debug, Deserialize)]
+pub struct LocationImportRow {
+    pub external_ref: Option<String>,
+    #[serde(flatten)]
+    pub location: CreateLocationRequest,
+}
+
+/// Validation errors of one import row; `row` is 1-based, header excluded
+#[derive(Debug, Clone, Serialize)]
+pub struct LocationImportRowError {
+    pub row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_ref: Option<String>,
+    pub errors: Vec<FieldError>,
+}
+
+/// Outcome of a bulk location import
+#[derive(Debug, Serialize)]
+pub struct LocationImportReport {
+    pub dry_run: bool,
+    pub total_rows: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub errors: Vec<LocationImportRowError>,
+    /// Resulting locations in row order; empty when rows failed validation
+    pub locations: Vec<BusinessLocation>,
+}
+
+/// Cross-field promotion rule broken by an otherwise well-formed request.
+/// Messages shown to clients come from the `i18n` catalog, keyed by `code`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum PromotionRuleViolation {
+    #[error("ends_at must be after starts_at")]
+    EndsBeforeStart,
+    #[error("discount_percent only applies to discount promotions")]
+    DiscountOnNonDiscountType,
+    #[error("discount_percent must be between 0 and 100")]
+    DiscountOutOfRange,
+    #[error("contest promotions require a prize")]
+    ContestWithoutPrize,
+    #[error("only reviewers can reject a promotion")]
+    StatusSetByModeration,
+    #[error("promotions are only paused and resumed with their business")]
+    StatusSetBySuspension,
+}
+
+impl PromotionRuleViolation {
+    pub fn code(&self) -> &'static str {
+        match self {
+            PromotionRuleViolation::EndsBeforeStart => "promotion_ends_before_start",
+            PromotionRuleViolation::DiscountOnNonDiscountType => "discount_requires_discount_type",
+            PromotionRuleViolation::DiscountOutOfRange => "discount_out_of_range",
+            PromotionRuleViolation::ContestWithoutPrize => "contest_requires_prize",
+            PromotionRuleViolation::StatusSetByModeration => "status_set_by_moderation",
+            PromotionRuleViolation::StatusSetBySuspension => "status_set_by_suspension",
+        }
+    }
+
+    /// Request field the rule is reported against
+    pub fn field(&self) -> &'static str {
+        match self {
+            PromotionRuleViolation::EndsBeforeStart => "ends_at",
+            PromotionRuleViolation::DiscountOnNonDiscountType
+            | PromotionRuleViolation::DiscountOutOfRange => "discount_percent",
+            PromotionRuleViolation::ContestWithoutPrize => "prize",
+            PromotionRuleViolation::StatusSetByModeration
+            | PromotionRuleViolation::StatusSetBySuspension => "status",
+        }
+    }
+}
+
 /// Request to create a promotion
 #[derive(Debug, Deserialize, Validate)]
 pub struct CreatePromotionRequest {
@@ -616,36 +1398,45 @@ pub struct CreatePromotionRequest {
     pub metadata: Option<Value>,
     pub starts_at: DateTime<Utc>,
     pub ends_at: DateTime<Utc>,
+    /// Locations of the business the promotion applies to, on top of the one
+    /// in the path when created for a location. Creating for a business
+    /// without any makes the promotion business-wide.
+    #[serde(default)]
+    #[validate(length(max = 200))]
+    pub location_ids: Vec<Uuid>,
+    /// Whether `max_claims` counts claims across all locations or per location
+    #[serde(default)]
+    pub limit_scope: PromotionLimitScope,
 }
 
 impl CreatePromotionRequest {
-    pub fn validate_business_rules(&self) -> Result<(), String> {
+    pub fn validate_business_rules(&self) -> Result<(), PromotionRuleViolation> {
         if self.ends_at <= self.starts_at {
-            return Err("La fecha de finalización debe ser posterior a la fecha de inicio".into());
+            return Err(PromotionRuleViolation::EndsBeforeStart);
         }
 
         if let Some(discount) = self.discount_percent {
             if self.promotion_type != BusinessPromotionType::Discount {
-                return Err(
-                    "El porcentaje de descuento solo aplica para promociones de tipo discount"
-                        .into(),
-                );
+                return Err(PromotionRuleViolation::DiscountOnNonDiscountType);
             }
             if !(0..=100).contains(&discount) {
-                return Err("El descuento debe estar entre 0 y 100".into());
+                return Err(PromotionRuleViolation::DiscountOutOfRange);
             }
         }
 
         if self.promotion_type == BusinessPromotionType::Contest && self.prize.is_none() {
-            return Err("Las promociones de tipo concurso requieren especificar un premio".into());
+            return Err(PromotionRuleViolation::ContestWithoutPrize);
         }
 
         Ok(())
     }
 
+    /// New promotion of `business_id` for `location_ids`, or for the whole
+    /// business when there are none
     pub fn into_new_promotion(
         self,
-        location_id: Uuid,
+        business_id: Uuid,
+        location_ids: Vec<Uuid>,
         actor_id: Option<Uuid>,
     ) -> NewBusinessPromotion {
         let now = Utc::now();
@@ -654,10 +1445,15 @@ impl CreatePromotionRequest {
         } else {
             BusinessPromotionStatus::Active
         };
+        let (target_scope, location_id, location_ids) = promotion_targets(location_ids);
 
         NewBusinessPromotion {
             id: Uuid::new_v4(),
+            business_id,
             location_id,
+            target_scope,
+            limit_scope: self.limit_scope,
+            location_ids,
             title: self.title,
             subtitle: self.subtitle,
             description: self.description,
@@ -674,6 +1470,7 @@ impl CreatePromotionRequest {
             requires_purchase: self.requires_purchase,
             terms: self.terms,
             metadata: self.metadata.unwrap_or(Value::Object(Default::default())),
+            content_findings: ContentFindings::default(),
             starts_at: self.starts_at,
             ends_at: self.ends_at,
             published_at: None,
@@ -685,8 +1482,8 @@ impl CreatePromotionRequest {
     }
 }
 
-/// Request to update a promotion
-#[derive(Debug, Deserialize, Validate)]
+/// Request to update a promotion; also the document JSON Merge Patch applies to
+#[derive(Debug, PartialEq, Serialize, Deserialize, Validate)]
 pub struct UpdatePromotionRequest {
     #[validate(length(min = 3, max = 120))]
     pub title: String,
@@ -719,25 +1516,22 @@ pub struct UpdatePromotionRequest {
 }
 
 impl UpdatePromotionRequest {
-    pub fn validate_business_rules(&self) -> Result<(), String> {
+    pub fn validate_business_rules(&self) -> Result<(), PromotionRuleViolation> {
         if self.ends_at <= self.starts_at {
-            return Err("La fecha de finalización debe ser posterior a la fecha de inicio".into());
+            return Err(PromotionRuleViolation::EndsBeforeStart);
         }
 
         if let Some(discount) = self.discount_percent {
             if self.promotion_type != BusinessPromotionType::Discount {
-                return Err(
-                    "El porcentaje de descuento solo aplica para promociones de tipo discount"
-                        .into(),
-                );
+                return Err(PromotionRuleViolation::DiscountOnNonDiscountType);
             }
             if !(0..=100).contains(&discount) {
-                return Err("El descuento debe estar entre 0 y 100".into());
+                return Err(PromotionRuleViolation::DiscountOutOfRange);
             }
         }
 
         if self.promotion_type == BusinessPromotionType::Contest && self.prize.is_none() {
-            return Err("Las promociones de tipo concurso requieren especificar un premio".into());
+            return Err(PromotionRuleViolation::ContestWithoutPrize);
         }
 
         Ok(())
@@ -769,6 +1563,246 @@ impl UpdatePromotionRequest {
     }
 }
 
+impl From<&BusinessPromotion> for UpdatePromotionRequest {
+    fn from(promotion: &BusinessPromotion) -> Self {
+        Self {
+            title: promotion.title.clone(),
+            subtitle: promotion.subtitle.clone(),
+            description: promotion.description.clone(),
+            promotion_type: promotion.promotion_type,
+            status: promotion.status,
+            image_url: promotion.image_url.clone(),
+            prize: promotion.prize.clone(),
+            reward_points: promotion.reward_points,
+            discount_percent: promotion.discount_percent,
+            max_claims: promotion.max_claims,
+            per_user_limit: promotion.per_user_limit,
+            requires_check_in: promotion.requires_check_in,
+            requires_purchase: promotion.requires_purchase,
+            terms: promotion.terms.clone(),
+            starts_at: promotion.starts_at,
+            ends_at: promotion.ends_at,
+            published_at: promotion.published_at,
+            metadata: Some(promotion.metadata.clone()),
+        }
+    }
+}
+
+/// Request to copy a promotion, optionally to other locations of its business.
+/// Without `starts_at` the copy keeps the original dates; without `ends_at` it
+/// keeps the original duration.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ClonePromotionRequest {
+    /// One single-location copy per target location; without any, a single
+    /// copy that keeps the targets of the original
+    #[serde(default)]
+    #[validate(length(max = 50))]
+    pub location_ids: Vec<Uuid>,
+    #[validate(length(min = 3, max = 120))]
+    pub title: Option<String>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+impl ClonePromotionRequest {
+    /// Dates of the copies of `source`
+    pub fn schedule(&self, source: &BusinessPromotion) -> Result<(DateTime<Utc>, DateTime<Utc>), PromotionRuleViolation> {
+        let starts_at = self.starts_at.unwrap_or(source.starts_at);
+        let ends_at = self
+            .ends_at
+            .unwrap_or(starts_at + (source.ends_at - source.starts_at));
+        if ends_at <= starts_at {
+            return Err(PromotionRuleViolation::EndsBeforeStart);
+        }
+        Ok((starts_at, ends_at))
+    }
+}
+
+impl BusinessPromotion {
+    /// Fresh copy for `location_ids` (the whole business when empty): new id,
+    /// no claims, unpublished, and `metadata.cloned_from` pointing at this
+    /// promotion
+    pub fn clone_to(
+        &self,
+        location_ids: Vec<Uuid>,
+        title: Option<&str>,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+        actor_id: Option<Uuid>,
+    ) -> NewBusinessPromotion {
+        let now = Utc::now();
+        let status = if starts_at > now {
+            BusinessPromotionStatus::Scheduled
+        } else {
+            BusinessPromotionStatus::Active
+        };
+        let mut metadata = match &self.metadata {
+            Value::Object(map) => map.clone(),
+            _ => Default::default(),
+        };
+        metadata.remove("template_id");
+        metadata.insert("cloned_from".to_string(), Value::String(self.id.to_string()));
+        let (target_scope, location_id, location_ids) = promotion_targets(location_ids);
+
+        NewBusinessPromotion {
+            id: Uuid::new_v4(),
+            business_id: self.business_id,
+            location_id,
+            target_scope,
+            limit_scope: self.limit_scope,
+            location_ids,
+            title: title.map_or_else(|| self.title.clone(), str::to_string),
+            subtitle: self.subtitle.clone(),
+            description: self.description.clone(),
+            promotion_type: self.promotion_type,
+            status,
+            image_url: self.image_url.clone(),
+            prize: self.prize.clone(),
+            reward_points: self.reward_points,
+            discount_percent: self.discount_percent,
+            max_claims: self.max_claims,
+            per_user_limit: self.per_user_limit,
+            total_claims: 0,
+            requires_check_in: self.requires_check_in,
+            requires_purchase: self.requires_purchase,
+            terms: self.terms.clone(),
+            metadata: Value::Object(metadata),
+            content_findings: ContentFindings::default(),
+            starts_at,
+            ends_at,
+            published_at: None,
+            created_by: actor_id,
+            updated_by: actor_id,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Scope, single location and deduplicated targets for a promotion aimed at
+/// `location_ids`; no locations means the whole business
+fn promotion_targets(mut location_ids: Vec<Uuid>) -> (PromotionTargetScope, Option<Uuid>, Vec<Uuid>) {
+    let mut seen = HashSet::new();
+    location_ids.retain(|location_id| seen.insert(*location_id));
+    match location_ids.as_slice() {
+        [] => (PromotionTargetScope::Business, None, location_ids),
+        [location_id] => (PromotionTargetScope::Location, Some(*location_id), location_ids),
+        _ => (PromotionTargetScope::Locations, None, location_ids),
+    }
+}
+
+/// Request to record a claim of a promotion at one of its locations
+#[derive(Debug, Deserialize)]
+pub struct ClaimPromotionRequest {
+    pub location_id: Uuid,
+}
+
+/// Counters after a claim was recorded
+#[derive(Debug, Clone, Serialize)]
+pub struct PromotionClaim {
+    pub promotion_id: Uuid,
+    pub location_id: Uuid,
+    pub limit_scope: PromotionLimitScope,
+    pub max_claims: Option<i32>,
+    /// Claims across all locations
+    pub total_claims: i32,
+    /// Claims at `location_id`
+    pub location_claims: i32,
+    /// Claims left under the limit that applies here, if there is one
+    pub remaining_claims: Option<i32>,
+}
+
+/// A location a promotion applies to, with its claim counter
+#[derive(Debug, Clone, Serialize)]
+pub struct PromotionLocation {
+    pub location_id: Uuid,
+    pub location_name: String,
+    pub total_claims: i32,
+}
+
+/// Request to create a recurring promotion template. `rrule` is an RFC 5545
+/// recurrence rule (`FREQ=WEEKLY;BYDAY=FR;BYHOUR=18`) evaluated from
+/// `dtstart` in `timezone`, which defaults to the location's time zone.
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreatePromotionTemplateRequest {
+    #[validate(length(min = 3, max = 120))]
+    pub title: String,
+    #[validate(length(max = 160))]
+    pub subtitle: Option<String>,
+    #[validate(length(max = 4000))]
+    pub description: Option<String>,
+    pub promotion_type: BusinessPromotionType,
+    #[validate(length(max = 1024))]
+    pub image_url: Option<String>,
+    #[validate(length(max = 1024))]
+    pub prize: Option<String>,
+    #[validate(range(min = 0, max = 10000))]
+    pub reward_points: i32,
+    #[validate(range(min = 0, max = 100))]
+    pub discount_percent: Option<i32>,
+    #[validate(range(min = 1, max = 1000000))]
+    pub max_claims: Option<i32>,
+    #[validate(range(min = 1, max = 10000))]
+    pub per_user_limit: Option<i32>,
+    pub requires_check_in: bool,
+    pub requires_purchase: bool,
+    #[validate(length(max = 4000))]
+    pub terms: Option<String>,
+    pub metadata: Option<Value>,
+    #[validate(length(min = 1, max = 500))]
+    pub rrule: String,
+    pub dtstart: NaiveDateTime,
+    /// Length of each occurrence; at most one week
+    #[validate(range(min = 1, max = 10080))]
+    pub duration_minutes: i32,
+    pub timezone: Option<String>,
+}
+
+impl CreatePromotionTemplateRequest {
+    pub fn validate_business_rules(&self) -> Result<(), PromotionRuleViolation> {
+        if let Some(discount) = self.discount_percent {
+            if self.promotion_type != BusinessPromotionType::Discount {
+                return Err(PromotionRuleViolation::DiscountOnNonDiscountType);
+            }
+            if !(0..=100).contains(&discount) {
+                return Err(PromotionRuleViolation::DiscountOutOfRange);
+            }
+        }
+
+        if self.promotion_type == BusinessPromotionType::Contest && self.prize.is_none() {
+            return Err(PromotionRuleViolation::ContestWithoutPrize);
+        }
+
+        Ok(())
+    }
+
+    pub fn into_new_template(self, location_id: Uuid, timezone: String, actor_id: Option<Uuid>) -> NewPromotionTemplate {
+        NewPromotionTemplate {
+            id: Uuid::new_v4(),
+            location_id,
+            title: self.title,
+            subtitle: self.subtitle,
+            description: self.description,
+            promotion_type: self.promotion_type,
+            image_url: self.image_url,
+            prize: self.prize,
+            reward_points: self.reward_points,
+            discount_percent: self.discount_percent,
+            max_claims: self.max_claims,
+            per_user_limit: self.per_user_limit,
+            requires_check_in: self.requires_check_in,
+            requires_purchase: self.requires_purchase,
+            terms: self.terms,
+            metadata: self.metadata.unwrap_or(Value::Object(Default::default())),
+            rrule: self.rrule.trim().to_string(),
+            dtstart: self.dtstart,
+            duration_minutes: self.duration_minutes,
+            timezone,
+            created_by: actor_id,
+        }
+    }
+}
+
 /// Request to add location admin
 #[derive(Debug, Deserialize, Validate)]
 pub struct AddLocationAdminRequest {
@@ -805,6 +1839,82 @@ impl AddLocationAdminRequest {
     }
 }
 
+/// Request to register a webhook subscription
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateWebhookSubscriptionRequest {
+    #[validate(url, custom(function = "validate_webhook_url"))]
+    pub url: String,
+    #[validate(length(min = 16, max = 256))]
+    pub secret: Option<String>,
+    #[validate(length(min = 1))]
+    pub event_types: Vec<WebhookEventType>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+}
+
+impl CreateWebhookSubscriptionRequest {
+    pub fn into_new_subscription(self, created_by: Option<Uuid>) -> NewWebhookSubscription {
+        let secret = self.secret.unwrap_or_else(|| {
+            format!("whsec_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+        });
+
+        NewWebhookSubscription {
+            id: Uuid::new_v4(),
+            url: self.url,
+            secret,
+            event_types: self.event_types,
+            description: self.description,
+            created_by,
+        }
+    }
+}
+
+/// Request to update a webhook subscription
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateWebhookSubscriptionRequest {
+    #[validate(url, custom(function = "validate_webhook_url"))]
+    pub url: String,
+    #[validate(length(min = 1))]
+    pub event_types: Vec<WebhookEventType>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+    pub is_active: bool,
+}
+
+impl UpdateWebhookSubscriptionRequest {
+    pub fn apply_to_existing(&self, existing: &mut WebhookSubscription) {
+        // Re-enabling a subscription gives it a clean failure budget
+        if self.is_active && !existing.is_active {
+            existing.consecutive_failures = 0;
+            existing.disabled_at = None;
+            existing.disabled_reason = None;
+        }
+
+        existing.url = self.url.clone();
+        existing.event_types = self.event_types.clone();
+        existing.description = self.description.clone();
+        existing.is_active = self.is_active;
+        existing.updated_at = Utc::now();
+    }
+}
+
+/// Subscribers must be public https endpoints; malformed URLs are left to
+/// the `url` rule
+fn validate_webhook_url(url: &str) -> Result<(), ValidationError> {
+    match crate::clients::webhooks::check_target(url) {
+        Err(_) if reqwest::Url::parse(url).is_ok() => Err(ValidationError::new("public_https_url")),
+        _ => Ok(()),
+    }
+}
+
+/// Subscription returned on creation; the only time the secret is exposed
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedWebhookSubscription {
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+    pub secret: String,
+}
+
 // ============================================================================
 // COMPOSITE RESPONSE TYPES
 // ============================================================================
@@ -830,6 +1940,13 @@ pub struct RegistrationWithHistory {
     pub history: Vec<BusinessReviewEvent>,
 }
 
+/// Promotion with its moderation decisions, oldest first
+#[derive(Debug, Clone, Serialize)]
+pub struct PromotionWithReviewHistory {
+    pub promotion: BusinessPromotion,
+    pub history: Vec<PromotionReviewEvent>,
+}
+
 /// Business registration summary (for list views with locations but without full history)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegistrationSummary {
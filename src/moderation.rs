@@ -0,0 +1,68 @@
+//! Moderation gate for promotions.
+//!
+//! With `PROMOTION_MODERATION_ENABLED` set, promotions of businesses that are
+//! not trusted enter `pending_review` when they are created or when an edit
+//! would publish changed content. They are only scheduled, activated and
+//! shared to stories once a reviewer approves them.
+
+use serde::Deserialize;
+
+use crate::models::{BusinessPromotion, BusinessPromotionStatus, PromotionRuleViolation, UpdatePromotionRequest};
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PromotionModeration {
+    pub enabled: bool,
+}
+
+impl PromotionModeration {
+    /// Whether promotions of a business with this trust flag need review
+    pub fn requires_review(&self, promotions_trusted: bool) -> bool {
+        self.enabled && !promotions_trusted
+    }
+
+    /// Status for a new promotion that would otherwise get `status`
+    pub fn gate_new(&self, promotions_trusted: bool, status: BusinessPromotionStatus) -> BusinessPromotionStatus {
+        if status.is_published() && self.requires_review(promotions_trusted) {
+            BusinessPromotionStatus::PendingReview
+        } else {
+            status
+        }
+    }
+
+    /// Adjust the status of `after`, an edited copy of `before`. Taking a
+    /// promotion down never needs review; publishing one that was not
+    /// published, or changing the content of a published one, does. A paused
+    /// promotion can only be taken down further, to `draft` or `cancelled`.
+    pub fn gate_update(
+        &self,
+        promotions_trusted: bool,
+        before: &BusinessPromotion,
+        after: &mut BusinessPromotion,
+    ) -> Result<(), PromotionRuleViolation> {
+        if after.status == BusinessPromotionStatus::Rejected && before.status != BusinessPromotionStatus::Rejected {
+            return Err(PromotionRuleViolation::StatusSetByModeration);
+        }
+        let paused = |status| status == BusinessPromotionStatus::Paused;
+        let taken_down = matches!(after.status, BusinessPromotionStatus::Draft | BusinessPromotionStatus::Cancelled);
+        if paused(after.status) != paused(before.status) && !(paused(before.status) && taken_down) {
+            return Err(PromotionRuleViolation::StatusSetBySuspension);
+        }
+        if !self.requires_review(promotions_trusted) {
+            return Ok(());
+        }
+
+        let submitted = after.status.is_published() || after.status == BusinessPromotionStatus::PendingReview;
+        if submitted && (!before.status.is_published() || content_changed(before, after)) {
+            after.status = BusinessPromotionStatus::PendingReview;
+        }
+        Ok(())
+    }
+}
+
+/// Whether anything other than the status differs
+fn content_changed(before: &BusinessPromotion, after: &BusinessPromotion) -> bool {
+    let mut edited = UpdatePromotionRequest::from(after);
+    edited.status = before.status;
+    UpdatePromotionRequest::from(before) != edited
+}
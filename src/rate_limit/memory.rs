@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use super::{Decision, RateLimit, RateLimitStore};
+use crate::errors::AppError;
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Buckets of this process only. Each replica enforces its own limits.
+#[derive(Default)]
+pub struct MemoryRateLimitStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl MemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryRateLimitStore {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn acquire(&self, key: &str, limit: RateLimit) -> Result<Decision, AppError> {
+        let now = Instant::now();
+        let capacity = f64::from(limit.capacity);
+        let mut buckets = self
+            .buckets
+            .lock()
+            .map_err(|_| AppError::Internal("rate limit buckets lock poisoned".into()))?;
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * limit.refill_per_sec()).min(capacity);
+        bucket.updated_at = now;
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+
+        Ok(Decision {
+            allowed,
+            remaining: bucket.tokens,
+        })
+    }
+
+    async fn prune(&self, idle: Duration) -> Result<u64, AppError> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .map_err(|_| AppError::Internal("rate limit buckets lock poisoned".into()))?;
+        let before = buckets.len();
+        buckets.retain(|_, bucket| bucket.updated_at.elapsed() < idle);
+        Ok((before - buckets.len()) as u64)
+    }
+}
@@ -0,0 +1,359 @@
+//! Token-bucket rate limiting per actor or client IP.
+//!
+//! Every request takes a token from the bucket of its caller: the
+//! `X-Actor-Id` set by the gateway when the request comes through a trusted
+//! gateway, the client IP otherwise. Writes (any method other than GET, HEAD or OPTIONS) and reads
+//! have separate buckets and budgets. Buckets live in memory by default, or
+//! in Postgres with `rate_limit.store = "postgres"` so every replica shares them.
+
+pub mod memory;
+pub mod postgres;
+
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::web;
+use async_trait::async_trait;
+use ipnet::IpNet;
+use serde::{Deserialize, Deserializer};
+
+use crate::database::DatabaseService;
+use crate::errors::AppError;
+use crate::shutdown::Shutdown;
+
+pub use memory::MemoryRateLimitStore;
+pub use postgres::PostgresRateLimitStore;
+
+/// Requests that are never limited
+const EXEMPT_PATHS: &[&str] = &["/api/v1/health", "/api/v1/health/live", "/api/v1/health/ready", "/metrics"];
+
+/// Budget of one bucket: `capacity` requests at once, refilled at
+/// `per_minute` requests per minute
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimit {
+    pub capacity: u32,
+    pub per_minute: u32,
+}
+
+impl RateLimit {
+    pub fn refill_per_sec(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+}
+
+/// Which budget a request is charged to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestClass {
+    Read,
+    Write,
+}
+
+impl RequestClass {
+    pub fn of(method: &Method) -> Self {
+        if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+            RequestClass::Read
+        } else {
+            RequestClass::Write
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            RequestClass::Read => "read",
+            RequestClass::Write => "write",
+        }
+    }
+}
+
+/// Outcome of taking a token
+#[derive(Debug, Clone, Copy)]
+pub struct Decision {
+    pub allowed: bool,
+    /// Tokens left after this request
+    pub remaining: f64,
+}
+
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Take one token from the bucket `key`, refilled up to `limit` first
+    async fn acquire(&self, key: &str, limit: RateLimit) -> Result<Decision, AppError>;
+
+    /// Forget buckets untouched for `idle`; returns how many were dropped
+    async fn prune(&self, idle: Duration) -> Result<u64, AppError>;
+}
+
+/// Where the buckets live
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitBackend {
+    /// Each replica counts on its own
+    #[default]
+    Memory,
+    /// Shared by every replica; needs the Postgres storage backend
+    Postgres,
+}
+
+impl FromStr for RateLimitBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(RateLimitBackend::Memory),
+            "postgres" => Ok(RateLimitBackend::Postgres),
+            other => Err(format!("rate limit store '{other}' is not supported (use memory or postgres)")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub store: RateLimitBackend,
+    pub read: RateLimit,
+    pub write: RateLimit,
+    /// Networks of internal callers that skip the limits
+    #[serde(deserialize_with = "deserialize_networks")]
+    pub allowlist: Vec<IpNet>,
+    /// Peers whose `X-Actor-Id` is trusted, i.e. the gateway that sets it.
+    /// Anyone can send the header, so requests from other peers are keyed by
+    /// IP whatever actor they claim.
+    #[serde(deserialize_with = "deserialize_networks")]
+    pub trusted_gateways: Vec<IpNet>,
+    /// Take the client IP from `X-Forwarded-For`/`Forwarded` (only behind a
+    /// proxy that sets them) instead of the peer address
+    pub trust_forwarded_for: bool,
+    pub prune_interval_secs: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            store: RateLimitBackend::Memory,
+            read: RateLimit {
+                capacity: 120,
+                per_minute: 600,
+            },
+            write: RateLimit {
+                capacity: 20,
+                per_minute: 60,
+            },
+            allowlist: Vec::new(),
+            trusted_gateways: Vec::new(),
+            trust_forwarded_for: false,
+            prune_interval_secs: 300,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn prune_interval(&self) -> Duration {
+        Duration::from_secs(self.prune_interval_secs)
+    }
+
+    /// How long a bucket must sit unused before it is full again for sure
+    pub fn idle_after(&self) -> Duration {
+        [self.read, self.write]
+            .into_iter()
+            .map(|limit| Duration::from_secs(secs_until(f64::from(limit.capacity), limit)))
+            .fold(self.prune_interval(), Duration::max)
+    }
+
+    pub fn limit(&self, class: RequestClass) -> RateLimit {
+        match class {
+            RequestClass::Read => self.read,
+            RequestClass::Write => self.write,
+        }
+    }
+}
+
+/// An IP address or CIDR network, e.g. `10.0.0.0/8` or `127.0.0.1`
+fn parse_network(entry: &str) -> Result<IpNet, String> {
+    let entry = entry.trim();
+    entry
+        .parse::<IpNet>()
+        .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+        .map_err(|_| format!("'{entry}' is not an IP address or CIDR network"))
+}
+
+/// Comma-separated IPs or CIDR networks, e.g. `10.0.0.0/8, 127.0.0.1`
+pub(crate) fn parse_networks(var: &str, value: &str) -> Result<Vec<IpNet>, String> {
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| parse_network(entry).map_err(|err| format!("{var} entry {err}")))
+        .collect()
+}
+
+fn deserialize_networks<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<IpNet>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|entry| parse_network(entry).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+pub struct RateLimiter {
+    pub config: RateLimitConfig,
+    store: Arc<dyn RateLimitStore>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig, store: Arc<dyn RateLimitStore>) -> Self {
+        Self { config, store }
+    }
+
+    /// Build the limiter with the store selected by `config.store`;
+    /// `postgres` needs `db`
+    pub fn from_config(config: RateLimitConfig, db: Option<Arc<DatabaseService>>) -> Result<Self, String> {
+        let store: Arc<dyn RateLimitStore> = match (config.store, db) {
+            (RateLimitBackend::Memory, _) => Arc::new(MemoryRateLimitStore::new()),
+            (RateLimitBackend::Postgres, Some(db)) => Arc::new(PostgresRateLimitStore::new(db)),
+            (RateLimitBackend::Postgres, None) => {
+                return Err("rate_limit.store 'postgres' needs the Postgres storage backend".to_string())
+            }
+        };
+        Ok(Self::new(config, store))
+    }
+
+    pub fn store(&self) -> &dyn RateLimitStore {
+        self.store.as_ref()
+    }
+
+    fn is_allowlisted(&self, ip: Option<IpAddr>) -> bool {
+        ip.is_some_and(|ip| self.config.allowlist.iter().any(|net| net.contains(&ip)))
+    }
+
+    /// Whether the direct peer is a gateway, whose `X-Actor-Id` can be trusted
+    fn is_trusted_gateway(&self, req: &ServiceRequest) -> bool {
+        req.peer_addr()
+            .is_some_and(|addr| self.config.trusted_gateways.iter().any(|net| net.contains(&addr.ip())))
+    }
+}
+
+/// Forget idle buckets every `rate_limit.prune_interval_secs`. Only buckets
+/// that have refilled completely are dropped, so their callers lose nothing.
+pub fn spawn_pruner(limiter: Arc<RateLimiter>, shutdown: &Shutdown) {
+    if !limiter.config.enabled {
+        return;
+    }
+
+    let mut ticker = shutdown.ticker(limiter.config.prune_interval());
+    shutdown.spawn("rate_limit_pruner", async move {
+        while ticker.tick().await {
+            match limiter.store().prune(limiter.config.idle_after()).await {
+                Ok(0) => {}
+                Ok(count) => log::debug!("Pruned {} idle rate limit buckets", count),
+                Err(err) => log::warn!("Failed to prune rate limit buckets: {err:?}"),
+            }
+        }
+    });
+}
+
+/// Middleware charging each request to its caller's bucket. Rejected
+/// requests get `429 rate_limited` with `Retry-After`; every limited response
+/// carries `X-RateLimit-Limit`, `X-RateLimit-Remaining` and
+/// `X-RateLimit-Reset` (seconds until the bucket is full again).
+pub async fn enforce<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, actix_web::Error> {
+    let Some(limiter) = req.app_data::<web::Data<RateLimiter>>().cloned() else {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    };
+    if !limiter.config.enabled || EXEMPT_PATHS.contains(&req.path()) {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    }
+
+    let ip = client_ip(&req, limiter.config.trust_forwarded_for);
+    if limiter.is_allowlisted(ip) {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    }
+
+    let class = RequestClass::of(req.method());
+    let limit = limiter.config.limit(class);
+    let key = format!("{}:{}", class.as_str(), caller_key(&req, ip, limiter.is_trusted_gateway(&req)));
+    let decision = match limiter.store().acquire(&key, limit).await {
+        Ok(decision) => decision,
+        Err(err) => {
+            // Fail open: an unavailable store must not take the API down
+            log::warn!("Rate limit store {} failed, letting the request through: {err:?}", limiter.store().name());
+            return next.call(req).await.map(ServiceResponse::map_into_left_body);
+        }
+    };
+
+    if !decision.allowed {
+        let retry_after_secs = secs_until(1.0 - decision.remaining, limit);
+        let mut res = req.error_response(AppError::RateLimited { retry_after_secs });
+        insert_limit_headers(res.headers_mut(), limit, decision);
+        return Ok(res.map_into_right_body());
+    }
+
+    let mut res = next.call(req).await?;
+    insert_limit_headers(res.headers_mut(), limit, decision);
+    Ok(res.map_into_left_body())
+}
+
+/// Bucket owner: the actor when a trusted gateway identified one, else the
+/// IP. An unverified `X-Actor-Id` is ignored, so rotating it cannot buy a
+/// caller fresh buckets.
+fn caller_key(req: &ServiceRequest, ip: Option<IpAddr>, trusted_gateway: bool) -> String {
+    let actor = req
+        .headers()
+        .get("X-Actor-Id")
+        .filter(|_| trusted_gateway)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| uuid::Uuid::parse_str(s).ok());
+    match (actor, ip) {
+        (Some(actor_id), _) => format!("actor:{actor_id}"),
+        (None, Some(ip)) => format!("ip:{ip}"),
+        (None, None) => "ip:unknown".to_string(),
+    }
+}
+
+fn client_ip(req: &ServiceRequest, trust_forwarded_for: bool) -> Option<IpAddr> {
+    if trust_forwarded_for {
+        if let Some(ip) = req.connection_info().realip_remote_addr().and_then(parse_ip) {
+            return Some(ip);
+        }
+    }
+    req.peer_addr().map(|addr| addr.ip())
+}
+
+/// Accepts `1.2.3.4`, `1.2.3.4:5678`, `::1` and `[::1]:5678`
+fn parse_ip(value: &str) -> Option<IpAddr> {
+    value
+        .parse::<IpAddr>()
+        .ok()
+        .or_else(|| value.parse::<std::net::SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+/// Whole seconds until `tokens` more tokens have been refilled
+fn secs_until(tokens: f64, limit: RateLimit) -> u64 {
+    if tokens <= 0.0 {
+        return 0;
+    }
+    (tokens / limit.refill_per_sec()).ceil() as u64
+}
+
+fn insert_limit_headers(headers: &mut HeaderMap, limit: RateLimit, decision: Decision) {
+    let remaining = decision.remaining.max(0.0).floor() as u64;
+    let reset = secs_until(f64::from(limit.capacity) - decision.remaining, limit);
+    for (name, value) in [
+        ("x-ratelimit-limit", u64::from(limit.capacity)),
+        ("x-ratelimit-remaining", remaining),
+        ("x-ratelimit-reset", reset),
+    ] {
+        headers.insert(HeaderName::from_static(name), HeaderValue::from(value));
+    }
+}
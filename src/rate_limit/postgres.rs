@@ -0,0 +1,39 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use super::{Decision, RateLimit, RateLimitStore};
+use crate::database::DatabaseService;
+use crate::errors::AppError;
+
+/// Buckets in the `rate_limit_buckets` table, shared by every replica. Each
+/// request costs one upsert.
+pub struct PostgresRateLimitStore {
+    db: Arc<DatabaseService>,
+}
+
+impl PostgresRateLimitStore {
+    pub fn new(db: Arc<DatabaseService>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for PostgresRateLimitStore {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn acquire(&self, key: &str, limit: RateLimit) -> Result<Decision, AppError> {
+        let (allowed, remaining) = self
+            .db
+            .take_rate_limit_token(key, f64::from(limit.capacity), limit.refill_per_sec())
+            .await?;
+        Ok(Decision { allowed, remaining })
+    }
+
+    async fn prune(&self, idle: Duration) -> Result<u64, AppError> {
+        self.db.prune_rate_limit_buckets(idle.as_secs_f64()).await
+    }
+}
@@ -0,0 +1,297 @@
+//! Recurrence rules for promotion templates.
+//!
+//! Supports the subset of RFC 5545 `RRULE` that recurring promotions need:
+//! `FREQ=DAILY|WEEKLY` with `INTERVAL`, `BYDAY`, `BYHOUR`, `BYMINUTE`, `COUNT`
+//! and `UNTIL`. Occurrences are computed in the template's local time zone,
+//! so "every Friday at 18:00" stays at 18:00 across DST changes.
+
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc, Weekday,
+};
+use chrono_tz::Tz;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Until {
+    /// `UNTIL=20261231`, inclusive, in template local time
+    Local(NaiveDateTime),
+    /// `UNTIL=20261231T230000Z`
+    Utc(DateTime<Utc>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    frequency: Frequency,
+    interval: u32,
+    by_day: Vec<Weekday>,
+    by_hour: Vec<u32>,
+    by_minute: Vec<u32>,
+    count: Option<u32>,
+    until: Option<Until>,
+}
+
+impl Recurrence {
+    /// Parse `FREQ=WEEKLY;BYDAY=FR;BYHOUR=18`, optionally prefixed with `RRULE:`
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let rule = rule.trim();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+
+        let mut frequency = None;
+        let mut recurrence = Recurrence {
+            frequency: Frequency::Daily,
+            interval: 1,
+            by_day: Vec::new(),
+            by_hour: Vec::new(),
+            by_minute: Vec::new(),
+            count: None,
+            until: None,
+        };
+
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("`{part}` is not a KEY=VALUE pair"))?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        other => return Err(format!("FREQ={other} is not supported (use DAILY or WEEKLY)")),
+                    })
+                }
+                "INTERVAL" => recurrence.interval = parse_number(key, value, 1, 365)?,
+                "COUNT" => recurrence.count = Some(parse_number(key, value, 1, 10_000)?),
+                "UNTIL" => recurrence.until = Some(parse_until(value)?),
+                "BYDAY" => recurrence.by_day = parse_list(value, parse_weekday)?,
+                "BYHOUR" => recurrence.by_hour = parse_list(value, |v| parse_number("BYHOUR", v, 0, 23))?,
+                "BYMINUTE" => recurrence.by_minute = parse_list(value, |v| parse_number("BYMINUTE", v, 0, 59))?,
+                "WKST" if value.eq_ignore_ascii_case("MO") => {}
+                other => return Err(format!("{other} is not supported")),
+            }
+        }
+
+        recurrence.frequency = frequency.ok_or("FREQ is required")?;
+        if recurrence.count.is_some() && recurrence.until.is_some() {
+            return Err("COUNT and UNTIL cannot be combined".to_string());
+        }
+        recurrence.by_hour.sort_unstable();
+        recurrence.by_hour.dedup();
+        recurrence.by_minute.sort_unstable();
+        recurrence.by_minute.dedup();
+        Ok(recurrence)
+    }
+
+    /// Start times of the occurrences in `[from, to)`, for a series that starts
+    /// at `dtstart` local time in `tz`. Local times that do not exist (DST gaps)
+    /// are skipped; ambiguous ones use the earlier instant.
+    pub fn occurrences(
+        &self,
+        dtstart: NaiveDateTime,
+        tz: Tz,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<DateTime<Utc>> {
+        let mut found = Vec::new();
+        let times = self.times_of_day(dtstart.time());
+        let by_day = if self.by_day.is_empty() && self.frequency == Frequency::Weekly {
+            vec![dtstart.weekday()]
+        } else {
+            self.by_day.clone()
+        };
+        // Last local day that can hold an occurrence before `to`
+        let last_day = to.with_timezone(&tz).date_naive() + Duration::days(1);
+
+        let mut seen = 0u32;
+        let mut day = dtstart.date();
+        while day <= last_day {
+            if self.in_period(dtstart.date(), day) && (by_day.is_empty() || by_day.contains(&day.weekday())) {
+                for time in &times {
+                    let local = day.and_time(*time);
+                    if local < dtstart {
+                        continue;
+                    }
+                    let Some(start) = tz.from_local_datetime(&local).earliest() else {
+                        continue;
+                    };
+                    let start = start.with_timezone(&Utc);
+                    seen += 1;
+                    if self.count.is_some_and(|count| seen > count) || self.past_until(local, start) {
+                        return found;
+                    }
+                    if start >= to {
+                        return found;
+                    }
+                    if start >= from {
+                        found.push(start);
+                    }
+                }
+            }
+            day = match day.succ_opt() {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        found
+    }
+
+    fn times_of_day(&self, default: NaiveTime) -> Vec<NaiveTime> {
+        let hours = if self.by_hour.is_empty() { vec![default.hour()] } else { self.by_hour.clone() };
+        let minutes = if self.by_minute.is_empty() { vec![default.minute()] } else { self.by_minute.clone() };
+        hours
+            .iter()
+            .flat_map(|hour| minutes.iter().filter_map(move |minute| NaiveTime::from_hms_opt(*hour, *minute, 0)))
+            .collect()
+    }
+
+    /// Whether `day` falls in an active period given `INTERVAL`
+    fn in_period(&self, first_day: NaiveDate, day: NaiveDate) -> bool {
+        let elapsed = match self.frequency {
+            Frequency::Daily => (day - first_day).num_days(),
+            Frequency::Weekly => (week_start(day) - week_start(first_day)).num_days() / 7,
+        };
+        elapsed % i64::from(self.interval) == 0
+    }
+
+    fn past_until(&self, local: NaiveDateTime, start: DateTime<Utc>) -> bool {
+        match self.until {
+            Some(Until::Local(until)) => local > until,
+            Some(Until::Utc(until)) => start > until,
+            None => false,
+        }
+    }
+}
+
+fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(i64::from(day.weekday().num_days_from_monday()))
+}
+
+fn parse_number(key: &str, value: &str, min: u32, max: u32) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|number| (min..=max).contains(number))
+        .ok_or_else(|| format!("{key} must be a number between {min} and {max}"))
+}
+
+fn parse_list<T>(value: &str, parse: impl Fn(&str) -> Result<T, String>) -> Result<Vec<T>, String> {
+    value.split(',').map(|item| parse(item.trim())).collect()
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    match value.to_ascii_uppercase().as_str() {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        other => Err(format!("BYDAY value `{other}` is not supported (use MO..SU)")),
+    }
+}
+
+fn parse_until(value: &str) -> Result<Until, String> {
+    let invalid = || format!("UNTIL `{value}` must look like 20261231 or 20261231T235959Z");
+    if let Some(utc) = value.strip_suffix('Z') {
+        let until = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+        return Ok(Until::Utc(until.and_utc()));
+    }
+    if let Ok(until) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Ok(Until::Local(until));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| invalid())?;
+    Ok(Until::Local(date.and_hms_opt(23, 59, 59).expect("valid time")))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::Europe::Madrid;
+
+    use super::*;
+
+    fn local(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        local(value).and_utc()
+    }
+
+    #[test]
+    fn weekly_friday_evenings_keep_local_time_across_dst() {
+        // Fridays 18:00-20:00; the end comes from the template's duration
+        let rule = Recurrence::parse("RRULE:FREQ=WEEKLY;BYDAY=FR;BYHOUR=18;BYMINUTE=0").unwrap();
+        let starts = rule.occurrences(local("2026-10-02 18:00"), Madrid, utc("2026-10-01 00:00"), utc("2026-11-01 00:00"));
+        assert_eq!(
+            starts,
+            vec![
+                utc("2026-10-02 16:00"),
+                utc("2026-10-09 16:00"),
+                utc("2026-10-16 16:00"),
+                utc("2026-10-23 16:00"),
+                // Summer time ended on the 25th
+                utc("2026-10-30 17:00"),
+            ]
+        );
+    }
+
+    #[test]
+    fn window_includes_from_and_excludes_to() {
+        let rule = Recurrence::parse("FREQ=WEEKLY;BYDAY=FR;BYHOUR=18").unwrap();
+        let dtstart = local("2026-10-02 18:00");
+
+        let starts = rule.occurrences(dtstart, Madrid, utc("2026-10-09 16:00"), utc("2026-10-16 16:00"));
+        assert_eq!(starts, vec![utc("2026-10-09 16:00")]);
+
+        let starts = rule.occurrences(dtstart, Madrid, utc("2026-10-09 16:01"), utc("2026-10-16 16:01"));
+        assert_eq!(starts, vec![utc("2026-10-16 16:00")]);
+    }
+
+    #[test]
+    fn nothing_happens_before_dtstart() {
+        let rule = Recurrence::parse("FREQ=DAILY;BYHOUR=9,18").unwrap();
+        let starts = rule.occurrences(local("2026-10-05 12:00"), Madrid, utc("2026-10-01 00:00"), utc("2026-10-07 00:00"));
+        assert_eq!(starts, vec![utc("2026-10-05 16:00"), utc("2026-10-06 07:00"), utc("2026-10-06 16:00")]);
+    }
+
+    #[test]
+    fn interval_count_and_until_limit_the_series() {
+        let dtstart = local("2026-10-02 18:00");
+        let (from, to) = (utc("2026-10-01 00:00"), utc("2026-12-01 00:00"));
+
+        let rule = Recurrence::parse("FREQ=WEEKLY;INTERVAL=2;COUNT=3").unwrap();
+        let starts = rule.occurrences(dtstart, Madrid, from, to);
+        assert_eq!(starts, vec![utc("2026-10-02 16:00"), utc("2026-10-16 16:00"), utc("2026-10-30 17:00")]);
+
+        let rule = Recurrence::parse("FREQ=WEEKLY;UNTIL=20261016").unwrap();
+        assert_eq!(rule.occurrences(dtstart, Madrid, from, to).len(), 3);
+
+        let rule = Recurrence::parse("FREQ=WEEKLY;UNTIL=20261016T155959Z").unwrap();
+        assert_eq!(rule.occurrences(dtstart, Madrid, from, to).len(), 2);
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        for rule in [
+            "",
+            "BYDAY=FR",
+            "FREQ=MONTHLY",
+            "FREQ=WEEKLY;BYDAY=XX",
+            "FREQ=DAILY;BYHOUR=24",
+            "FREQ=DAILY;BYMINUTE=60",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;COUNT=2;UNTIL=20261231",
+            "FREQ=DAILY;UNTIL=2026-12-31",
+            "FREQ=DAILY;BYSETPOS=1",
+            "FREQ=DAILY;WKST=SU",
+            "FREQ",
+        ] {
+            assert!(Recurrence::parse(rule).is_err(), "{rule} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,1169 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use super::{
+    AuditLogRepository, BusinessRepository, LocationAdminRepository, LocationRepository, OutboxRepository,
+    PromotionRepository, RegistrationRepository,
+};
+use crate::audit::{json_diff, AuditAction, AuditContext, AuditEntity};
+use crate::errors::{AppError, FieldError};
+use crate::events::{DomainEvent, DomainEventPayload, EntityDeleted, EventPublisher, LocationAdminRemoved};
+use crate::models::{
+    AuditLog, AuditLogFilter, Business, BusinessLocation, BusinessPromotion, BusinessPromotionStatus,
+    BusinessRegistration, BusinessVerificationStatus, LocationAdmin, NewBusiness, NewBusinessLocation,
+    NewBusinessPromotion, NewBusinessRegistration, NewLocationAdmin, PendingBusinessReview, PromotionClaim,
+    PromotionLimitScope, PromotionLocation, PromotionTargetScope, ReviewAction, ReviewStats,
+};
+
+#[derive(Default)]
+struct State {
+    registrations: HashMap<Uuid, BusinessRegistration>,
+    businesses: HashMap<Uuid, Business>,
+    locations: HashMap<Uuid, BusinessLocation>,
+    promotions: HashMap<Uuid, BusinessPromotion>,
+    /// `business_promotion_locations`: promotion id to (location id, claims)
+    promotion_targets: HashMap<Uuid, Vec<(Uuid, i32)>>,
+    location_admins: Vec<LocationAdmin>,
+    /// `audit_logs`, oldest first
+    audit_logs: Vec<AuditLog>,
+    /// `event_outbox`, in commit order
+    outbox: Vec<OutboxEntry>,
+}
+
+/// An `event_outbox` row
+struct OutboxEntry {
+    event: DomainEvent,
+    webhooks_dispatched: bool,
+    published: bool,
+}
+
+/// Every repository in maps of this process. Each call holds the lock for its
+/// whole duration, so it is as atomic as the Postgres transaction it mirrors,
+/// audit entries and outbox events included.
+#[derive(Default)]
+pub struct MemoryStore {
+    state: Mutex<State>,
+    /// Held while publishing, like the outbox advisory lock on Postgres
+    publishing: tokio::sync::Mutex<()>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, State>, AppError> {
+        self.state
+            .lock()
+            .map_err(|_| AppError::Internal("memory store lock poisoned".into()))
+    }
+}
+
+impl State {
+    /// Append an audit entry, as `audit::record` does in the database layer
+    fn record<T: Serialize>(
+        &mut self,
+        ctx: &AuditContext,
+        entity: AuditEntity,
+        entity_id: Uuid,
+        action: AuditAction,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), AppError> {
+        let before = before.map(serde_json::to_value).transpose()?;
+        let after = after.map(serde_json::to_value).transpose()?;
+        let diff = json_diff(before.as_ref(), after.as_ref());
+        self.audit_logs.push(AuditLog {
+            id: Uuid::new_v4(),
+            actor_id: ctx.actor_id,
+            actor_name: ctx.actor_name.clone(),
+            entity_type: entity.as_str().to_string(),
+            entity_id,
+            action: action.as_str().to_string(),
+            before,
+            after,
+            diff,
+            request_id: ctx.request_id.clone(),
+            ip_address: ctx.ip_address.clone(),
+            created_at: Utc::now(),
+        });
+        Ok(())
+    }
+
+    /// Append events to the outbox, as `outbox::enqueue_events` does
+    fn enqueue(&mut self, payloads: impl IntoIterator<Item = DomainEventPayload>) {
+        self.outbox.extend(payloads.into_iter().map(|payload| OutboxEntry {
+            event: DomainEvent::new(payload),
+            webhooks_dispatched: false,
+            published: false,
+        }));
+    }
+
+    fn live_business(&self, business_id: Uuid) -> Option<&Business> {
+        self.businesses.get(&business_id).filter(|b| b.deleted_at.is_none())
+    }
+
+    fn live_location(&self, location_id: Uuid) -> Option<&BusinessLocation> {
+        self.locations.get(&location_id).filter(|l| l.deleted_at.is_none())
+    }
+
+    fn location_deleted(&self, location_id: Option<Uuid>) -> bool {
+        location_id
+            .and_then(|id| self.locations.get(&id))
+            .is_some_and(|l| l.deleted_at.is_some())
+    }
+
+    fn targets(&self, promotion_id: Uuid, location_id: Uuid) -> Option<i32> {
+        self.promotion_targets
+            .get(&promotion_id)?
+            .iter()
+            .find(|(target, _)| *target == location_id)
+            .map(|(_, claims)| *claims)
+    }
+
+    /// Same field errors as `check_business_locations` in the database layer
+    fn check_business_locations(&self, business_id: Uuid, location_ids: &[Uuid]) -> Result<(), AppError> {
+        let foreign: Vec<FieldError> = location_ids
+            .iter()
+            .filter(|id| self.live_location(**id).is_none_or(|l| l.business_id != business_id))
+            .map(|location_id| {
+                let params = BTreeMap::from([("location_id".to_string(), json!(location_id))]);
+                FieldError::new("location_ids", "not_in_business", params)
+            })
+            .collect();
+        if foreign.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(foreign))
+        }
+    }
+
+    fn insert_promotion(&mut self, promotion: NewBusinessPromotion) -> BusinessPromotion {
+        self.promotion_targets
+            .insert(promotion.id, promotion.location_ids.iter().map(|id| (*id, 0)).collect());
+        let promotion = BusinessPromotion {
+            id: promotion.id,
+            business_id: promotion.business_id,
+            location_id: promotion.location_id,
+            target_scope: promotion.target_scope,
+            limit_scope: promotion.limit_scope,
+            title: promotion.title,
+            subtitle: promotion.subtitle,
+            description: promotion.description,
+            promotion_type: promotion.promotion_type,
+            status: promotion.status,
+            image_url: promotion.image_url,
+            prize: promotion.prize,
+            reward_points: promotion.reward_points,
+            discount_percent: promotion.discount_percent,
+            max_claims: promotion.max_claims,
+            per_user_limit: promotion.per_user_limit,
+            total_claims: promotion.total_claims,
+            requires_check_in: promotion.requires_check_in,
+            requires_purchase: promotion.requires_purchase,
+            terms: promotion.terms,
+            metadata: promotion.metadata,
+            content_findings: promotion.content_findings,
+            starts_at: promotion.starts_at,
+            ends_at: promotion.ends_at,
+            published_at: promotion.published_at,
+            created_by: promotion.created_by,
+            updated_by: promotion.updated_by,
+            created_at: promotion.created_at,
+            updated_at: promotion.updated_at,
+            deleted_at: None,
+            deleted_by: None,
+            version: 1,
+        };
+        self.promotions.insert(promotion.id, promotion.clone());
+        promotion
+    }
+
+    /// Audit entry and events of a new promotion
+    fn promotion_created(&mut self, promotion: &BusinessPromotion, ctx: &AuditContext) -> Result<(), AppError> {
+        self.record(ctx, AuditEntity::Promotion, promotion.id, AuditAction::Create, None, Some(promotion))?;
+        self.enqueue([DomainEventPayload::PromotionCreated(promotion.clone())]);
+        if promotion.status == BusinessPromotionStatus::Active {
+            self.enqueue([DomainEventPayload::PromotionActivated(promotion.clone())]);
+        }
+        Ok(())
+    }
+}
+
+fn check_version(expected: Option<i32>, current_version: i32) -> Result<(), AppError> {
+    if expected.is_some_and(|expected| expected != current_version) {
+        Err(AppError::VersionMismatch { current_version })
+    } else {
+        Ok(())
+    }
+}
+
+fn newest_start_first(mut promotions: Vec<BusinessPromotion>) -> Vec<BusinessPromotion> {
+    promotions.sort_by_key(|p| Reverse(p.starts_at));
+    promotions
+}
+
+// ============================================================================
+// REGISTRATIONS
+// ============================================================================
+
+#[async_trait]
+impl RegistrationRepository for MemoryStore {
+    async fn create_registration(
+        &self,
+        registration: NewBusinessRegistration,
+        ctx: &AuditContext,
+    ) -> Result<BusinessRegistration, AppError> {
+        let registration = BusinessRegistration {
+            id: registration.id,
+            user_id: registration.user_id,
+            business_id: registration.business_id,
+            name: registration.name,
+            category: registration.category,
+            address: registration.address,
+            description: registration.description,
+            phone: registration.phone,
+            website: registration.website,
+            tax_id: registration.tax_id,
+            document_urls: registration.document_urls,
+            is_multi_user_team: registration.is_multi_user_team,
+            status: registration.status,
+            owner_email: registration.owner_email,
+            owner_username: registration.owner_username,
+            rejection_reason: registration.rejection_reason,
+            reviewer_notes: registration.reviewer_notes,
+            reviewer_id: registration.reviewer_id,
+            reviewer_name: registration.reviewer_name,
+            content_findings: registration.content_findings,
+            submitted_at: registration.submitted_at,
+            updated_at: registration.updated_at,
+        };
+        let mut state = self.state()?;
+        state.registrations.insert(registration.id, registration.clone());
+        state.record(ctx, AuditEntity::Registration, registration.id, AuditAction::Create, None, Some(&registration))?;
+        state.enqueue([DomainEventPayload::RegistrationSubmitted(registration.clone())]);
+        Ok(registration)
+    }
+
+    async fn get_registration_by_id(&self, registration_id: Uuid) -> Result<Option<BusinessRegistration>, AppError> {
+        Ok(self.state()?.registrations.get(&registration_id).cloned())
+    }
+
+    async fn get_latest_registration_for_user(&self, user_id: Uuid) -> Result<Option<BusinessRegistration>, AppError> {
+        Ok(self.list_registrations_for_user(user_id).await?.into_iter().next())
+    }
+
+    async fn list_registrations_for_user(&self, user_id: Uuid) -> Result<Vec<BusinessRegistration>, AppError> {
+        let mut registrations: Vec<BusinessRegistration> = self
+            .state()?
+            .registrations
+            .values()
+            .filter(|r| r.user_id == user_id)
+            .cloned()
+            .collect();
+        registrations.sort_by_key(|r| Reverse(r.submitted_at));
+        Ok(registrations)
+    }
+
+    async fn list_pending_reviews(&self, limit: i64, offset: i64) -> Result<Vec<PendingBusinessReview>, AppError> {
+        let state = self.state()?;
+        let mut pending: Vec<&BusinessRegistration> = state
+            .registrations
+            .values()
+            .filter(|r| r.status == BusinessVerificationStatus::Pending)
+            .collect();
+        pending.sort_by_key(|r| r.submitted_at);
+
+        Ok(pending
+            .into_iter()
+            .skip(usize::try_from(offset).unwrap_or(0))
+            .take(usize::try_from(limit).unwrap_or(0))
+            .map(|r| PendingBusinessReview {
+                id: r.id,
+                name: r.name.clone(),
+                category: r.category.clone(),
+                address: r.address.clone(),
+                tax_id: r.tax_id.clone(),
+                document_urls: r.document_urls.clone(),
+                submitted_at: r.submitted_at,
+                owner_email: r.owner_email.clone(),
+                owner_username: r.owner_username.clone(),
+                content_findings: r.content_findings.clone(),
+            })
+            .collect())
+    }
+
+    async fn record_review_event(
+        &self,
+        registration_id: Uuid,
+        reviewer_id: Option<Uuid>,
+        reviewer_name: Option<String>,
+        action: ReviewAction,
+        notes: Option<String>,
+        rejection_reason: Option<String>,
+        new_status: BusinessVerificationStatus,
+        ctx: &AuditContext,
+    ) -> Result<BusinessRegistration, AppError> {
+        let mut state = self.state()?;
+        let registration = state
+            .registrations
+            .get_mut(&registration_id)
+            .ok_or(AppError::NotFound("Registration"))?;
+        let before = registration.clone();
+
+        registration.status = new_status;
+        registration.rejection_reason = rejection_reason;
+        registration.reviewer_notes = notes.or(registration.reviewer_notes.take());
+        registration.reviewer_id = reviewer_id.or(registration.reviewer_id);
+        registration.reviewer_name = reviewer_name.or(registration.reviewer_name.take());
+        registration.updated_at = Utc::now();
+        let registration = registration.clone();
+
+        state.record(ctx, AuditEntity::Registration, registration_id, AuditAction::Review(action), Some(&before), Some(&registration))?;
+        state.enqueue(DomainEventPayload::from_review_action(action, registration.clone()));
+        Ok(registration)
+    }
+
+    async fn get_review_stats(&self) -> Result<ReviewStats, AppError> {
+        let state = self.state()?;
+        let since = Utc::now() - Duration::days(1);
+        let count = |status: BusinessVerificationStatus, recent_only: bool| {
+            state
+                .registrations
+                .values()
+                .filter(|r| r.status == status && (!recent_only || r.submitted_at >= since))
+                .count() as i64
+        };
+
+        Ok(ReviewStats {
+            pending: count(BusinessVerificationStatus::Pending, false),
+            under_review: count(BusinessVerificationStatus::UnderReview, false),
+            approved_today: count(BusinessVerificationStatus::Approved, true),
+            rejected_today: count(BusinessVerificationStatus::Rejected, true),
+        })
+    }
+}
+
+// ============================================================================
+// BUSINESSES
+// ============================================================================
+
+#[async_trait]
+impl BusinessRepository for MemoryStore {
+    async fn create_business(&self, business: NewBusiness, ctx: &AuditContext) -> Result<Business, AppError> {
+        let business = Business {
+            id: business.id,
+            registration_id: business.registration_id,
+            owner_user_id: business.owner_user_id,
+            business_name: business.business_name,
+            category: business.category,
+            tax_id: business.tax_id,
+            description: business.description,
+            website: business.website,
+            logo_url: business.logo_url,
+            is_active: business.is_active,
+            promotions_trusted: false,
+            metadata: business.metadata,
+            created_at: business.created_at,
+            updated_at: business.updated_at,
+            deleted_at: None,
+            deleted_by: None,
+            version: 1,
+        };
+        let mut state = self.state()?;
+        state.businesses.insert(business.id, business.clone());
+        state.record(ctx, AuditEntity::Business, business.id, AuditAction::Create, None, Some(&business))?;
+        state.enqueue([DomainEventPayload::BusinessCreated(business.clone())]);
+        Ok(business)
+    }
+
+    async fn get_business(&self, business_id: Uuid) -> Result<Option<Business>, AppError> {
+        Ok(self.state()?.live_business(business_id).cloned())
+    }
+
+    async fn list_businesses_for_user(&self, user_id: Uuid, include_deleted: bool) -> Result<Vec<Business>, AppError> {
+        let mut businesses: Vec<Business> = self
+            .state()?
+            .businesses
+            .values()
+            .filter(|b| b.owner_user_id == user_id && (include_deleted || b.deleted_at.is_none()))
+            .cloned()
+            .collect();
+        businesses.sort_by_key(|b| Reverse(b.created_at));
+        Ok(businesses)
+    }
+
+    async fn update_business(&self, business: Business, ctx: &AuditContext) -> Result<Business, AppError> {
+        let mut state = self.state()?;
+        let current = state
+            .businesses
+            .get_mut(&business.id)
+            .filter(|b| b.deleted_at.is_none())
+            .ok_or(AppError::NotFound("Business"))?;
+        check_version(Some(business.version), current.version)?;
+        let before = current.clone();
+
+        *current = Business {
+            promotions_trusted: current.promotions_trusted,
+            owner_user_id: current.owner_user_id,
+            created_at: current.created_at,
+            deleted_at: None,
+            deleted_by: None,
+            version: current.version + 1,
+            updated_at: Utc::now(),
+            ..business
+        };
+        let business = current.clone();
+
+        state.record(ctx, AuditEntity::Business, business.id, AuditAction::Update, Some(&before), Some(&business))?;
+        state.enqueue([DomainEventPayload::BusinessUpdated(business.clone())]);
+        Ok(business)
+    }
+
+    async fn delete_business(
+        &self,
+        business_id: Uuid,
+        expected_version: Option<i32>,
+        force: bool,
+        ctx: &AuditContext,
+    ) -> Result<(), AppError> {
+        let mut state = self.state()?;
+        let current_version = state
+            .live_business(business_id)
+            .ok_or(AppError::NotFound("Business"))?
+            .version;
+        check_version(expected_version, current_version)?;
+
+        if !force {
+            let active = state
+                .promotions
+                .values()
+                .filter(|p| {
+                    p.business_id == business_id
+                        && p.status == BusinessPromotionStatus::Active
+                        && p.deleted_at.is_none()
+                        && !state.location_deleted(p.location_id)
+                })
+                .count() as i64;
+            if active > 0 {
+                return Err(AppError::ActivePromotions(active));
+            }
+        }
+
+        let now = Utc::now();
+        let mut promotions = Vec::new();
+        for promotion in state.promotions.values_mut() {
+            if promotion.business_id == business_id && promotion.deleted_at.is_none() {
+                let before = promotion.clone();
+                promotion.deleted_at = Some(now);
+                promotion.deleted_by = ctx.actor_id;
+                promotion.version += 1;
+                promotions.push((before, promotion.clone()));
+            }
+        }
+        let mut locations = Vec::new();
+        for location in state.locations.values_mut() {
+            if location.business_id == business_id && location.deleted_at.is_none() {
+                let before = location.clone();
+                location.deleted_at = Some(now);
+                location.deleted_by = ctx.actor_id;
+                location.version += 1;
+                locations.push((before, location.clone()));
+            }
+        }
+        let Some(business) = state.businesses.get_mut(&business_id) else {
+            return Err(AppError::NotFound("Business"));
+        };
+        let before = business.clone();
+        business.deleted_at = Some(now);
+        business.deleted_by = ctx.actor_id;
+        business.version += 1;
+        let business = business.clone();
+
+        for (before, promotion) in &promotions {
+            state.record(ctx, AuditEntity::Promotion, promotion.id, AuditAction::Delete, Some(before), Some(promotion))?;
+        }
+        for (before, location) in &locations {
+            state.record(ctx, AuditEntity::Location, location.id, AuditAction::Delete, Some(before), Some(location))?;
+        }
+        state.record(ctx, AuditEntity::Business, business_id, AuditAction::Delete, Some(&before), Some(&business))?;
+
+        let events = promotions
+            .iter()
+            .map(|(_, p)| DomainEventPayload::PromotionDeleted(EntityDeleted { id: p.id, parent_id: p.location_id }))
+            .chain(locations.iter().map(|(_, l)| {
+                DomainEventPayload::LocationDeleted(EntityDeleted { id: l.id, parent_id: Some(business_id) })
+            }))
+            .chain([DomainEventPayload::BusinessDeleted(EntityDeleted { id: business_id, parent_id: None })])
+            .collect::<Vec<_>>();
+        state.enqueue(events);
+        Ok(())
+    }
+
+    async fn restore_business(&self, business_id: Uuid, ctx: &AuditContext) -> Result<Business, AppError> {
+        let mut state = self.state()?;
+        let business = state
+            .businesses
+            .get_mut(&business_id)
+            .filter(|b| b.deleted_at.is_some())
+            .ok_or(AppError::NotFound("Business"))?;
+        let before = business.clone();
+        let deleted_at = business.deleted_at.take();
+        let now = Utc::now();
+        business.deleted_by = None;
+        business.version += 1;
+        business.updated_at = now;
+        let business = business.clone();
+
+        let mut locations = Vec::new();
+        for location in state.locations.values_mut() {
+            if location.business_id == business_id && location.deleted_at == deleted_at {
+                restore(&mut location.deleted_at, &mut location.deleted_by, &mut location.version, &mut location.updated_at, now);
+                locations.push(location.clone());
+            }
+        }
+        let mut promotions = Vec::new();
+        for promotion in state.promotions.values_mut() {
+            if promotion.business_id == business_id && promotion.deleted_at == deleted_at {
+                restore(&mut promotion.deleted_at, &mut promotion.deleted_by, &mut promotion.version, &mut promotion.updated_at, now);
+                promotions.push(promotion.clone());
+            }
+        }
+
+        state.record(ctx, AuditEntity::Business, business_id, AuditAction::Restore, Some(&before), Some(&business))?;
+        for location in &locations {
+            state.record(ctx, AuditEntity::Location, location.id, AuditAction::Restore, None, Some(location))?;
+        }
+        for promotion in &promotions {
+            state.record(ctx, AuditEntity::Promotion, promotion.id, AuditAction::Restore, None, Some(promotion))?;
+        }
+
+        let events = [DomainEventPayload::BusinessRestored(business.clone())]
+            .into_iter()
+            .chain(locations.into_iter().map(DomainEventPayload::LocationRestored))
+            .chain(promotions.into_iter().map(DomainEventPayload::PromotionRestored))
+            .collect::<Vec<_>>();
+        state.enqueue(events);
+        Ok(business)
+    }
+
+    async fn is_business_suspended(&self, business_id: Uuid) -> Result<bool, AppError> {
+        let state = self.state()?;
+        Ok(state
+            .businesses
+            .get(&business_id)
+            .and_then(|b| b.registration_id)
+            .and_then(|id| state.registrations.get(&id))
+            .is_some_and(|r| r.status == BusinessVerificationStatus::Suspended))
+    }
+}
+
+/// Clear a soft delete the way the `restore_*` statements do
+fn restore(
+    deleted_at: &mut Option<DateTime<Utc>>,
+    deleted_by: &mut Option<Uuid>,
+    version: &mut i32,
+    updated_at: &mut DateTime<Utc>,
+    now: DateTime<Utc>,
+) {
+    *deleted_at = None;
+    *deleted_by = None;
+    *version += 1;
+    *updated_at = now;
+}
+
+// ============================================================================
+// LOCATIONS
+// ============================================================================
+
+#[async_trait]
+impl LocationRepository for MemoryStore {
+    async fn create_location(&self, location: NewBusinessLocation, ctx: &AuditContext) -> Result<BusinessLocation, AppError> {
+        let mut state = self.state()?;
+        if !state.businesses.contains_key(&location.business_id) {
+            return Err(AppError::NotFound("Business"));
+        }
+
+        let now = Utc::now();
+        let location = BusinessLocation {
+            id: location.id,
+            business_id: location.business_id,
+            location_name: location.location_name,
+            formatted_address: location.formatted_address,
+            street: location.street,
+            city: location.city,
+            state_region: location.state_region,
+            postal_code: location.postal_code,
+            country: location.country,
+            latitude: location.latitude,
+            longitude: location.longitude,
+            google_place_id: location.google_place_id,
+            timezone: location.timezone,
+            phone: location.phone,
+            email: location.email,
+            is_active: location.is_active,
+            is_primary: location.is_primary,
+            operating_hours: location.operating_hours,
+            notes: location.notes,
+            metadata: location.metadata,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
+            version: 1,
+        };
+        state.locations.insert(location.id, location.clone());
+        state.record(ctx, AuditEntity::Location, location.id, AuditAction::Create, None, Some(&location))?;
+        state.enqueue([DomainEventPayload::LocationCreated(location.clone())]);
+        Ok(location)
+    }
+
+    async fn get_location(&self, location_id: Uuid) -> Result<Option<BusinessLocation>, AppError> {
+        Ok(self.state()?.live_location(location_id).cloned())
+    }
+
+    async fn list_locations_for_business(
+        &self,
+        business_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Vec<BusinessLocation>, AppError> {
+        let mut locations: Vec<BusinessLocation> = self
+            .state()?
+            .locations
+            .values()
+            .filter(|l| l.business_id == business_id && (include_deleted || l.deleted_at.is_none()))
+            .cloned()
+            .collect();
+        locations.sort_by(|a, b| b.is_primary.cmp(&a.is_primary).then(a.created_at.cmp(&b.created_at)));
+        Ok(locations)
+    }
+
+    async fn update_location(&self, location: BusinessLocation, ctx: &AuditContext) -> Result<BusinessLocation, AppError> {
+        let mut state = self.state()?;
+        let current = state
+            .locations
+            .get_mut(&location.id)
+            .filter(|l| l.deleted_at.is_none())
+            .ok_or(AppError::NotFound("Location"))?;
+        check_version(Some(location.version), current.version)?;
+        let before = current.clone();
+
+        *current = BusinessLocation {
+            business_id: current.business_id,
+            created_at: current.created_at,
+            deleted_at: None,
+            deleted_by: None,
+            version: current.version + 1,
+            updated_at: Utc::now(),
+            ..location
+        };
+        let location = current.clone();
+
+        state.record(ctx, AuditEntity::Location, location.id, AuditAction::Update, Some(&before), Some(&location))?;
+        state.enqueue([DomainEventPayload::LocationUpdated(location.clone())]);
+        Ok(location)
+    }
+
+    async fn delete_location(&self, location_id: Uuid, expected_version: Option<i32>, ctx: &AuditContext) -> Result<(), AppError> {
+        let mut state = self.state()?;
+        let location = state
+            .locations
+            .get_mut(&location_id)
+            .filter(|l| l.deleted_at.is_none())
+            .ok_or(AppError::NotFound("Location"))?;
+        check_version(expected_version, location.version)?;
+
+        let now = Utc::now();
+        let before = location.clone();
+        location.deleted_at = Some(now);
+        location.deleted_by = ctx.actor_id;
+        location.version += 1;
+        let location = location.clone();
+        let mut promotions = Vec::new();
+        for promotion in state.promotions.values_mut() {
+            if promotion.location_id == Some(location_id) && promotion.deleted_at.is_none() {
+                let before = promotion.clone();
+                promotion.deleted_at = Some(now);
+                promotion.deleted_by = ctx.actor_id;
+                promotion.version += 1;
+                promotions.push((before, promotion.clone()));
+            }
+        }
+
+        for (before, promotion) in &promotions {
+            state.record(ctx, AuditEntity::Promotion, promotion.id, AuditAction::Delete, Some(before), Some(promotion))?;
+        }
+        state.record(ctx, AuditEntity::Location, location_id, AuditAction::Delete, Some(&before), Some(&location))?;
+
+        let events = promotions
+            .iter()
+            .map(|(_, p)| DomainEventPayload::PromotionDeleted(EntityDeleted { id: p.id, parent_id: Some(location_id) }))
+            .chain([DomainEventPayload::LocationDeleted(EntityDeleted { id: location_id, parent_id: Some(location.business_id) })])
+            .collect::<Vec<_>>();
+        state.enqueue(events);
+        Ok(())
+    }
+
+    async fn restore_location(&self, location_id: Uuid, ctx: &AuditContext) -> Result<BusinessLocation, AppError> {
+        let mut state = self.state()?;
+        let business_id = state
+            .locations
+            .get(&location_id)
+            .filter(|l| l.deleted_at.is_some())
+            .ok_or(AppError::NotFound("Location"))?
+            .business_id;
+        if state.live_business(business_id).is_none() {
+            return Err(AppError::ParentDeleted("Business"));
+        }
+
+        let now = Utc::now();
+        let Some(location) = state.locations.get_mut(&location_id) else {
+            return Err(AppError::NotFound("Location"));
+        };
+        let before = location.clone();
+        restore(&mut location.deleted_at, &mut location.deleted_by, &mut location.version, &mut location.updated_at, now);
+        let location = location.clone();
+
+        let mut promotions = Vec::new();
+        for promotion in state.promotions.values_mut() {
+            if promotion.location_id == Some(location_id) && promotion.deleted_at == before.deleted_at {
+                restore(&mut promotion.deleted_at, &mut promotion.deleted_by, &mut promotion.version, &mut promotion.updated_at, now);
+                promotions.push(promotion.clone());
+            }
+        }
+
+        state.record(ctx, AuditEntity::Location, location_id, AuditAction::Restore, Some(&before), Some(&location))?;
+        for promotion in &promotions {
+            state.record(ctx, AuditEntity::Promotion, promotion.id, AuditAction::Restore, None, Some(promotion))?;
+        }
+
+        let events = [DomainEventPayload::LocationRestored(location.clone())]
+            .into_iter()
+            .chain(promotions.into_iter().map(DomainEventPayload::PromotionRestored))
+            .collect::<Vec<_>>();
+        state.enqueue(events);
+        Ok(location)
+    }
+}
+
+// ============================================================================
+// PROMOTIONS
+// ============================================================================
+
+#[async_trait]
+impl PromotionRepository for MemoryStore {
+    async fn create_promotion(&self, promotion: NewBusinessPromotion, ctx: &AuditContext) -> Result<BusinessPromotion, AppError> {
+        let mut state = self.state()?;
+        state.check_business_locations(promotion.business_id, &promotion.location_ids)?;
+        let promotion = state.insert_promotion(promotion);
+        state.promotion_created(&promotion, ctx)?;
+        Ok(promotion)
+    }
+
+    async fn clone_promotion(
+        &self,
+        business_id: Uuid,
+        clones: Vec<NewBusinessPromotion>,
+        ctx: &AuditContext,
+    ) -> Result<Vec<BusinessPromotion>, AppError> {
+        let mut state = self.state()?;
+        let targets: Vec<Uuid> = clones.iter().flat_map(|clone| clone.location_ids.iter().copied()).collect();
+        state.check_business_locations(business_id, &targets)?;
+        let mut promotions = Vec::with_capacity(clones.len());
+        for clone in clones {
+            let promotion = state.insert_promotion(clone);
+            state.promotion_created(&promotion, ctx)?;
+            promotions.push(promotion);
+        }
+        Ok(promotions)
+    }
+
+    async fn get_promotion(&self, promotion_id: Uuid) -> Result<Option<BusinessPromotion>, AppError> {
+        Ok(self
+            .state()?
+            .promotions
+            .get(&promotion_id)
+            .filter(|p| p.deleted_at.is_none())
+            .cloned())
+    }
+
+    async fn list_promotions_for_location(
+        &self,
+        location_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Vec<BusinessPromotion>, AppError> {
+        let state = self.state()?;
+        let Some(location) = state.locations.get(&location_id) else {
+            return Ok(Vec::new());
+        };
+        let promotions = state
+            .promotions
+            .values()
+            .filter(|p| {
+                p.business_id == location.business_id
+                    && (p.target_scope == PromotionTargetScope::Business || state.targets(p.id, location_id).is_some())
+                    && (include_deleted || p.deleted_at.is_none())
+            })
+            .cloned()
+            .collect();
+        Ok(newest_start_first(promotions))
+    }
+
+    async fn list_promotions_for_business(
+        &self,
+        business_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Vec<BusinessPromotion>, AppError> {
+        let state = self.state()?;
+        let promotions = state
+            .promotions
+            .values()
+            .filter(|p| {
+                p.business_id == business_id
+                    && (include_deleted || (p.deleted_at.is_none() && !state.location_deleted(p.location_id)))
+            })
+            .cloned()
+            .collect();
+        Ok(newest_start_first(promotions))
+    }
+
+    async fn list_promotion_locations(&self, promotion_id: Uuid) -> Result<Vec<PromotionLocation>, AppError> {
+        let state = self.state()?;
+        let Some(promotion) = state.promotions.get(&promotion_id) else {
+            return Ok(Vec::new());
+        };
+        let mut locations: Vec<PromotionLocation> = state
+            .locations
+            .values()
+            .filter(|l| l.business_id == promotion.business_id && l.deleted_at.is_none())
+            .filter_map(|l| {
+                let total_claims = match state.targets(promotion_id, l.id) {
+                    Some(claims) => claims,
+                    None if promotion.target_scope == PromotionTargetScope::Business => 0,
+                    None => return None,
+                };
+                Some(PromotionLocation {
+                    location_id: l.id,
+                    location_name: l.location_name.clone(),
+                    total_claims,
+                })
+            })
+            .collect();
+        locations.sort_by(|a, b| a.location_name.cmp(&b.location_name).then(a.location_id.cmp(&b.location_id)));
+        Ok(locations)
+    }
+
+    async fn update_promotion(&self, promotion: BusinessPromotion, ctx: &AuditContext) -> Result<BusinessPromotion, AppError> {
+        let mut state = self.state()?;
+        let current = state
+            .promotions
+            .get_mut(&promotion.id)
+            .filter(|p| p.deleted_at.is_none())
+            .ok_or(AppError::NotFound("Promotion"))?;
+        check_version(Some(promotion.version), current.version)?;
+        let before = current.clone();
+
+        *current = BusinessPromotion {
+            business_id: current.business_id,
+            location_id: current.location_id,
+            target_scope: current.target_scope,
+            limit_scope: current.limit_scope,
+            total_claims: current.total_claims,
+            created_by: current.created_by,
+            created_at: current.created_at,
+            deleted_at: None,
+            deleted_by: None,
+            version: current.version + 1,
+            updated_at: Utc::now(),
+            ..promotion
+        };
+        let promotion = current.clone();
+
+        state.record(ctx, AuditEntity::Promotion, promotion.id, AuditAction::Update, Some(&before), Some(&promotion))?;
+        state.enqueue([DomainEventPayload::PromotionUpdated(promotion.clone())]);
+        if promotion.status == BusinessPromotionStatus::Active && before.status != BusinessPromotionStatus::Active {
+            state.enqueue([DomainEventPayload::PromotionActivated(promotion.clone())]);
+        }
+        Ok(promotion)
+    }
+
+    async fn delete_promotion(&self, promotion_id: Uuid, expected_version: Option<i32>, ctx: &AuditContext) -> Result<(), AppError> {
+        let mut state = self.state()?;
+        let promotion = state
+            .promotions
+            .get_mut(&promotion_id)
+            .filter(|p| p.deleted_at.is_none())
+            .ok_or(AppError::NotFound("Promotion"))?;
+        check_version(expected_version, promotion.version)?;
+
+        let before = promotion.clone();
+        promotion.deleted_at = Some(Utc::now());
+        promotion.deleted_by = ctx.actor_id;
+        promotion.version += 1;
+        let promotion = promotion.clone();
+
+        state.record(ctx, AuditEntity::Promotion, promotion_id, AuditAction::Delete, Some(&before), Some(&promotion))?;
+        state.enqueue([DomainEventPayload::PromotionDeleted(EntityDeleted { id: promotion_id, parent_id: promotion.location_id })]);
+        Ok(())
+    }
+
+    async fn restore_promotion(&self, promotion_id: Uuid, ctx: &AuditContext) -> Result<BusinessPromotion, AppError> {
+        let mut state = self.state()?;
+        let (business_id, location_id) = state
+            .promotions
+            .get(&promotion_id)
+            .filter(|p| p.deleted_at.is_some())
+            .map(|p| (p.business_id, p.location_id))
+            .ok_or(AppError::NotFound("Promotion"))?;
+        if state.live_business(business_id).is_none() {
+            return Err(AppError::ParentDeleted("Business"));
+        }
+        if state.location_deleted(location_id) {
+            return Err(AppError::ParentDeleted("Location"));
+        }
+
+        let Some(promotion) = state.promotions.get_mut(&promotion_id) else {
+            return Err(AppError::NotFound("Promotion"));
+        };
+        let before = promotion.clone();
+        restore(&mut promotion.deleted_at, &mut promotion.deleted_by, &mut promotion.version, &mut promotion.updated_at, Utc::now());
+        let promotion = promotion.clone();
+
+        state.record(ctx, AuditEntity::Promotion, promotion_id, AuditAction::Restore, Some(&before), Some(&promotion))?;
+        state.enqueue([DomainEventPayload::PromotionRestored(promotion.clone())]);
+        Ok(promotion)
+    }
+
+    async fn claim_promotion(&self, promotion_id: Uuid, location_id: Uuid) -> Result<PromotionClaim, AppError> {
+        let mut state = self.state()?;
+        let promotion = state
+            .promotions
+            .get(&promotion_id)
+            .filter(|p| p.deleted_at.is_none())
+            .ok_or(AppError::NotFound("Promotion"))?;
+        let now = Utc::now();
+        if promotion.status != BusinessPromotionStatus::Active || now < promotion.starts_at || now >= promotion.ends_at {
+            return Err(AppError::PromotionNotClaimable);
+        }
+
+        let location_claims = state
+            .live_location(location_id)
+            .filter(|l| l.business_id == promotion.business_id)
+            .and_then(|_| match state.targets(promotion_id, location_id) {
+                Some(claims) => Some(claims),
+                None if promotion.target_scope == PromotionTargetScope::Business => Some(0),
+                None => None,
+            })
+            .ok_or_else(|| AppError::Validation(vec![FieldError::new("location_id", "not_targeted", Default::default())]))?;
+
+        let (limit_scope, max_claims) = (promotion.limit_scope, promotion.max_claims);
+        let counted = match limit_scope {
+            PromotionLimitScope::Global => promotion.total_claims,
+            PromotionLimitScope::PerLocation => location_claims,
+        };
+        if let Some(max_claims) = max_claims.filter(|max_claims| counted >= *max_claims) {
+            return Err(AppError::ClaimLimitReached(max_claims));
+        }
+
+        let Some(promotion) = state.promotions.get_mut(&promotion_id) else {
+            return Err(AppError::NotFound("Promotion"));
+        };
+        promotion.total_claims += 1;
+        let total_claims = promotion.total_claims;
+        let targets = state.promotion_targets.entry(promotion_id).or_default();
+        let location_claims = match targets.iter_mut().find(|(target, _)| *target == location_id) {
+            Some((_, claims)) => {
+                *claims += 1;
+                *claims
+            }
+            None => {
+                targets.push((location_id, 1));
+                1
+            }
+        };
+
+        let counted = match limit_scope {
+            PromotionLimitScope::Global => total_claims,
+            PromotionLimitScope::PerLocation => location_claims,
+        };
+        Ok(PromotionClaim {
+            promotion_id,
+            location_id,
+            limit_scope,
+            max_claims,
+            total_claims,
+            location_claims,
+            remaining_claims: max_claims.map(|max_claims| (max_claims - counted).max(0)),
+        })
+    }
+}
+
+// ============================================================================
+// LOCATION ADMINISTRATORS
+// ============================================================================
+
+#[async_trait]
+impl LocationAdminRepository for MemoryStore {
+    async fn add_location_admin(&self, admin: NewLocationAdmin, ctx: &AuditContext) -> Result<LocationAdmin, AppError> {
+        let mut state = self.state()?;
+        if !state.locations.contains_key(&admin.location_id) {
+            return Err(AppError::NotFound("Location"));
+        }
+
+        let admin = LocationAdmin {
+            id: admin.id,
+            location_id: admin.location_id,
+            user_id: admin.user_id,
+            user_email: admin.user_email,
+            user_username: admin.user_username,
+            role: admin.role,
+            granted_by: admin.granted_by,
+            granted_by_username: admin.granted_by_username,
+            is_active: admin.is_active,
+            granted_at: admin.granted_at,
+            created_at: admin.created_at,
+            updated_at: admin.updated_at,
+        };
+        state.location_admins.push(admin.clone());
+        state.record(ctx, AuditEntity::LocationAdmin, admin.id, AuditAction::Create, None, Some(&admin))?;
+        state.enqueue([DomainEventPayload::LocationAdminAdded(admin.clone())]);
+        Ok(admin)
+    }
+
+    async fn list_location_admins(&self, location_id: Uuid) -> Result<Vec<LocationAdmin>, AppError> {
+        let mut admins: Vec<LocationAdmin> = self
+            .state()?
+            .location_admins
+            .iter()
+            .filter(|a| a.location_id == location_id && a.is_active)
+            .cloned()
+            .collect();
+        admins.sort_by_key(|a| Reverse(a.granted_at));
+        Ok(admins)
+    }
+
+    async fn remove_location_admin(&self, location_id: Uuid, user_id: Uuid, ctx: &AuditContext) -> Result<(), AppError> {
+        let mut state = self.state()?;
+        let now = Utc::now();
+        let mut removed = Vec::new();
+        for admin in state.location_admins.iter_mut() {
+            if admin.location_id == location_id && admin.user_id == user_id && admin.is_active {
+                let before = admin.clone();
+                admin.is_active = false;
+                admin.updated_at = now;
+                removed.push((before, admin.clone()));
+            }
+        }
+
+        for (before, admin) in &removed {
+            state.record(ctx, AuditEntity::LocationAdmin, admin.id, AuditAction::Delete, Some(before), Some(admin))?;
+        }
+        if !removed.is_empty() {
+            state.enqueue([DomainEventPayload::LocationAdminRemoved(LocationAdminRemoved { location_id, user_id })]);
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// AUDIT TRAIL
+// ============================================================================
+
+#[async_trait]
+impl AuditLogRepository for MemoryStore {
+    async fn list_audit_logs(&self, filter: &AuditLogFilter) -> Result<Vec<AuditLog>, AppError> {
+        let limit = filter.limit.unwrap_or(50).clamp(1, 500);
+        let offset = filter.offset.unwrap_or(0).max(0);
+
+        Ok(self
+            .state()?
+            .audit_logs
+            .iter()
+            .rev()
+            .filter(|entry| {
+                filter.entity_type.as_ref().is_none_or(|entity_type| *entity_type == entry.entity_type)
+                    && filter.entity_id.is_none_or(|entity_id| entity_id == entry.entity_id)
+                    && filter.actor_id.is_none_or(|actor_id| Some(actor_id) == entry.actor_id)
+                    && filter.action.as_ref().is_none_or(|action| *action == entry.action)
+                    && filter.from.is_none_or(|from| entry.created_at >= from)
+                    && filter.to.is_none_or(|to| entry.created_at < to)
+            })
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+}
+
+// ============================================================================
+// EVENT OUTBOX
+// ============================================================================
+
+#[async_trait]
+impl OutboxRepository for MemoryStore {
+    /// Webhook subscriptions only exist on Postgres, so events are just marked
+    /// as fanned out
+    async fn dispatch_outbox_webhooks(&self, limit: i64) -> Result<usize, AppError> {
+        let mut state = self.state()?;
+        let pending = state
+            .outbox
+            .iter_mut()
+            .filter(|entry| !entry.webhooks_dispatched)
+            .take(usize::try_from(limit).unwrap_or(0));
+        let mut processed = 0;
+        for entry in pending {
+            entry.webhooks_dispatched = true;
+            processed += 1;
+        }
+        Ok(processed)
+    }
+
+    async fn publish_outbox_events(&self, publisher: &dyn EventPublisher, limit: i64) -> Result<usize, AppError> {
+        let Ok(_publishing) = self.publishing.try_lock() else {
+            return Ok(0);
+        };
+        let pending: Vec<DomainEvent> = self
+            .state()?
+            .outbox
+            .iter()
+            .filter(|entry| !entry.published)
+            .take(usize::try_from(limit).unwrap_or(0))
+            .map(|entry| entry.event.clone())
+            .collect();
+
+        // The state lock is not held while the broker answers
+        let mut published = HashSet::with_capacity(pending.len());
+        for event in &pending {
+            if let Err(err) = publisher.publish(event).await {
+                log::warn!("Failed to publish outbox event {} via {}: {}", event.id, publisher.name(), err);
+                break;
+            }
+            published.insert(event.id);
+        }
+
+        for entry in self.state()?.outbox.iter_mut() {
+            if published.contains(&entry.event.id) {
+                entry.published = true;
+            }
+        }
+        Ok(published.len())
+    }
+
+    async fn purge_outbox(&self, retention_days: i32, publish_required: bool) -> Result<u64, AppError> {
+        let cutoff = Utc::now() - Duration::days(retention_days.into());
+        let mut state = self.state()?;
+        let before = state.outbox.len();
+        state.outbox.retain(|entry| {
+            entry.event.occurred_at >= cutoff || !entry.webhooks_dispatched || (publish_required && !entry.published)
+        });
+        Ok((before - state.outbox.len()) as u64)
+    }
+}
+
@@ -0,0 +1,274 @@
+//! Storage of registrations, businesses, locations, promotions, location
+//! admins, claims, the audit trail and the event outbox behind traits, so the
+//! handlers and the outbox relay do not depend on Postgres.
+//!
+//! `DatabaseService` implements every trait. `MemoryStore` implements them
+//! too, with the same rules (versions, soft-delete cascades, restores,
+//! target checks, claim limits) and the same audit entries and domain events
+//! for each write, keeping everything in the process: data is lost on
+//! restart. The backend is chosen at startup with `DATABASE_BACKEND`
+//! (`postgres` or `memory`).
+
+pub mod memory;
+pub mod postgres;
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::audit::AuditContext;
+use crate::database::DatabaseService;
+use crate::errors::AppError;
+use crate::events::EventPublisher;
+use crate::models::{
+    AuditLog, AuditLogFilter, Business, BusinessLocation, BusinessPromotion, BusinessRegistration,
+    BusinessVerificationStatus, LocationAdmin, NewBusiness, NewBusinessLocation, NewBusinessPromotion,
+    NewBusinessRegistration, NewLocationAdmin, PendingBusinessReview, PromotionClaim, PromotionLocation,
+    ReviewAction, ReviewStats,
+};
+
+pub use memory::MemoryStore;
+
+/// Where registrations, businesses, locations, promotions and admins live
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    #[default]
+    Postgres,
+    /// In-process maps, for tests and local demos. Covers the repositories
+    /// in this module; templates, webhooks, exports, imports, moderation,
+    /// trust and suspensions answer `postgres_required`.
+    Memory,
+}
+
+impl FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "postgres" => Ok(StorageBackend::Postgres),
+            "memory" => Ok(StorageBackend::Memory),
+            other => Err(format!("storage backend '{other}' is not supported (use postgres or memory)")),
+        }
+    }
+}
+
+#[async_trait]
+pub trait RegistrationRepository: Send + Sync {
+    async fn create_registration(
+        &self,
+        registration: NewBusinessRegistration,
+        ctx: &AuditContext,
+    ) -> Result<BusinessRegistration, AppError>;
+
+    async fn get_registration_by_id(&self, registration_id: Uuid) -> Result<Option<BusinessRegistration>, AppError>;
+
+    async fn get_latest_registration_for_user(&self, user_id: Uuid) -> Result<Option<BusinessRegistration>, AppError>;
+
+    /// Newest first
+    async fn list_registrations_for_user(&self, user_id: Uuid) -> Result<Vec<BusinessRegistration>, AppError>;
+
+    /// Pending registrations, oldest first
+    async fn list_pending_reviews(&self, limit: i64, offset: i64) -> Result<Vec<PendingBusinessReview>, AppError>;
+
+    /// Record a reviewer's action and move the registration to `new_status`
+    #[allow(clippy::too_many_arguments)]
+    async fn record_review_event(
+        &self,
+        registration_id: Uuid,
+        reviewer_id: Option<Uuid>,
+        reviewer_name: Option<String>,
+        action: ReviewAction,
+        notes: Option<String>,
+        rejection_reason: Option<String>,
+        new_status: BusinessVerificationStatus,
+        ctx: &AuditContext,
+    ) -> Result<BusinessRegistration, AppError>;
+
+    async fn get_review_stats(&self) -> Result<ReviewStats, AppError>;
+}
+
+#[async_trait]
+pub trait BusinessRepository: Send + Sync {
+    async fn create_business(&self, business: NewBusiness, ctx: &AuditContext) -> Result<Business, AppError>;
+
+    /// Live businesses only
+    async fn get_business(&self, business_id: Uuid) -> Result<Option<Business>, AppError>;
+
+    /// Newest first
+    async fn list_businesses_for_user(&self, user_id: Uuid, include_deleted: bool) -> Result<Vec<Business>, AppError>;
+
+    /// Write `business` if it still has the version it was read with
+    async fn update_business(&self, business: Business, ctx: &AuditContext) -> Result<Business, AppError>;
+
+    /// Soft-delete a business with its live locations and promotions; refused
+    /// while promotions are active unless `force` is set
+    async fn delete_business(
+        &self,
+        business_id: Uuid,
+        expected_version: Option<i32>,
+        force: bool,
+        ctx: &AuditContext,
+    ) -> Result<(), AppError>;
+
+    /// Undo `delete_business`, with the children deleted in the same cascade
+    async fn restore_business(&self, business_id: Uuid, ctx: &AuditContext) -> Result<Business, AppError>;
+
+    /// Whether the registration behind a business is suspended
+    async fn is_business_suspended(&self, business_id: Uuid) -> Result<bool, AppError>;
+}
+
+#[async_trait]
+pub trait LocationRepository: Send + Sync {
+    async fn create_location(&self, location: NewBusinessLocation, ctx: &AuditContext) -> Result<BusinessLocation, AppError>;
+
+    /// Live locations only
+    async fn get_location(&self, location_id: Uuid) -> Result<Option<BusinessLocation>, AppError>;
+
+    /// Primary location first, then oldest first
+    async fn list_locations_for_business(
+        &self,
+        business_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Vec<BusinessLocation>, AppError>;
+
+    /// Write `location` if it still has the version it was read with
+    async fn update_location(&self, location: BusinessLocation, ctx: &AuditContext) -> Result<BusinessLocation, AppError>;
+
+    /// Soft-delete a location and its live single-location promotions
+    async fn delete_location(&self, location_id: Uuid, expected_version: Option<i32>, ctx: &AuditContext) -> Result<(), AppError>;
+
+    /// Undo `delete_location`; refused while the business is deleted
+    async fn restore_location(&self, location_id: Uuid, ctx: &AuditContext) -> Result<BusinessLocation, AppError>;
+}
+
+#[async_trait]
+pub trait PromotionRepository: Send + Sync {
+    /// Every targeted location must be a live location of the business
+    async fn create_promotion(&self, promotion: NewBusinessPromotion, ctx: &AuditContext) -> Result<BusinessPromotion, AppError>;
+
+    /// Insert copies of a promotion, all or nothing
+    async fn clone_promotion(
+        &self,
+        business_id: Uuid,
+        clones: Vec<NewBusinessPromotion>,
+        ctx: &AuditContext,
+    ) -> Result<Vec<BusinessPromotion>, AppError>;
+
+    /// Live promotions only
+    async fn get_promotion(&self, promotion_id: Uuid) -> Result<Option<BusinessPromotion>, AppError>;
+
+    /// The location's own promotions plus the multi-location and
+    /// business-wide ones that apply to it, latest start first
+    async fn list_promotions_for_location(
+        &self,
+        location_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Vec<BusinessPromotion>, AppError>;
+
+    /// Latest start first; without `include_deleted`, promotions of deleted
+    /// locations are left out too
+    async fn list_promotions_for_business(
+        &self,
+        business_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Vec<BusinessPromotion>, AppError>;
+
+    /// Live locations a promotion applies to, with the claims recorded at each
+    async fn list_promotion_locations(&self, promotion_id: Uuid) -> Result<Vec<PromotionLocation>, AppError>;
+
+    /// Write `promotion` if it still has the version it was read with
+    async fn update_promotion(&self, promotion: BusinessPromotion, ctx: &AuditContext) -> Result<BusinessPromotion, AppError>;
+
+    async fn delete_promotion(&self, promotion_id: Uuid, expected_version: Option<i32>, ctx: &AuditContext) -> Result<(), AppError>;
+
+    /// Undo `delete_promotion`; refused while its business or location is
+    /// deleted
+    async fn restore_promotion(&self, promotion_id: Uuid, ctx: &AuditContext) -> Result<BusinessPromotion, AppError>;
+
+    /// Count one claim of an active promotion at one of its locations,
+    /// within `max_claims` as its `limit_scope` applies it
+    async fn claim_promotion(&self, promotion_id: Uuid, location_id: Uuid) -> Result<PromotionClaim, AppError>;
+}
+
+#[async_trait]
+pub trait LocationAdminRepository: Send + Sync {
+    async fn add_location_admin(&self, admin: NewLocationAdmin, ctx: &AuditContext) -> Result<LocationAdmin, AppError>;
+
+    /// Active admins, most recently granted first
+    async fn list_location_admins(&self, location_id: Uuid) -> Result<Vec<LocationAdmin>, AppError>;
+
+    /// Deactivate the user's grants on the location; nothing to remove is
+    /// not an error
+    async fn remove_location_admin(&self, location_id: Uuid, user_id: Uuid, ctx: &AuditContext) -> Result<(), AppError>;
+}
+
+#[async_trait]
+pub trait AuditLogRepository: Send + Sync {
+    /// Entries matching `filter`, newest first
+    async fn list_audit_logs(&self, filter: &AuditLogFilter) -> Result<Vec<AuditLog>, AppError>;
+}
+
+/// Events written together with the changes that produced them, waiting to
+/// be relayed by `jobs::outbox_relay`
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    /// Turn up to `limit` events into webhook deliveries; returns how many
+    /// events were processed
+    async fn dispatch_outbox_webhooks(&self, limit: i64) -> Result<usize, AppError>;
+
+    /// Publish up to `limit` events in commit order, stopping at the first
+    /// failure; returns how many were published
+    async fn publish_outbox_events(&self, publisher: &dyn EventPublisher, limit: i64) -> Result<usize, AppError>;
+
+    /// Delete relayed events older than `retention_days`
+    async fn purge_outbox(&self, retention_days: i32, publish_required: bool) -> Result<u64, AppError>;
+}
+
+/// One implementation of every repository, as the handlers receive them
+#[derive(Clone)]
+pub struct Repositories {
+    pub registrations: Arc<dyn RegistrationRepository>,
+    pub businesses: Arc<dyn BusinessRepository>,
+    pub locations: Arc<dyn LocationRepository>,
+    pub promotions: Arc<dyn PromotionRepository>,
+    pub location_admins: Arc<dyn LocationAdminRepository>,
+    pub audit_logs: Arc<dyn AuditLogRepository>,
+    pub outbox: Arc<dyn OutboxRepository>,
+}
+
+impl Repositories {
+    pub fn postgres(db: Arc<DatabaseService>) -> Self {
+        Self::backed_by(db)
+    }
+
+    /// A fresh, empty `MemoryStore`
+    pub fn memory() -> Self {
+        Self::backed_by(Arc::new(MemoryStore::new()))
+    }
+
+    fn backed_by<S>(store: Arc<S>) -> Self
+    where
+        S: RegistrationRepository
+            + BusinessRepository
+            + LocationRepository
+            + PromotionRepository
+            + LocationAdminRepository
+            + AuditLogRepository
+            + OutboxRepository
+            + 'static,
+    {
+        Self {
+            registrations: store.clone(),
+            businesses: store.clone(),
+            locations: store.clone(),
+            promotions: store.clone(),
+            location_admins: store.clone(),
+            audit_logs: store.clone(),
+            outbox: store,
+        }
+    }
+}
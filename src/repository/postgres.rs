@@ -0,0 +1,238 @@
+//! The repositories on Postgres: `DatabaseService` already has every
+//! operation, with its audit entries and outbox events in the same
+//! transaction.
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use super::{
+    AuditLogRepository, BusinessRepository, LocationAdminRepository, LocationRepository, OutboxRepository,
+    PromotionRepository, RegistrationRepository,
+};
+use crate::audit::AuditContext;
+use crate::database::DatabaseService;
+use crate::errors::AppError;
+use crate::events::EventPublisher;
+use crate::models::{
+    AuditLog, AuditLogFilter, Business, BusinessLocation, BusinessPromotion, BusinessRegistration,
+    BusinessVerificationStatus, LocationAdmin, NewBusiness, NewBusinessLocation, NewBusinessPromotion,
+    NewBusinessRegistration, NewLocationAdmin, PendingBusinessReview, PromotionClaim, PromotionLocation,
+    ReviewAction, ReviewStats,
+};
+
+#[async_trait]
+impl RegistrationRepository for DatabaseService {
+    async fn create_registration(
+        &self,
+        registration: NewBusinessRegistration,
+        ctx: &AuditContext,
+    ) -> Result<BusinessRegistration, AppError> {
+        DatabaseService::create_registration(self, registration, ctx).await
+    }
+
+    async fn get_registration_by_id(&self, registration_id: Uuid) -> Result<Option<BusinessRegistration>, AppError> {
+        DatabaseService::get_registration_by_id(self, registration_id).await
+    }
+
+    async fn get_latest_registration_for_user(&self, user_id: Uuid) -> Result<Option<BusinessRegistration>, AppError> {
+        DatabaseService::get_latest_registration_for_user(self, user_id).await
+    }
+
+    async fn list_registrations_for_user(&self, user_id: Uuid) -> Result<Vec<BusinessRegistration>, AppError> {
+        DatabaseService::list_registrations_for_user(self, user_id).await
+    }
+
+    async fn list_pending_reviews(&self, limit: i64, offset: i64) -> Result<Vec<PendingBusinessReview>, AppError> {
+        DatabaseService::list_pending_reviews(self, limit, offset).await
+    }
+
+    async fn record_review_event(
+        &self,
+        registration_id: Uuid,
+        reviewer_id: Option<Uuid>,
+        reviewer_name: Option<String>,
+        action: ReviewAction,
+        notes: Option<String>,
+        rejection_reason: Option<String>,
+        new_status: BusinessVerificationStatus,
+        ctx: &AuditContext,
+    ) -> Result<BusinessRegistration, AppError> {
+        DatabaseService::record_review_event(
+            self,
+            registration_id,
+            reviewer_id,
+            reviewer_name,
+            action,
+            notes,
+            rejection_reason,
+            new_status,
+            ctx,
+        )
+        .await
+    }
+
+    async fn get_review_stats(&self) -> Result<ReviewStats, AppError> {
+        DatabaseService::get_review_stats(self).await
+    }
+}
+
+#[async_trait]
+impl BusinessRepository for DatabaseService {
+    async fn create_business(&self, business: NewBusiness, ctx: &AuditContext) -> Result<Business, AppError> {
+        DatabaseService::create_business(self, business, ctx).await
+    }
+
+    async fn get_business(&self, business_id: Uuid) -> Result<Option<Business>, AppError> {
+        DatabaseService::get_business(self, business_id).await
+    }
+
+    async fn list_businesses_for_user(&self, user_id: Uuid, include_deleted: bool) -> Result<Vec<Business>, AppError> {
+        DatabaseService::list_businesses_for_user(self, user_id, include_deleted).await
+    }
+
+    async fn update_business(&self, business: Business, ctx: &AuditContext) -> Result<Business, AppError> {
+        DatabaseService::update_business(self, business, ctx).await
+    }
+
+    async fn delete_business(
+        &self,
+        business_id: Uuid,
+        expected_version: Option<i32>,
+        force: bool,
+        ctx: &AuditContext,
+    ) -> Result<(), AppError> {
+        DatabaseService::delete_business(self, business_id, expected_version, force, ctx).await
+    }
+
+    async fn restore_business(&self, business_id: Uuid, ctx: &AuditContext) -> Result<Business, AppError> {
+        DatabaseService::restore_business(self, business_id, ctx).await
+    }
+
+    async fn is_business_suspended(&self, business_id: Uuid) -> Result<bool, AppError> {
+        DatabaseService::is_business_suspended(self, business_id).await
+    }
+}
+
+#[async_trait]
+impl LocationRepository for DatabaseService {
+    async fn create_location(&self, location: NewBusinessLocation, ctx: &AuditContext) -> Result<BusinessLocation, AppError> {
+        DatabaseService::create_location(self, location, ctx).await
+    }
+
+    async fn get_location(&self, location_id: Uuid) -> Result<Option<BusinessLocation>, AppError> {
+        DatabaseService::get_location(self, location_id).await
+    }
+
+    async fn list_locations_for_business(
+        &self,
+        business_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Vec<BusinessLocation>, AppError> {
+        DatabaseService::list_locations_for_business(self, business_id, include_deleted).await
+    }
+
+    async fn update_location(&self, location: BusinessLocation, ctx: &AuditContext) -> Result<BusinessLocation, AppError> {
+        DatabaseService::update_location(self, location, ctx).await
+    }
+
+    async fn delete_location(&self, location_id: Uuid, expected_version: Option<i32>, ctx: &AuditContext) -> Result<(), AppError> {
+        DatabaseService::delete_location(self, location_id, expected_version, ctx).await
+    }
+
+    async fn restore_location(&self, location_id: Uuid, ctx: &AuditContext) -> Result<BusinessLocation, AppError> {
+        DatabaseService::restore_location(self, location_id, ctx).await
+    }
+}
+
+#[async_trait]
+impl PromotionRepository for DatabaseService {
+    async fn create_promotion(&self, promotion: NewBusinessPromotion, ctx: &AuditContext) -> Result<BusinessPromotion, AppError> {
+        DatabaseService::create_promotion(self, promotion, ctx).await
+    }
+
+    async fn clone_promotion(
+        &self,
+        business_id: Uuid,
+        clones: Vec<NewBusinessPromotion>,
+        ctx: &AuditContext,
+    ) -> Result<Vec<BusinessPromotion>, AppError> {
+        DatabaseService::clone_promotion(self, business_id, clones, ctx).await
+    }
+
+    async fn get_promotion(&self, promotion_id: Uuid) -> Result<Option<BusinessPromotion>, AppError> {
+        DatabaseService::get_promotion(self, promotion_id).await
+    }
+
+    async fn list_promotions_for_location(
+        &self,
+        location_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Vec<BusinessPromotion>, AppError> {
+        DatabaseService::list_promotions_for_location(self, location_id, include_deleted).await
+    }
+
+    async fn list_promotions_for_business(
+        &self,
+        business_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Vec<BusinessPromotion>, AppError> {
+        DatabaseService::list_promotions_for_business(self, business_id, include_deleted).await
+    }
+
+    async fn list_promotion_locations(&self, promotion_id: Uuid) -> Result<Vec<PromotionLocation>, AppError> {
+        DatabaseService::list_promotion_locations(self, promotion_id).await
+    }
+
+    async fn update_promotion(&self, promotion: BusinessPromotion, ctx: &AuditContext) -> Result<BusinessPromotion, AppError> {
+        DatabaseService::update_promotion(self, promotion, ctx).await
+    }
+
+    async fn delete_promotion(&self, promotion_id: Uuid, expected_version: Option<i32>, ctx: &AuditContext) -> Result<(), AppError> {
+        DatabaseService::delete_promotion(self, promotion_id, expected_version, ctx).await
+    }
+
+    async fn restore_promotion(&self, promotion_id: Uuid, ctx: &AuditContext) -> Result<BusinessPromotion, AppError> {
+        DatabaseService::restore_promotion(self, promotion_id, ctx).await
+    }
+
+    async fn claim_promotion(&self, promotion_id: Uuid, location_id: Uuid) -> Result<PromotionClaim, AppError> {
+        DatabaseService::claim_promotion(self, promotion_id, location_id).await
+    }
+}
+
+#[async_trait]
+impl LocationAdminRepository for DatabaseService {
+    async fn add_location_admin(&self, admin: NewLocationAdmin, ctx: &AuditContext) -> Result<LocationAdmin, AppError> {
+        DatabaseService::add_location_admin(self, admin, ctx).await
+    }
+
+    async fn list_location_admins(&self, location_id: Uuid) -> Result<Vec<LocationAdmin>, AppError> {
+        DatabaseService::list_location_admins(self, location_id).await
+    }
+
+    async fn remove_location_admin(&self, location_id: Uuid, user_id: Uuid, ctx: &AuditContext) -> Result<(), AppError> {
+        DatabaseService::remove_location_admin(self, location_id, user_id, ctx).await
+    }
+}
+
+#[async_trait]
+impl AuditLogRepository for DatabaseService {
+    async fn list_audit_logs(&self, filter: &AuditLogFilter) -> Result<Vec<AuditLog>, AppError> {
+        DatabaseService::list_audit_logs(self, filter).await
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for DatabaseService {
+    async fn dispatch_outbox_webhooks(&self, limit: i64) -> Result<usize, AppError> {
+        DatabaseService::dispatch_outbox_webhooks(self, limit).await
+    }
+
+    async fn publish_outbox_events(&self, publisher: &dyn EventPublisher, limit: i64) -> Result<usize, AppError> {
+        DatabaseService::publish_outbox_events(self, publisher, limit).await
+    }
+
+    async fn purge_outbox(&self, retention_days: i32, publish_required: bool) -> Result<u64, AppError> {
+        DatabaseService::purge_outbox(self, retention_days, publish_required).await
+    }
+}
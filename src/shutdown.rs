@@ -0,0 +1,169 @@
+//! Coordinated shutdown of the HTTP server and background work.
+//!
+//! On SIGTERM or Ctrl-C the server stops accepting connections and lets
+//! in-flight requests finish, while background jobs stop after their current
+//! cycle and one-off tasks (e.g. stories shares) run to completion. All of it
+//! shares one deadline (`server.shutdown_timeout_secs`); whatever is still
+//! running then is abandoned and logged, and the connection pool is closed.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use tokio::runtime::Handle;
+use tokio::time::{Instant, Interval};
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+
+/// Tracks every background task so shutdown can wait for them
+#[derive(Clone)]
+pub struct Shutdown {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    token: CancellationToken,
+    tracker: TaskTracker,
+    /// Runtime of `main`, which outlives the HTTP workers' runtimes
+    runtime: Handle,
+    next_id: AtomicU64,
+    running: Mutex<BTreeMap<u64, &'static str>>,
+    started_at: OnceLock<Instant>,
+}
+
+impl Shutdown {
+    /// Must be called from the runtime that runs until the process exits,
+    /// which is why there is no `Default`
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                token: CancellationToken::new(),
+                tracker: TaskTracker::new(),
+                runtime: Handle::current(),
+                next_id: AtomicU64::new(0),
+                running: Mutex::new(BTreeMap::new()),
+                started_at: OnceLock::new(),
+            }),
+        }
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.inner.token.is_cancelled()
+    }
+
+    /// Resolves once shutdown begins
+    pub async fn cancelled(&self) {
+        self.inner.token.cancelled().await
+    }
+
+    /// Spawn tracked work. It runs on the main runtime, so a task started by
+    /// a request outlives the HTTP worker that handled it.
+    pub fn spawn<F>(&self, name: &'static str, future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        self.running().insert(id, name);
+        let inner = self.inner.clone();
+        self.inner.tracker.spawn_on(
+            async move {
+                future.await;
+                inner.running.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+            },
+            &self.inner.runtime,
+        );
+    }
+
+    /// Interval for a job loop that stops ticking once shutdown begins, so
+    /// the job finishes its current cycle and exits
+    pub fn ticker(&self, period: Duration) -> Ticker {
+        Ticker {
+            interval: tokio::time::interval(period),
+            token: self.inner.token.clone(),
+        }
+    }
+
+    /// Start shutting down: jobs stop after their current cycle. The
+    /// deadline counts from the first call.
+    pub fn begin(&self) {
+        self.inner.started_at.get_or_init(Instant::now);
+        self.inner.token.cancel();
+    }
+
+    /// Wait for tracked tasks until `timeout` after shutdown began, then log
+    /// what is still running; it is abandoned when the runtime stops
+    pub async fn drain(&self, timeout: Duration) {
+        self.begin();
+        let deadline = *self.inner.started_at.get_or_init(Instant::now) + timeout;
+        self.inner.tracker.close();
+
+        let pending = self.running().len();
+        if pending > 0 {
+            log::info!("Waiting for {} background tasks to finish", pending);
+        }
+        if tokio::time::timeout_at(deadline, self.inner.tracker.wait()).await.is_ok() {
+            log::info!("All background tasks finished");
+            return;
+        }
+
+        let mut abandoned: BTreeMap<&str, usize> = BTreeMap::new();
+        for name in self.running().values() {
+            *abandoned.entry(name).or_default() += 1;
+        }
+        let summary: Vec<String> = abandoned.iter().map(|(name, count)| format!("{name} x{count}")).collect();
+        log::warn!(
+            "Shutdown deadline of {}s passed; abandoning {} background tasks: {}",
+            timeout.as_secs(),
+            abandoned.values().sum::<usize>(),
+            summary.join(", ")
+        );
+    }
+
+    fn running(&self) -> std::sync::MutexGuard<'_, BTreeMap<u64, &'static str>> {
+        self.inner.running.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+pub struct Ticker {
+    interval: Interval,
+    token: CancellationToken,
+}
+
+impl Ticker {
+    /// Wait for the next tick; `false` once shutdown has begun
+    pub async fn tick(&mut self) -> bool {
+        tokio::select! {
+            biased;
+            _ = self.token.cancelled() => false,
+            _ = self.interval.tick() => true,
+        }
+    }
+}
+
+/// Resolves on SIGTERM (what orchestrators send) or Ctrl-C
+pub async fn signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = sigterm.recv() => log::info!("SIGTERM received"),
+                    _ = tokio::signal::ctrl_c() => log::info!("Ctrl-C received"),
+                }
+            }
+            Err(err) => {
+                log::warn!("Cannot listen for SIGTERM: {err}");
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        log::info!("Ctrl-C received");
+    }
+}
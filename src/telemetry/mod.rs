@@ -0,0 +1,342 @@
+//! Logging, request correlation and trace propagation.
+//!
+//! Every request runs with a [`RequestContext`]: the `X-Request-Id` sent by
+//! the caller (or a new one) and a W3C trace context continued from its
+//! `traceparent` header (or a new trace). Log lines written while handling
+//! the request carry both ids, outbound calls forward them, and with the
+//! `otel` feature the request and database spans are exported over OTLP.
+
+mod format;
+#[cfg(feature = "otel")]
+mod otel;
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::HttpMessage;
+use tracing::Instrument;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+pub const TRACEPARENT_HEADER: HeaderName = HeaderName::from_static("traceparent");
+
+/// Longest `X-Request-Id` accepted from a caller
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_CONTEXT: RequestContext;
+}
+
+/// W3C trace context of one span: `00-{trace_id}-{span_id}-{flags}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceParent {
+    pub trace_id: [u8; 16],
+    pub span_id: [u8; 8],
+    pub sampled: bool,
+}
+
+impl TraceParent {
+    /// Start of a new trace
+    pub fn root() -> Self {
+        Self {
+            trace_id: *Uuid::new_v4().as_bytes(),
+            span_id: new_span_id(),
+            sampled: true,
+        }
+    }
+
+    /// Span of this service continuing the caller's trace
+    pub fn child(&self) -> Self {
+        Self {
+            span_id: new_span_id(),
+            ..*self
+        }
+    }
+
+    /// Parse a `traceparent` header; unknown versions are read as version
+    /// 00, as the spec asks, and all-zero ids are rejected
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.trim().split('-');
+        let (version, trace_id, span_id, flags) = (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+        if version.len() != 2 || version.eq_ignore_ascii_case("ff") || (version == "00" && parts.next().is_some()) {
+            return None;
+        }
+        let mut parsed = Self {
+            trace_id: [0; 16],
+            span_id: [0; 8],
+            sampled: false,
+        };
+        hex::decode_to_slice(trace_id, &mut parsed.trace_id).ok()?;
+        hex::decode_to_slice(span_id, &mut parsed.span_id).ok()?;
+        let mut flags_byte = [0; 1];
+        hex::decode_to_slice(flags, &mut flags_byte).ok()?;
+        let lowercase = [trace_id, span_id, flags].iter().all(|part| part.bytes().all(is_lower_hex));
+        if !lowercase || parsed.trace_id == [0; 16] || parsed.span_id == [0; 8] {
+            return None;
+        }
+        parsed.sampled = flags_byte[0] & 0x01 == 1;
+        Some(parsed)
+    }
+
+    pub fn trace_id_hex(&self) -> String {
+        hex::encode(self.trace_id)
+    }
+
+    pub fn span_id_hex(&self) -> String {
+        hex::encode(self.span_id)
+    }
+
+    pub fn to_header(self) -> String {
+        format!(
+            "00-{}-{}-{}",
+            self.trace_id_hex(),
+            self.span_id_hex(),
+            if self.sampled { "01" } else { "00" }
+        )
+    }
+}
+
+fn is_lower_hex(byte: u8) -> bool {
+    byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte)
+}
+
+fn new_span_id() -> [u8; 8] {
+    let bytes = Uuid::new_v4().into_bytes();
+    let mut span_id = [0; 8];
+    span_id.copy_from_slice(&bytes[8..]);
+    span_id
+}
+
+/// Correlation ids of the request being handled
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub request_id: String,
+    /// This service's span for the request
+    pub trace: TraceParent,
+}
+
+/// Context of the request the current task is handling, if any
+pub fn current() -> Option<RequestContext> {
+    REQUEST_CONTEXT.try_with(RequestContext::clone).ok()
+}
+
+/// Run `future` with the request context and span of the caller, for work
+/// that outlives the request such as a spawned stories call
+pub fn inherit<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let span = tracing::Span::current();
+    let context = current();
+    async move {
+        match context {
+            Some(context) => REQUEST_CONTEXT.scope(context, future).instrument(span).await,
+            None => future.instrument(span).await,
+        }
+    }
+}
+
+/// Add `traceparent` and `X-Request-Id` of the current request to an
+/// outbound call. Every client calling another service should send its
+/// requests through here.
+pub fn propagate(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    let context = current();
+    let traceparent = outbound_traceparent(context.as_ref());
+    let request = match traceparent {
+        Some(traceparent) => request.header(TRACEPARENT_HEADER.as_str(), traceparent.to_header()),
+        None => request,
+    };
+    match context {
+        Some(context) => request.header(REQUEST_ID_HEADER.as_str(), context.request_id),
+        None => request,
+    }
+}
+
+/// Parent of an outbound call: the innermost exported span when spans are
+/// exported, else this service's span for the request
+fn outbound_traceparent(context: Option<&RequestContext>) -> Option<TraceParent> {
+    #[cfg(feature = "otel")]
+    if let Some(traceparent) = otel::current_span() {
+        return Some(traceparent);
+    }
+    context.map(|context| context.trace)
+}
+
+/// Middleware giving each request its [`RequestContext`], echoing the request
+/// id in the `X-Request-Id` response header and writing the access log line.
+/// It must wrap every other middleware so their log lines are correlated too.
+pub async fn correlate<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<B>, actix_web::Error> {
+    let request_id = req
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(str::trim)
+        .filter(|id| is_valid_request_id(id))
+        .map(String::from)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let incoming = req
+        .headers()
+        .get(&TRACEPARENT_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .and_then(TraceParent::parse);
+
+    let span = tracing::info_span!(
+        "http_request",
+        otel.kind = "server",
+        http.method = %req.method(),
+        http.target = %req.path(),
+        request_id = %request_id,
+    );
+    let trace = request_trace(&span, incoming);
+
+    let context = RequestContext {
+        request_id: request_id.clone(),
+        trace,
+    };
+    req.extensions_mut().insert(context.clone());
+
+    let access = AccessLog::of(&req);
+    let started = Instant::now();
+    let result = REQUEST_CONTEXT
+        .scope(context.clone(), async {
+            let result = next.call(req).await;
+            let status = match &result {
+                Ok(res) => res.status(),
+                Err(err) => err.as_response_error().status_code(),
+            };
+            access.write(status.as_u16(), started.elapsed());
+            result
+        })
+        .instrument(span)
+        .await;
+
+    let mut res = result?;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        res.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    Ok(res)
+}
+
+/// What the access log records about a request
+struct AccessLog {
+    method: String,
+    target: String,
+    peer: Option<String>,
+    user_agent: Option<String>,
+}
+
+impl AccessLog {
+    fn of(req: &ServiceRequest) -> Self {
+        Self {
+            method: req.method().to_string(),
+            target: req.uri().path_and_query().map_or_else(|| req.path().to_string(), |pq| pq.to_string()),
+            peer: req.peer_addr().map(|addr| addr.ip().to_string()),
+            user_agent: req
+                .headers()
+                .get(actix_web::http::header::USER_AGENT)
+                .and_then(|h| h.to_str().ok())
+                .map(String::from),
+        }
+    }
+
+    fn write(&self, status: u16, elapsed: Duration) {
+        tracing::info!(
+            target: "access",
+            method = %self.method,
+            path = %self.target,
+            status,
+            duration_ms = elapsed.as_secs_f64() * 1000.0,
+            peer = self.peer.as_deref(),
+            user_agent = self.user_agent.as_deref(),
+            "{} {} {}",
+            self.method,
+            self.target,
+            status,
+        );
+    }
+}
+
+/// Ids of this service's span for the request: the exported span when spans
+/// are exported, else a new span in the caller's trace (or a new trace)
+fn request_trace(span: &tracing::Span, incoming: Option<TraceParent>) -> TraceParent {
+    #[cfg(feature = "otel")]
+    if let Some(exported) = otel::link(span, incoming) {
+        return exported;
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = span;
+    incoming.map_or_else(TraceParent::root, |parent| parent.child())
+}
+
+/// Ids from callers are echoed back and logged, so only short printable
+/// ones are kept
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogFormat {
+    Json,
+    Text,
+}
+
+/// Flushes exported spans when the service stops
+pub struct TelemetryGuard {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl TelemetryGuard {
+    pub fn shutdown(self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider {
+            if let Err(err) = provider.shutdown() {
+                log::warn!("Failed to flush exported spans: {err}");
+            }
+        }
+    }
+}
+
+/// Install the global logger. `RUST_LOG` filters as before (default `info`)
+/// and `LOG_FORMAT` picks `json` lines (default) or human-readable `text`.
+/// With the `otel` feature, spans are exported when
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` is set.
+pub fn init() -> Result<TelemetryGuard, String> {
+    let format = match std::env::var("LOG_FORMAT").unwrap_or_default().trim().to_ascii_lowercase().as_str() {
+        "" | "json" => LogFormat::Json,
+        "text" => LogFormat::Text,
+        other => return Err(format!("LOG_FORMAT '{other}' is not supported (use json or text)")),
+    };
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let fmt_layer = match format {
+        LogFormat::Json => tracing_subscriber::fmt::layer().event_format(format::JsonLines).boxed(),
+        LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+    };
+    let subscriber = tracing_subscriber::registry().with(filter).with(fmt_layer);
+
+    #[cfg(feature = "otel")]
+    {
+        let provider = otel::tracer_provider()?;
+        subscriber
+            .with(provider.as_ref().map(otel::layer))
+            .try_init()
+            .map_err(|err| err.to_string())?;
+        Ok(TelemetryGuard { provider })
+    }
+
+    #[cfg(not(feature = "otel"))]
+    {
+        subscriber.try_init().map_err(|err| err.to_string())?;
+        if std::env::var_os("OTEL_EXPORTER_OTLP_ENDPOINT").is_some() {
+            log::warn!("OTEL_EXPORTER_OTLP_ENDPOINT is set but this build has no `otel` feature; spans are not exported");
+        }
+        Ok(TelemetryGuard {})
+    }
+}
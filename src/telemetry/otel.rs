@@ -0,0 +1,75 @@
+//! OTLP export of spans, built with the `otel` feature.
+
+use opentelemetry::trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState, TracerProvider};
+use opentelemetry::Context;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::registry::LookupSpan;
+
+use super::TraceParent;
+use crate::health::SERVICE_NAME;
+
+/// Exporter configured by the standard `OTEL_EXPORTER_OTLP_*` variables
+/// (OTLP over HTTP), or `None` when no endpoint is set
+pub(super) fn tracer_provider() -> Result<Option<SdkTracerProvider>, String> {
+    let configured = ["OTEL_EXPORTER_OTLP_ENDPOINT", "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT"]
+        .iter()
+        .any(|key| std::env::var(key).is_ok_and(|value| !value.trim().is_empty()));
+    if !configured {
+        return Ok(None);
+    }
+
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .build()
+        .map_err(|err| format!("invalid OTLP exporter configuration: {err}"))?;
+    let service_name = std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| SERVICE_NAME.to_string());
+    Ok(Some(
+        SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(service_name).build())
+            .build(),
+    ))
+}
+
+pub(super) fn layer<S>(provider: &SdkTracerProvider) -> tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME))
+}
+
+/// Make `span` a child of the caller's span and return the ids it is
+/// exported with, or `None` when spans are not exported
+pub(super) fn link(span: &Span, incoming: Option<TraceParent>) -> Option<TraceParent> {
+    if let Some(parent) = incoming {
+        let flags = if parent.sampled { TraceFlags::SAMPLED } else { TraceFlags::default() };
+        let remote = SpanContext::new(
+            TraceId::from_bytes(parent.trace_id),
+            SpanId::from_bytes(parent.span_id),
+            flags,
+            true,
+            TraceState::default(),
+        );
+        let _ = span.set_parent(Context::new().with_remote_span_context(remote));
+    }
+    exported(span)
+}
+
+/// Ids of the innermost exported span of the current task
+pub(super) fn current_span() -> Option<TraceParent> {
+    exported(&Span::current())
+}
+
+fn exported(span: &Span) -> Option<TraceParent> {
+    let context = span.context();
+    let span_context = context.span().span_context().clone();
+    span_context.is_valid().then(|| TraceParent {
+        trace_id: span_context.trace_id().to_bytes(),
+        span_id: span_context.span_id().to_bytes(),
+        sampled: span_context.is_sampled(),
+    })
+}
@@ -0,0 +1,35 @@
+use serde_json::json;
+
+use crate::support::{id_of, TestApp, OWNER_ID};
+
+#[actix_rt::test]
+async fn changes_are_recorded_with_actor_and_request_id() {
+    let app = TestApp::spawn().await;
+    let business_id = id_of(&app.create_business().await);
+    app.patch(&format!("/businesses/{business_id}"), json!({ "category": "cafe" }))
+        .actor(OWNER_ID, "Owner")
+        .header("X-Request-Id", "req-audit-1")
+        .send()
+        .await
+        .assert_status(200);
+
+    let res = app.get(&format!("/audit-logs?entity_type=business&entity_id={business_id}")).admin().send().await;
+    let entries = res.data().as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+
+    let res = app.get(&format!("/audit-logs?entity_id={business_id}&action=update")).admin().send().await;
+    let entries = res.data().as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["actor_id"], json!(OWNER_ID));
+    assert_eq!(entries[0]["request_id"], "req-audit-1");
+    assert_eq!(entries[0]["after"]["category"], "cafe");
+}
+
+#[actix_rt::test]
+async fn audit_logs_are_admin_only() {
+    let app = TestApp::spawn().await;
+    let res = app.get("/audit-logs").actor(OWNER_ID, "Owner").send().await;
+    res.assert_status(403);
+    assert_eq!(res.code(), "admin_required");
+    app.get("/audit-logs").send().await.assert_status(403);
+}
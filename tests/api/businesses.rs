@@ -0,0 +1,151 @@
+use reqwest::Method;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::support::{business_body, id_of, promotion_body, TestApp, OWNER_ID};
+
+#[actix_rt::test]
+async fn created_business_can_be_read_and_listed() {
+    let app = TestApp::spawn().await;
+    let business = app.create_business().await;
+    let id = id_of(&business);
+    assert_eq!(business["version"], 1);
+
+    let res = app.get(&format!("/businesses/{id}")).send().await;
+    assert_eq!(res.data()["business_name"], "Café del Puerto");
+    assert_eq!(res.header("etag"), Some("\"1\""));
+
+    let res = app.get(&format!("/businesses/users/{OWNER_ID}")).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+}
+
+#[actix_rt::test]
+async fn creating_a_business_needs_actor_headers() {
+    let app = TestApp::spawn().await;
+
+    let res = app.post("/businesses", business_body(OWNER_ID)).send().await;
+    res.assert_status(400);
+    assert_eq!(res.code(), "invalid_actor_id");
+}
+
+#[actix_rt::test]
+async fn update_replaces_the_business_and_checks_if_match() {
+    let app = TestApp::spawn().await;
+    let id = id_of(&app.create_business().await);
+    let path = format!("/businesses/{id}");
+    let body = json!({
+        "business_name": "Café del Muelle",
+        "category": "cafe",
+        "website": "https://cafedelmuelle.example"
+    });
+
+    let res = app.put(&path, body.clone()).actor(OWNER_ID, "Owner").header("If-Match", "\"1\"").send().await;
+    assert_eq!(res.data()["business_name"], "Café del Muelle");
+    assert_eq!(res.data()["owner_user_id"], json!(OWNER_ID));
+    assert!(res.data()["description"].is_null());
+    assert_eq!(res.header("etag"), Some("\"2\""));
+
+    let res = app.put(&path, body).actor(OWNER_ID, "Owner").header("If-Match", "\"1\"").send().await;
+    res.assert_status(412);
+    assert_eq!(res.code(), "version_mismatch");
+}
+
+#[actix_rt::test]
+async fn patch_merges_into_the_business() {
+    let app = TestApp::spawn().await;
+    let id = id_of(&app.create_business().await);
+
+    let res = app
+        .patch(&format!("/businesses/{id}"), json!({ "website": null, "category": "cafe" }))
+        .actor(OWNER_ID, "Owner")
+        .send()
+        .await;
+    let business = res.data();
+    assert_eq!(business["category"], "cafe");
+    assert!(business["website"].is_null());
+    assert_eq!(business["description"], "Family restaurant by the harbour");
+}
+
+#[actix_rt::test]
+async fn patch_honours_if_match() {
+    let app = TestApp::spawn().await;
+    let id = id_of(&app.create_business().await);
+    let path = format!("/businesses/{id}");
+
+    let res = app.patch(&path, json!({ "category": "cafe" })).actor(OWNER_ID, "Owner").header("If-Match", "\"1\"").send().await;
+    res.assert_status(200);
+    assert_eq!(res.data()["version"], 2);
+    assert_eq!(res.header("etag"), Some("\"2\""));
+
+    let res = app.patch(&path, json!({ "category": "bar" })).actor(OWNER_ID, "Owner").header("If-Match", "\"1\"").send().await;
+    res.assert_status(412);
+    assert_eq!(res.code(), "version_mismatch");
+    assert_eq!(res.header("etag"), Some("\"2\""));
+
+    let res = app.get(&path).send().await;
+    assert_eq!(res.data()["category"], "cafe");
+}
+
+#[actix_rt::test]
+async fn patch_routes_accept_merge_patch_json() {
+    let app = TestApp::spawn().await;
+    let (business_id, location_id) = app.create_business_with_location().await;
+    let promotion_id = id_of(&app.create_promotion(location_id, promotion_body()).await);
+    let send = |path: String, content_type: &str, body: serde_json::Value| {
+        app.api(Method::PATCH, &path)
+            .actor(OWNER_ID, "Owner")
+            .header("Content-Type", content_type)
+            .body(body.to_string())
+            .send()
+    };
+
+    for (path, field) in [
+        (format!("/businesses/{business_id}"), "description"),
+        (format!("/locations/{location_id}"), "notes"),
+        (format!("/promotions/{promotion_id}"), "description"),
+    ] {
+        let res = send(path.clone(), "application/merge-patch+json", json!({ field: "Patched" })).await;
+        res.assert_status(200);
+        assert_eq!(res.data()[field], "Patched", "{path}");
+
+        let res = send(path.clone(), "application/json", json!({ field: null })).await;
+        res.assert_status(200);
+        assert!(res.data()[field].is_null(), "{path}");
+
+        let res = send(path, "text/plain", json!({ field: "Plain" })).await;
+        res.assert_status(400);
+        assert_eq!(res.code(), "unsupported_content_type");
+    }
+}
+
+#[actix_rt::test]
+async fn delete_refuses_active_promotions_unless_forced_and_restore_undoes_it() {
+    let app = TestApp::spawn().await;
+    let (business_id, location_id) = app.create_business_with_location().await;
+    app.create_promotion(location_id, promotion_body()).await;
+    let path = format!("/businesses/{business_id}");
+
+    let res = app.delete(&path).actor(OWNER_ID, "Owner").send().await;
+    res.assert_status(409);
+    assert_eq!(res.code(), "business_has_active_promotions");
+
+    app.delete(&format!("{path}?force=true")).actor(OWNER_ID, "Owner").send().await.assert_status(204);
+    app.get(&path).send().await.assert_status(404);
+    let res = app.get(&format!("/businesses/users/{OWNER_ID}?include_deleted=true")).admin().send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+
+    let restore = format!("{path}/restore");
+    app.post(&restore, json!({})).actor(OWNER_ID, "Owner").send().await.assert_status(403);
+    let res = app.post(&restore, json!({})).admin().send().await;
+    assert!(res.data()["deleted_at"].is_null());
+    app.get(&format!("/locations/{location_id}")).send().await.assert_status(200);
+}
+
+#[actix_rt::test]
+async fn missing_business_is_not_found() {
+    let app = TestApp::spawn().await;
+    let path = format!("/businesses/{}", Uuid::new_v4());
+
+    app.get(&path).send().await.assert_status(404);
+    app.delete(&path).actor(OWNER_ID, "Owner").send().await.assert_status(404);
+}
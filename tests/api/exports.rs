@@ -0,0 +1,47 @@
+use reqwest::Method;
+
+use crate::support::{promotion_body, TestApp};
+
+#[actix_rt::test]
+async fn exports_are_admin_only() {
+    let app = TestApp::spawn().await;
+
+    for path in ["/exports/businesses.csv", "/exports/promotions.csv", "/exports/locations.geojson"] {
+        app.get(path).send().await.assert_status(403);
+    }
+}
+
+#[actix_rt::test]
+async fn businesses_and_promotions_export_as_csv() {
+    let app = TestApp::spawn().await;
+    let (_, location_id) = app.create_business_with_location().await;
+    app.create_promotion(location_id, promotion_body()).await;
+
+    let res = app.get("/exports/businesses.csv").admin().send().await;
+    res.assert_status(200);
+    assert_eq!(res.header("content-type"), Some("text/csv; charset=utf-8"));
+    assert!(res.header("content-disposition").unwrap_or_default().contains("businesses.csv"));
+    let lines: Vec<&str> = res.text.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", res.text);
+    assert!(lines[0].starts_with("id,business_name,category"));
+    assert!(lines[1].contains("Café del Puerto"));
+
+    let res = app.get("/exports/promotions.csv?status=active").admin().send().await;
+    assert_eq!(res.text.lines().count(), 2, "{}", res.text);
+    let res = app.get("/exports/promotions.csv?status=draft").admin().send().await;
+    assert_eq!(res.text.lines().count(), 1, "{}", res.text);
+}
+
+#[actix_rt::test]
+async fn locations_export_as_geojson() {
+    let app = TestApp::spawn().await;
+    app.create_business_with_location().await;
+
+    let res = app.api(Method::GET, "/exports/locations.geojson").admin().send().await;
+    res.assert_status(200);
+    assert_eq!(res.header("content-type"), Some("application/geo+json"));
+    assert_eq!(res.body["type"], "FeatureCollection");
+    let feature = &res.body["features"][0];
+    assert_eq!(feature["geometry"]["coordinates"][0], -71.6127);
+    assert_eq!(feature["properties"]["location_name"], "Harbour");
+}
@@ -0,0 +1,73 @@
+use reqwest::Method;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+use crate::support::{TestApp, TestOptions};
+
+#[actix_rt::test]
+async fn health_and_liveness_answer_ok() {
+    let app = TestApp::spawn().await;
+
+    for route in ["/health", "/health/live"] {
+        let res = app.get(route).send().await;
+        res.assert_status(200);
+        assert_eq!(res.body["status"], "ok");
+        assert_eq!(res.body["service"], "business-review-service");
+    }
+}
+
+#[actix_rt::test]
+async fn readiness_checks_database_and_migrations() {
+    let app = TestApp::spawn().await;
+
+    let res = app.get("/health/ready").send().await;
+    res.assert_status(200);
+    assert_eq!(res.body["status"], "up");
+    assert_eq!(res.body["checks"]["database"]["status"], "up");
+    assert_eq!(res.body["checks"]["migrations"]["status"], "up");
+    assert!(res.body["checks"].get("stories").is_none());
+}
+
+#[actix_rt::test]
+async fn readiness_reports_missing_migrations() {
+    let app = TestApp::spawn().await;
+    sqlx::query("DELETE FROM schema_migrations WHERE version = (SELECT max(version) FROM schema_migrations)")
+        .execute(app.db().pool())
+        .await
+        .unwrap();
+
+    let res = app.get("/health/ready").send().await;
+    res.assert_status(503);
+    assert_eq!(res.body["checks"]["migrations"]["status"], "down");
+}
+
+#[actix_rt::test]
+async fn readiness_degrades_when_stories_is_down() {
+    let app = TestApp::spawn_with(TestOptions {
+        probe_stories: true,
+        ..TestOptions::default()
+    })
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/health"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&app.stories)
+        .await;
+
+    let res = app.get("/health/ready").send().await;
+    res.assert_status(200);
+    assert_eq!(res.body["status"], "degraded");
+    assert_eq!(res.body["checks"]["stories"]["status"], "down");
+}
+
+#[actix_rt::test]
+async fn metrics_are_served_outside_the_api_scope() {
+    let app = TestApp::spawn().await;
+    app.get("/health").send().await.assert_status(200);
+
+    let res = app.request(Method::GET, "/metrics").send().await;
+    res.assert_status(200);
+    assert!(res.header("content-type").unwrap_or_default().starts_with("text/plain"));
+    assert!(res.text.contains("business_review_http_requests_total"));
+    assert!(res.text.contains("business_review_db_pool_max_size 4"));
+}
@@ -0,0 +1,50 @@
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::support::{TestApp, OWNER_ID};
+
+#[actix_rt::test]
+async fn admins_are_added_listed_and_removed() {
+    let app = TestApp::spawn().await;
+    let (_, location_id) = app.create_business_with_location().await;
+    let path = format!("/locations/{location_id}/admins");
+    let manager = Uuid::new_v4();
+
+    let res = app
+        .post(
+            &path,
+            json!({ "user_id": manager, "user_email": "manager@example.com", "user_username": "manager", "role": "manager" }),
+        )
+        .actor(OWNER_ID, "Owner")
+        .send()
+        .await;
+    res.assert_status(201);
+    assert_eq!(res.data()["role"], "manager");
+    assert_eq!(res.data()["granted_by"], json!(OWNER_ID));
+
+    let res = app.get(&path).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+
+    let admin = format!("{path}/{manager}");
+    app.delete(&admin).actor(OWNER_ID, "Owner").send().await.assert_status(204);
+    let res = app.get(&path).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(0));
+    // Removing is idempotent
+    app.delete(&admin).actor(OWNER_ID, "Owner").send().await.assert_status(204);
+}
+
+#[actix_rt::test]
+async fn admin_with_invalid_email_is_rejected() {
+    let app = TestApp::spawn().await;
+    let (_, location_id) = app.create_business_with_location().await;
+
+    let res = app
+        .post(
+            &format!("/locations/{location_id}/admins"),
+            json!({ "user_id": Uuid::new_v4(), "user_email": "nope", "user_username": "manager", "role": "staff" }),
+        )
+        .actor(OWNER_ID, "Owner")
+        .send()
+        .await;
+    res.assert_status(422);
+}
@@ -0,0 +1,137 @@
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::support::{id_of, location_body, TestApp, OWNER_ID};
+
+#[actix_rt::test]
+async fn created_location_can_be_read_and_listed() {
+    let app = TestApp::spawn().await;
+    let (business_id, location_id) = app.create_business_with_location().await;
+
+    let res = app.get(&format!("/locations/{location_id}")).send().await;
+    assert_eq!(res.data()["location_name"], "Harbour");
+    assert_eq!(res.data()["business_id"], json!(business_id));
+    assert_eq!(res.header("etag"), Some("\"1\""));
+
+    let res = app.get(&format!("/businesses/{business_id}/locations")).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+}
+
+#[actix_rt::test]
+async fn location_of_missing_business_is_rejected() {
+    let app = TestApp::spawn().await;
+
+    let res = app
+        .post(&format!("/businesses/{}/locations", Uuid::new_v4()), location_body("Harbour"))
+        .actor(OWNER_ID, "Owner")
+        .send()
+        .await;
+    assert!(res.status.is_client_error(), "{}: {}", res.status, res.text);
+}
+
+#[actix_rt::test]
+async fn update_and_patch_change_the_location() {
+    let app = TestApp::spawn().await;
+    let (_, location_id) = app.create_business_with_location().await;
+    let path = format!("/locations/{location_id}");
+
+    let mut body = location_body("Harbour front");
+    body["is_active"] = json!(true);
+    let res = app.put(&path, body).actor(OWNER_ID, "Owner").header("If-Match", "\"1\"").send().await;
+    assert_eq!(res.data()["location_name"], "Harbour front");
+    assert_eq!(res.data()["version"], 2);
+
+    let res = app
+        .patch(&path, json!({ "phone": "+56 32 123 4567", "city": null }))
+        .actor(OWNER_ID, "Owner")
+        .header("If-Match", "\"2\"")
+        .send()
+        .await;
+    assert_eq!(res.data()["phone"], "+56 32 123 4567");
+    assert!(res.data()["city"].is_null());
+    assert_eq!(res.data()["location_name"], "Harbour front");
+
+    let res = app.patch(&path, json!({ "location_name": "X" })).actor(OWNER_ID, "Owner").send().await;
+    res.assert_status(422);
+}
+
+#[actix_rt::test]
+async fn deleted_location_is_hidden_until_restored() {
+    let app = TestApp::spawn().await;
+    let (business_id, location_id) = app.create_business_with_location().await;
+    let path = format!("/locations/{location_id}");
+
+    app.delete(&path).actor(OWNER_ID, "Owner").send().await.assert_status(204);
+    app.get(&path).send().await.assert_status(404);
+    let listed = format!("/businesses/{business_id}/locations?include_deleted=true");
+    let res = app.get(&listed).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(0));
+    let res = app.get(&listed).admin().send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+
+    let restore = format!("{path}/restore");
+    app.post(&restore, json!({})).actor(OWNER_ID, "Owner").send().await.assert_status(403);
+    let res = app.post(&restore, json!({})).admin().send().await;
+    assert_eq!(id_of(res.data()), location_id);
+    app.get(&path).send().await.assert_status(200);
+}
+
+#[actix_rt::test]
+async fn csv_import_creates_and_then_updates_by_external_ref() {
+    let app = TestApp::spawn().await;
+    let business_id = id_of(&app.create_business().await);
+    let path = format!("/businesses/{business_id}/locations/import");
+    let csv = "external_ref,location_name,formatted_address,city,latitude,longitude,is_primary\n\
+               vina-1,Viña Centro,Av. Valparaíso 100,Viña del Mar,-33.02,-71.55,true\n\
+               valpo-1,Cerro Alegre,Lautaro Rosas 200,Valparaíso,-33.04,-71.62,false\n";
+    let import = |query: &str| {
+        app.api(reqwest::Method::POST, &format!("{path}{query}"))
+            .actor(OWNER_ID, "Owner")
+            .header("Content-Type", "text/csv")
+            .body(csv)
+            .send()
+    };
+
+    let res = import("?dry_run=true").await;
+    res.assert_status(200);
+    assert_eq!(res.data()["created"], 2);
+    let res = app.get(&format!("/businesses/{business_id}/locations")).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(0));
+
+    let res = import("").await;
+    res.assert_status(201);
+    assert_eq!(res.data()["created"], 2);
+
+    let res = import("").await;
+    assert_eq!(res.data()["created"], 0);
+    assert_eq!(res.data()["updated"], 2);
+}
+
+#[actix_rt::test]
+async fn import_with_invalid_rows_writes_nothing() {
+    let app = TestApp::spawn().await;
+    let business_id = id_of(&app.create_business().await);
+    let path = format!("/businesses/{business_id}/locations/import");
+    let rows = json!([
+        { "location_name": "Viña Centro", "formatted_address": "Av. Valparaíso 100", "is_primary": false },
+        { "location_name": "X", "formatted_address": "Lautaro Rosas 200", "is_primary": false }
+    ]);
+
+    let res = app.post(&path, rows.clone()).actor(OWNER_ID, "Owner").send().await;
+    res.assert_status(422);
+    assert_eq!(res.code(), "invalid_import_rows");
+    assert_eq!(res.body["details"][0]["row"], 2);
+
+    let res = app.post(&format!("{path}?dry_run=true"), rows).actor(OWNER_ID, "Owner").send().await;
+    assert_eq!(res.data()["errors"].as_array().map(Vec::len), Some(1));
+
+    let res = app
+        .api(reqwest::Method::POST, &path)
+        .actor(OWNER_ID, "Owner")
+        .header("Content-Type", "text/plain")
+        .body("name\nx")
+        .send()
+        .await;
+    res.assert_status(400);
+    assert_eq!(res.code(), "unsupported_import_format");
+}
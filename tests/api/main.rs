@@ -0,0 +1,20 @@
+//! Route tests, one module per area of `handlers.rs`. They need a Postgres
+//! server; see `support` for how to point them at one. `storage` runs the
+//! main flows on both storage backends.
+
+mod support;
+
+mod audit_logs;
+mod businesses;
+mod exports;
+mod health;
+mod location_admins;
+mod locations;
+mod moderation;
+mod promotions;
+mod rate_limits;
+mod registrations;
+mod reviews;
+mod storage;
+mod templates;
+mod webhooks;
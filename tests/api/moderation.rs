@@ -0,0 +1,73 @@
+use serde_json::json;
+
+use crate::support::{id_of, promotion_body, TestApp, TestOptions, OWNER_ID};
+
+async fn moderated_app() -> TestApp {
+    TestApp::spawn_with(TestOptions {
+        moderation: true,
+        ..TestOptions::default()
+    })
+    .await
+}
+
+#[actix_rt::test]
+async fn promotions_of_untrusted_businesses_wait_for_review() {
+    let app = moderated_app().await;
+    let (_, location_id) = app.create_business_with_location().await;
+    let promotion = app.create_promotion(location_id, promotion_body()).await;
+    assert_eq!(promotion["status"], "pending_review");
+    let id = id_of(&promotion);
+
+    app.get("/promotion-reviews/pending").send().await.assert_status(403);
+    let res = app.get("/promotion-reviews/pending").admin().send().await;
+    let pending = res.data().as_array().unwrap();
+    assert_eq!(pending.len(), 1);
+
+    let path = format!("/promotion-reviews/{id}/action");
+    let res = app.post(&path, json!({ "action": "comment", "notes": "Checking the terms" })).admin().send().await;
+    assert_eq!(res.data()["status"], "pending_review");
+
+    let res = app.post(&path, json!({ "action": "approve" })).admin().send().await;
+    assert_eq!(res.data()["status"], "active");
+    assert_eq!(app.stories_shares(1).await.len(), 1);
+
+    let res = app.get(&format!("/promotion-reviews/{id}")).admin().send().await;
+    assert_eq!(res.data()["promotion"]["status"], "active");
+    assert_eq!(res.data()["history"].as_array().map(Vec::len), Some(2));
+
+    let res = app.post(&path, json!({ "action": "approve" })).admin().send().await;
+    res.assert_status(409);
+}
+
+#[actix_rt::test]
+async fn rejected_promotion_needs_a_reason() {
+    let app = moderated_app().await;
+    let (_, location_id) = app.create_business_with_location().await;
+    let id = id_of(&app.create_promotion(location_id, promotion_body()).await);
+    let path = format!("/promotion-reviews/{id}/action");
+
+    app.post(&path, json!({ "action": "reject" })).admin().send().await.assert_status(422);
+    app.post(&path, json!({ "action": "suspend" })).admin().send().await.assert_status(422);
+    app.post(&path, json!({ "action": "approve" })).actor(OWNER_ID, "Owner").send().await.assert_status(403);
+
+    let res = app
+        .post(&path, json!({ "action": "reject", "rejection_reason": "Misleading discount" }))
+        .admin()
+        .send()
+        .await;
+    assert_eq!(res.data()["status"], "rejected");
+}
+
+#[actix_rt::test]
+async fn trusted_businesses_skip_moderation() {
+    let app = moderated_app().await;
+    let (business_id, location_id) = app.create_business_with_location().await;
+    let path = format!("/businesses/{business_id}/promotion-trust");
+
+    app.put(&path, json!({ "trusted": true })).actor(OWNER_ID, "Owner").send().await.assert_status(403);
+    let res = app.put(&path, json!({ "trusted": true })).admin().send().await;
+    assert_eq!(res.data()["promotions_trusted"], true);
+
+    let promotion = app.create_promotion(location_id, promotion_body()).await;
+    assert_eq!(promotion["status"], "active");
+}
@@ -0,0 +1,198 @@
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::support::{id_of, promotion_body, TestApp, OWNER_ID};
+
+#[actix_rt::test]
+async fn active_promotion_is_created_and_shared_to_stories() {
+    let app = TestApp::spawn().await;
+    let (business_id, location_id) = app.create_business_with_location().await;
+
+    let promotion = app.create_promotion(location_id, promotion_body()).await;
+    assert_eq!(promotion["status"], "active");
+    assert_eq!(promotion["target_scope"], "location");
+    let id = id_of(&promotion);
+
+    let shares = app.stories_shares(1).await;
+    assert_eq!(shares.len(), 1);
+    assert_eq!(shares[0]["promotion_id"], json!(id));
+    assert_eq!(shares[0]["author_id"], json!(OWNER_ID));
+
+    let res = app.get(&format!("/promotions/{id}")).send().await;
+    assert_eq!(res.data()["title"], "Two for one coffee");
+    assert_eq!(res.header("etag"), Some("\"1\""));
+
+    let res = app.get(&format!("/locations/{location_id}/promotions")).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+    let res = app.get(&format!("/businesses/{business_id}/promotions")).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+}
+
+#[actix_rt::test]
+async fn scheduled_promotion_is_not_shared() {
+    let app = TestApp::spawn().await;
+    let (_, location_id) = app.create_business_with_location().await;
+    let mut body = promotion_body();
+    let starts_at = chrono::Utc::now() + chrono::Duration::days(1);
+    body["starts_at"] = json!(starts_at);
+    body["ends_at"] = json!(starts_at + chrono::Duration::days(1));
+
+    let promotion = app.create_promotion(location_id, body).await;
+    assert_eq!(promotion["status"], "scheduled");
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert!(app.stories_shares(0).await.is_empty());
+}
+
+#[actix_rt::test]
+async fn business_rules_are_checked_on_create() {
+    let app = TestApp::spawn().await;
+    let (_, location_id) = app.create_business_with_location().await;
+    let path = format!("/locations/{location_id}/promotions");
+
+    let mut body = promotion_body();
+    body["ends_at"] = body["starts_at"].clone();
+    let res = app.post(&path, body).actor(OWNER_ID, "Owner").send().await;
+    res.assert_status(422);
+
+    let mut body = promotion_body();
+    body["promotion_type"] = json!("contest");
+    body["discount_percent"] = json!(null);
+    let res = app.post(&path, body).actor(OWNER_ID, "Owner").send().await;
+    res.assert_status(422);
+    assert_eq!(res.code(), "contest_requires_prize");
+
+    let res = app
+        .post(&format!("/locations/{}/promotions", Uuid::new_v4()), promotion_body())
+        .actor(OWNER_ID, "Owner")
+        .send()
+        .await;
+    res.assert_status(404);
+}
+
+#[actix_rt::test]
+async fn business_wide_promotion_applies_to_every_location() {
+    let app = TestApp::spawn().await;
+    let (business_id, first) = app.create_business_with_location().await;
+    let second = id_of(&app.create_location(business_id, "Hills").await);
+
+    let res = app
+        .post(&format!("/businesses/{business_id}/promotions"), promotion_body())
+        .actor(OWNER_ID, "Owner")
+        .send()
+        .await;
+    res.assert_status(201);
+    assert_eq!(res.data()["target_scope"], "business");
+    let id = id_of(res.data());
+
+    let res = app.get(&format!("/promotions/{id}/locations")).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(2));
+    for location_id in [first, second] {
+        let res = app.get(&format!("/locations/{location_id}/promotions")).send().await;
+        assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+    }
+}
+
+#[actix_rt::test]
+async fn update_and_patch_change_the_promotion() {
+    let app = TestApp::spawn().await;
+    let (_, location_id) = app.create_business_with_location().await;
+    let promotion = app.create_promotion(location_id, promotion_body()).await;
+    let path = format!("/promotions/{}", id_of(&promotion));
+
+    let mut body = promotion_body();
+    body["title"] = json!("Three for two coffee");
+    body["status"] = json!("draft");
+    let res = app.put(&path, body).actor(OWNER_ID, "Owner").header("If-Match", "\"1\"").send().await;
+    assert_eq!(res.data()["title"], "Three for two coffee");
+    assert_eq!(res.data()["status"], "draft");
+
+    let res = app.patch(&path, json!({ "status": "active" })).actor(OWNER_ID, "Owner").send().await;
+    assert_eq!(res.data()["status"], "active");
+    assert_eq!(res.data()["title"], "Three for two coffee");
+    assert_eq!(app.stories_shares(2).await.len(), 2);
+
+    let res = app.patch(&path, json!({ "title": "New" })).actor(OWNER_ID, "Owner").header("If-Match", "\"1\"").send().await;
+    res.assert_status(412);
+}
+
+#[actix_rt::test]
+async fn deleted_promotion_is_hidden_until_restored() {
+    let app = TestApp::spawn().await;
+    let (_, location_id) = app.create_business_with_location().await;
+    let id = id_of(&app.create_promotion(location_id, promotion_body()).await);
+    let path = format!("/promotions/{id}");
+
+    app.delete(&path).actor(OWNER_ID, "Owner").send().await.assert_status(204);
+    app.get(&path).send().await.assert_status(404);
+
+    let restore = format!("{path}/restore");
+    app.post(&restore, json!({})).actor(OWNER_ID, "Owner").send().await.assert_status(403);
+    let res = app.post(&restore, json!({})).admin().send().await;
+    assert_eq!(id_of(res.data()), id);
+    app.get(&path).send().await.assert_status(200);
+}
+
+#[actix_rt::test]
+async fn clone_copies_the_promotion_to_other_locations() {
+    let app = TestApp::spawn().await;
+    let (business_id, location_id) = app.create_business_with_location().await;
+    let other = id_of(&app.create_location(business_id, "Hills").await);
+    let id = id_of(&app.create_promotion(location_id, promotion_body()).await);
+
+    let res = app
+        .post(&format!("/promotions/{id}/clone"), json!({ "location_ids": [other], "title": "Hills coffee deal" }))
+        .actor(OWNER_ID, "Owner")
+        .send()
+        .await;
+    res.assert_status(201);
+    let clones = res.data().as_array().unwrap();
+    assert_eq!(clones.len(), 1);
+    assert_eq!(clones[0]["location_id"], json!(other));
+    assert_eq!(clones[0]["title"], "Hills coffee deal");
+    assert_eq!(clones[0]["metadata"]["cloned_from"], json!(id));
+}
+
+#[actix_rt::test]
+async fn clone_skips_repeated_locations() {
+    let app = TestApp::spawn().await;
+    let (business_id, location_id) = app.create_business_with_location().await;
+    let hills = id_of(&app.create_location(business_id, "Hills").await);
+    let harbour = id_of(&app.create_location(business_id, "Harbour").await);
+    let id = id_of(&app.create_promotion(location_id, promotion_body()).await);
+
+    let res = app
+        .post(&format!("/promotions/{id}/clone"), json!({ "location_ids": [hills, harbour, hills] }))
+        .actor(OWNER_ID, "Owner")
+        .send()
+        .await;
+    res.assert_status(201);
+    let clones = res.data().as_array().unwrap();
+    assert_eq!(clones.len(), 2);
+    assert_eq!(clones[0]["location_id"], json!(hills));
+    assert_eq!(clones[1]["location_id"], json!(harbour));
+}
+
+#[actix_rt::test]
+async fn claims_count_against_the_limit() {
+    let app = TestApp::spawn().await;
+    let (_, location_id) = app.create_business_with_location().await;
+    let mut body = promotion_body();
+    body["max_claims"] = json!(1);
+    let id = id_of(&app.create_promotion(location_id, body).await);
+    let path = format!("/promotions/{id}/claims");
+    let claimant = Uuid::new_v4();
+
+    let res = app.post(&path, json!({ "location_id": location_id })).actor(claimant, "Visitor").send().await;
+    res.assert_status(201);
+    assert_eq!(res.data()["total_claims"], 1);
+    assert_eq!(res.data()["remaining_claims"], 0);
+
+    let res = app.post(&path, json!({ "location_id": location_id })).actor(claimant, "Visitor").send().await;
+    res.assert_status(409);
+
+    let res = app.post(&path, json!({ "location_id": Uuid::new_v4() })).actor(claimant, "Visitor").send().await;
+    res.assert_status(422);
+
+    let res = app.get(&format!("/promotions/{id}/locations")).send().await;
+    assert_eq!(res.data()[0]["total_claims"], 1);
+}
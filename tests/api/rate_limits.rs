@@ -0,0 +1,47 @@
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::support::{TestApp, TestOptions};
+
+#[actix_rt::test]
+async fn writes_are_limited_per_actor() {
+    let app = TestApp::spawn_with(TestOptions {
+        rate_limit: true,
+        behind_gateway: true,
+        ..TestOptions::default()
+    })
+    .await;
+    let actor = Uuid::new_v4();
+
+    for _ in 0..20 {
+        let res = app.post("/registrations", json!({})).actor(actor, "Burst").send().await;
+        assert_ne!(res.status, 429);
+        assert!(res.header("x-ratelimit-remaining").is_some());
+    }
+    let res = app.post("/registrations", json!({})).actor(actor, "Burst").send().await;
+    res.assert_status(429);
+    assert_eq!(res.code(), "rate_limited");
+    assert!(res.header("retry-after").is_some());
+
+    // Reads and other callers have buckets of their own
+    app.get("/reviews/stats").actor(actor, "Burst").send().await.assert_status(200);
+    let res = app.post("/registrations", json!({})).actor(Uuid::new_v4(), "Other").send().await;
+    assert_ne!(res.status, 429);
+}
+
+#[actix_rt::test]
+async fn actor_ids_from_untrusted_peers_share_the_ip_bucket() {
+    let app = TestApp::spawn_with(TestOptions {
+        rate_limit: true,
+        ..TestOptions::default()
+    })
+    .await;
+
+    for _ in 0..20 {
+        let res = app.post("/registrations", json!({})).actor(Uuid::new_v4(), "Spoofed").send().await;
+        assert_ne!(res.status, 429);
+    }
+    let res = app.post("/registrations", json!({})).actor(Uuid::new_v4(), "Spoofed").send().await;
+    res.assert_status(429);
+    assert_eq!(res.code(), "rate_limited");
+}
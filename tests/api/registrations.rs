@@ -0,0 +1,58 @@
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::support::{id_of, registration_body, TestApp};
+
+#[actix_rt::test]
+async fn submitted_registration_can_be_read_back() {
+    let app = TestApp::spawn().await;
+    let user_id = Uuid::new_v4();
+
+    let res = app.post("/registrations", registration_body(user_id)).send().await;
+    res.assert_status(201);
+    let registration = res.data().clone();
+    assert_eq!(registration["status"], "pending");
+    assert_eq!(registration["document_urls"], json!(["https://docs.example/rut.pdf"]));
+    let id = id_of(&registration);
+
+    let res = app.get(&format!("/registrations/{id}")).send().await;
+    assert_eq!(id_of(res.data()), id);
+
+    let res = app.get(&format!("/registrations/users/{user_id}/latest")).send().await;
+    assert_eq!(id_of(res.data()), id);
+
+    let res = app.get(&format!("/registrations/users/{user_id}")).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+}
+
+#[actix_rt::test]
+async fn invalid_registration_is_rejected_field_by_field() {
+    let app = TestApp::spawn().await;
+    let mut body = registration_body(Uuid::new_v4());
+    body["owner_email"] = json!("not-an-email");
+    body["document_urls"] = json!([]);
+
+    let res = app.post("/registrations", body).send().await;
+    res.assert_status(422);
+    assert_eq!(res.code(), "validation_failed");
+    let fields: Vec<&str> = res.body["details"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|field| field["field"].as_str())
+        .collect();
+    assert!(fields.contains(&"owner_email") && fields.contains(&"document_urls"), "{fields:?}");
+}
+
+#[actix_rt::test]
+async fn missing_registration_is_not_found() {
+    let app = TestApp::spawn().await;
+
+    let res = app.get(&format!("/registrations/{}", Uuid::new_v4())).send().await;
+    res.assert_status(404);
+    let res = app.get(&format!("/registrations/users/{}/latest", Uuid::new_v4())).send().await;
+    res.assert_status(404);
+    let res = app.get("/registrations/not-a-uuid").send().await;
+    res.assert_status(400);
+    assert_eq!(res.code(), "invalid_path_parameter");
+}
@@ -0,0 +1,133 @@
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::support::{business_body, id_of, promotion_body, registration_body, TestApp, ADMIN_ID, OWNER_ID};
+
+async fn submit(app: &TestApp) -> Uuid {
+    let res = app.post("/registrations", registration_body(Uuid::new_v4())).send().await;
+    id_of(res.data())
+}
+
+#[actix_rt::test]
+async fn pending_registrations_are_listed_and_counted() {
+    let app = TestApp::spawn().await;
+    let first = submit(&app).await;
+    submit(&app).await;
+
+    let res = app.get("/reviews/pending?limit=1").send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+
+    let res = app.get(&format!("/reviews/{first}")).send().await;
+    assert_eq!(id_of(res.data()), first);
+
+    let res = app.get("/reviews/stats").send().await;
+    assert_eq!(res.data()["pending"], 2);
+}
+
+#[actix_rt::test]
+async fn review_actions_move_the_registration_through_its_states() {
+    let app = TestApp::spawn().await;
+    let id = submit(&app).await;
+    let action = |body| app.post(&format!("/reviews/{id}/action"), body).send();
+
+    let res = action(json!({ "action": "request_more_info", "notes": "Send the RUT certificate" })).await;
+    assert_eq!(res.data()["status"], "under_review");
+    assert_eq!(res.data()["reviewer_notes"], "Send the RUT certificate");
+
+    let res = action(json!({ "action": "comment", "notes": "Certificate received" })).await;
+    assert_eq!(res.data()["status"], "under_review");
+
+    let res = action(json!({ "action": "approve", "reviewer_id": ADMIN_ID, "reviewer_name": "Reviewer" })).await;
+    assert_eq!(res.data()["status"], "approved");
+    assert_eq!(res.data()["reviewer_id"], json!(ADMIN_ID));
+
+    let res = app.get("/reviews/stats").send().await;
+    assert_eq!(res.data()["pending"], 0);
+}
+
+#[actix_rt::test]
+async fn rejecting_requires_a_reason() {
+    let app = TestApp::spawn().await;
+    let id = submit(&app).await;
+
+    let res = app.post(&format!("/reviews/{id}/action"), json!({ "action": "reject" })).send().await;
+    res.assert_status(422);
+
+    let res = app
+        .post(&format!("/reviews/{id}/action"), json!({ "action": "reject", "rejection_reason": "Unreadable documents" }))
+        .send()
+        .await;
+    assert_eq!(res.data()["status"], "rejected");
+    assert_eq!(res.data()["rejection_reason"], "Unreadable documents");
+}
+
+#[actix_rt::test]
+async fn suspension_and_resume_are_recorded() {
+    let app = TestApp::spawn().await;
+    let id = submit(&app).await;
+    let action = |body| app.post(&format!("/reviews/{id}/action"), body).send();
+    action(json!({ "action": "approve" })).await.assert_status(200);
+
+    let res = action(json!({ "action": "suspend", "notes": "Fraud report" })).await;
+    assert_eq!(res.data()["status"], "suspended");
+    let res = action(json!({ "action": "suspend" })).await;
+    res.assert_status(409);
+
+    let res = action(json!({ "action": "resume" })).await;
+    assert_eq!(res.data()["status"], "approved");
+
+    let path = format!("/reviews/{id}/suspensions");
+    app.get(&path).send().await.assert_status(403);
+    let res = app.get(&path).admin().send().await;
+    let suspensions = res.data().as_array().unwrap();
+    assert_eq!(suspensions.len(), 1);
+    assert!(!suspensions[0]["resumed_at"].is_null(), "{}", suspensions[0]);
+}
+
+#[actix_rt::test]
+async fn suspension_cascade_is_audited() {
+    let app = TestApp::spawn().await;
+    let res = app.post("/registrations", registration_body(OWNER_ID)).send().await;
+    let id = id_of(res.data());
+    let action = |body| app.post(&format!("/reviews/{id}/action"), body).send();
+    action(json!({ "action": "approve" })).await.assert_status(200);
+
+    let mut body = business_body(OWNER_ID);
+    body["registration_id"] = json!(id);
+    let res = app.post("/businesses", body).actor(OWNER_ID, "Owner").send().await;
+    let business_id = id_of(res.data());
+    let location_id = id_of(&app.create_location(business_id, "Harbour").await);
+    let promotion_id = id_of(&app.create_promotion(location_id, promotion_body()).await);
+
+    let app = &app;
+    let updates = |entity_type: &'static str, entity_id: Uuid| async move {
+        let path = format!("/audit-logs?entity_type={entity_type}&entity_id={entity_id}&action=update");
+        app.get(&path).admin().send().await.data().as_array().unwrap().clone()
+    };
+
+    action(json!({ "action": "suspend" })).await.assert_status(200);
+    let entries = updates("location", location_id).await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["after"]["is_active"], false);
+    let entries = updates("promotion", promotion_id).await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["before"]["status"], "active");
+    assert_eq!(entries[0]["after"]["status"], "paused");
+
+    action(json!({ "action": "resume" })).await.assert_status(200);
+    assert_eq!(updates("location", location_id).await.len(), 2);
+    let entries = updates("promotion", promotion_id).await;
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().any(|entry| entry["after"]["status"] == "active"), "{entries:?}");
+}
+
+#[actix_rt::test]
+async fn review_action_on_missing_registration_is_not_found() {
+    let app = TestApp::spawn().await;
+
+    let res = app
+        .post(&format!("/reviews/{}/action", Uuid::new_v4()), json!({ "action": "approve" }))
+        .send()
+        .await;
+    res.assert_status(404);
+}
@@ -0,0 +1,216 @@
+use serde_json::json;
+use uuid::Uuid;
+
+use crazytrip_business_review_service::events::publisher::InMemoryEventPublisher;
+
+use crate::support::{id_of, promotion_body, registration_body, TestApp, TestOptions, OWNER_ID};
+
+/// Run each behaviour once on Postgres and once on the memory backend, so
+/// both keep the same semantics
+macro_rules! on_both_backends {
+    ($($behaviour:ident),* $(,)?) => {
+        mod postgres {
+            $(
+                #[actix_rt::test]
+                async fn $behaviour() {
+                    super::$behaviour(super::spawn(false).await).await;
+                }
+            )*
+        }
+
+        mod memory {
+            $(
+                #[actix_rt::test]
+                async fn $behaviour() {
+                    super::$behaviour(super::spawn(true).await).await;
+                }
+            )*
+        }
+    };
+}
+
+on_both_backends!(
+    registrations_go_through_review,
+    business_versions_and_delete_cascade_hold,
+    promotion_targets_are_checked,
+    location_admins_are_kept,
+    claims_count_against_the_limit,
+    writes_are_audited,
+    events_are_relayed_in_commit_order,
+);
+
+async fn spawn(in_memory: bool) -> TestApp {
+    TestApp::spawn_with(TestOptions {
+        in_memory,
+        ..TestOptions::default()
+    })
+    .await
+}
+
+async fn registrations_go_through_review(app: TestApp) {
+    let user_id = Uuid::new_v4();
+    let res = app.post("/registrations", registration_body(user_id)).send().await;
+    res.assert_status(201);
+    let id = id_of(res.data());
+
+    let res = app.get("/reviews/pending").send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+
+    let res = app
+        .post(&format!("/reviews/{id}/action"), json!({ "action": "request_more_info", "notes": "Send the RUT" }))
+        .send()
+        .await;
+    assert_eq!(res.data()["status"], "under_review");
+    let res = app.post(&format!("/reviews/{id}/action"), json!({ "action": "approve" })).send().await;
+    assert_eq!(res.data()["status"], "approved");
+    assert_eq!(res.data()["reviewer_notes"], "Send the RUT");
+
+    let res = app.get(&format!("/registrations/users/{user_id}/latest")).send().await;
+    assert_eq!(id_of(res.data()), id);
+    let res = app.get("/reviews/stats").send().await;
+    assert_eq!(res.data()["pending"], 0);
+    assert_eq!(res.data()["approved_today"], 1);
+}
+
+async fn business_versions_and_delete_cascade_hold(app: TestApp) {
+    let (business_id, location_id) = app.create_business_with_location().await;
+    let promotion_id = id_of(&app.create_promotion(location_id, promotion_body()).await);
+    let path = format!("/businesses/{business_id}");
+
+    let res = app
+        .patch(&path, json!({ "category": "cafe" }))
+        .actor(OWNER_ID, "Owner")
+        .header("If-Match", "\"1\"")
+        .send()
+        .await;
+    assert_eq!(res.header("etag"), Some("\"2\""));
+    let res = app.patch(&path, json!({ "category": "bar" })).actor(OWNER_ID, "Owner").header("If-Match", "\"1\"").send().await;
+    res.assert_status(412);
+
+    let res = app.delete(&path).actor(OWNER_ID, "Owner").send().await;
+    res.assert_status(409);
+    assert_eq!(res.code(), "business_has_active_promotions");
+    app.delete(&format!("{path}?force=true")).actor(OWNER_ID, "Owner").send().await.assert_status(204);
+    app.get(&format!("/locations/{location_id}")).send().await.assert_status(404);
+    app.get(&format!("/promotions/{promotion_id}")).send().await.assert_status(404);
+
+    let res = app.post(&format!("/locations/{location_id}/restore"), json!({})).admin().send().await;
+    res.assert_status(409);
+    assert_eq!(res.code(), "parent_deleted");
+    app.post(&format!("{path}/restore"), json!({})).admin().send().await.assert_status(200);
+    app.get(&format!("/locations/{location_id}")).send().await.assert_status(200);
+    app.get(&format!("/promotions/{promotion_id}")).send().await.assert_status(200);
+}
+
+async fn promotion_targets_are_checked(app: TestApp) {
+    let (business_id, first) = app.create_business_with_location().await;
+    let second = id_of(&app.create_location(business_id, "Hills").await);
+    let (_, foreign) = app.create_business_with_location().await;
+
+    let mut body = promotion_body();
+    body["location_ids"] = json!([foreign]);
+    let res = app.post(&format!("/locations/{first}/promotions"), body).actor(OWNER_ID, "Owner").send().await;
+    res.assert_status(422);
+    assert_eq!(res.body["details"][0]["code"], "not_in_business");
+
+    let res = app
+        .post(&format!("/businesses/{business_id}/promotions"), promotion_body())
+        .actor(OWNER_ID, "Owner")
+        .send()
+        .await;
+    let id = id_of(res.data());
+    let res = app.get(&format!("/promotions/{id}/locations")).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(2));
+
+    app.delete(&format!("/locations/{second}")).actor(OWNER_ID, "Owner").send().await.assert_status(204);
+    let res = app.get(&format!("/promotions/{id}/locations")).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+    let res = app.get(&format!("/locations/{first}/promotions")).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+}
+
+async fn location_admins_are_kept(app: TestApp) {
+    let (_, location_id) = app.create_business_with_location().await;
+    let path = format!("/locations/{location_id}/admins");
+    let manager = Uuid::new_v4();
+
+    app.post(
+        &path,
+        json!({ "user_id": manager, "user_email": "manager@example.com", "user_username": "manager", "role": "manager" }),
+    )
+    .actor(OWNER_ID, "Owner")
+    .send()
+    .await
+    .assert_status(201);
+    let res = app.get(&path).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+
+    app.delete(&format!("{path}/{manager}")).actor(OWNER_ID, "Owner").send().await.assert_status(204);
+    let res = app.get(&path).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(0));
+}
+
+async fn claims_count_against_the_limit(app: TestApp) {
+    let (_, location_id) = app.create_business_with_location().await;
+    let mut body = promotion_body();
+    body["max_claims"] = json!(1);
+    let id = id_of(&app.create_promotion(location_id, body).await);
+    let path = format!("/promotions/{id}/claims");
+    let claim = |location_id: Uuid| app.post(&path, json!({ "location_id": location_id })).actor(Uuid::new_v4(), "Visitor").send();
+
+    let res = claim(location_id).await;
+    res.assert_status(201);
+    assert_eq!(res.data()["total_claims"], 1);
+    assert_eq!(res.data()["remaining_claims"], 0);
+    let res = claim(location_id).await;
+    res.assert_status(409);
+    assert_eq!(res.code(), "promotion_claim_limit_reached");
+    let res = claim(Uuid::new_v4()).await;
+    assert_eq!(res.body["details"][0]["code"], "not_targeted");
+
+    let res = app.get(&format!("/promotions/{id}/locations")).send().await;
+    assert_eq!(res.data()[0]["total_claims"], 1);
+}
+
+async fn writes_are_audited(app: TestApp) {
+    let (business_id, location_id) = app.create_business_with_location().await;
+    app.delete(&format!("/businesses/{business_id}")).actor(OWNER_ID, "Owner").send().await.assert_status(204);
+
+    let res = app.get(&format!("/audit-logs?entity_type=location&entity_id={location_id}")).admin().send().await;
+    let entries = res.data().as_array().unwrap();
+    let actions: Vec<&str> = entries.iter().filter_map(|entry| entry["action"].as_str()).collect();
+    assert_eq!(actions, ["delete", "create"]);
+    assert_eq!(entries[0]["actor_id"], json!(OWNER_ID));
+    assert_eq!(entries[0]["before"]["deleted_at"], json!(null));
+    assert!(!entries[0]["after"]["deleted_at"].is_null(), "{}", entries[0]);
+
+    let res = app.get(&format!("/audit-logs?entity_id={business_id}&action=delete")).admin().send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+}
+
+async fn events_are_relayed_in_commit_order(app: TestApp) {
+    let (_, location_id) = app.create_business_with_location().await;
+    app.delete(&format!("/locations/{location_id}")).actor(OWNER_ID, "Owner").send().await.assert_status(204);
+
+    let publisher = InMemoryEventPublisher::new();
+    let outbox = &app.repositories.outbox;
+    assert_eq!(outbox.publish_outbox_events(&publisher, 100).await.unwrap(), 3);
+    let types: Vec<&str> = publisher.events().iter().map(|event| event.event_type()).collect();
+    assert_eq!(types, ["business.created", "location.created", "location.deleted"]);
+    assert_eq!(outbox.publish_outbox_events(&publisher, 100).await.unwrap(), 0);
+}
+
+#[actix_rt::test]
+async fn postgres_only_routes_answer_501_in_memory() {
+    let app = spawn(true).await;
+    let (_, location_id) = app.create_business_with_location().await;
+
+    let res = app.get(&format!("/locations/{location_id}/promotion-templates")).send().await;
+    res.assert_status(501);
+    assert_eq!(res.code(), "postgres_required");
+    app.get("/webhooks").admin().send().await.assert_status(501);
+
+    let res = app.get("/health/ready").send().await;
+    res.assert_status(200);
+    assert_eq!(res.body["checks"]["database"]["details"]["backend"], "memory");
+}
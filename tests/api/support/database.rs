@@ -0,0 +1,179 @@
+//! A Postgres database per test.
+//!
+//! The migrations are applied once, to a template database named after a
+//! hash of `migrations/`, and every test gets a copy of it
+//! (`CREATE DATABASE … TEMPLATE …`), which takes milliseconds. Changing a
+//! migration changes the hash, so a stale template is never reused.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use reqwest::Url;
+use sha2::{Digest, Sha256};
+use tokio::sync::OnceCell;
+use sqlx::{Connection, PgConnection};
+use uuid::Uuid;
+
+/// SQLSTATE of `CREATE DATABASE … TEMPLATE` while the template is in use
+const OBJECT_IN_USE: &str = "55006";
+
+/// Server the test databases are created on; any database of it works as
+/// the maintenance connection
+const DEFAULT_TEST_DATABASE_URL: &str = "postgres://postgres@127.0.0.1:5432/postgres";
+
+static TEMPLATE: OnceCell<String> = OnceCell::const_new();
+
+/// A fresh database with every migration applied, dropped with the value
+pub struct TestDatabase {
+    pub name: String,
+    pub url: String,
+}
+
+impl TestDatabase {
+    pub async fn create() -> Self {
+        let template = TEMPLATE.get_or_init(create_template).await;
+        let name = format!("crazytrip_test_{}", Uuid::new_v4().simple());
+        let mut admin = connect(&admin_url()).await;
+        let create = format!("CREATE DATABASE \"{name}\" TEMPLATE \"{template}\"");
+        // Copying fails while anything is connected to the template, e.g.
+        // the migration connection of a run that just built it
+        let mut attempts = 0;
+        while let Err(err) = sqlx::raw_sql(&create).execute(&mut admin).await {
+            attempts += 1;
+            let in_use = err.as_database_error().and_then(|db| db.code()).as_deref() == Some(OBJECT_IN_USE);
+            if !in_use || attempts == 50 {
+                panic!("cannot create test database {name}: {err}");
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        Self {
+            url: database_url(&name),
+            name,
+        }
+    }
+}
+
+impl Drop for TestDatabase {
+    fn drop(&mut self) {
+        // Drop runs outside any async context, so the connection gets a
+        // runtime of its own
+        let name = self.name.clone();
+        let dropped = std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("runtime for dropping the test database");
+            runtime.block_on(async {
+                let mut admin = connect(&admin_url()).await;
+                sqlx::raw_sql(&format!("DROP DATABASE IF EXISTS \"{name}\" WITH (FORCE)"))
+                    .execute(&mut admin)
+                    .await
+            })
+        })
+        .join();
+        if let Ok(Err(err)) = dropped {
+            eprintln!("cannot drop test database {}: {err}", self.name);
+        }
+    }
+}
+
+fn admin_url() -> String {
+    std::env::var("TEST_DATABASE_URL").unwrap_or_else(|_| DEFAULT_TEST_DATABASE_URL.to_string())
+}
+
+fn database_url(name: &str) -> String {
+    let mut url = Url::parse(&admin_url()).expect("TEST_DATABASE_URL must be a postgres:// URL");
+    url.set_path(name);
+    url.to_string()
+}
+
+async fn connect(url: &str) -> PgConnection {
+    PgConnection::connect(url)
+        .await
+        .unwrap_or_else(|err| panic!("cannot connect to the test Postgres server (TEST_DATABASE_URL): {err}"))
+}
+
+/// Migration files in the order `bin/migrate` applies them
+fn migration_files() -> Vec<PathBuf> {
+    let pattern = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/V*.sql");
+    let mut files: Vec<PathBuf> = glob::glob(pattern).expect("valid glob").flatten().collect();
+    files.sort();
+    files
+}
+
+/// Name of the template for the current migrations, creating it if no test
+/// run has yet. It is built under a temporary name and renamed once
+/// complete, so concurrent test runs never copy a half-migrated template.
+async fn create_template() -> String {
+    let files = migration_files();
+    let mut hash = Sha256::new();
+    for file in &files {
+        hash.update(file.file_name().unwrap_or_default().as_encoded_bytes());
+        hash.update(std::fs::read(file).expect("readable migration"));
+    }
+    let name = format!("crazytrip_test_template_{}", &hex::encode(hash.finalize())[..16]);
+
+    let mut admin = connect(&admin_url()).await;
+    let exists = sqlx::query("SELECT 1 FROM pg_database WHERE datname = $1")
+        .bind(&name)
+        .fetch_optional(&mut admin)
+        .await
+        .expect("query pg_database")
+        .is_some();
+    if exists {
+        return name;
+    }
+
+    let building = format!("{name}_{}", Uuid::new_v4().simple());
+    sqlx::raw_sql(&format!("CREATE DATABASE \"{building}\""))
+        .execute(&mut admin)
+        .await
+        .expect("create template database");
+    {
+        let mut conn = connect(&database_url(&building)).await;
+        apply_migrations(&mut conn, &files).await;
+        let _ = conn.close().await;
+    }
+    // A concurrent run may have won the race; its template is as good
+    if sqlx::raw_sql(&format!("ALTER DATABASE \"{building}\" RENAME TO \"{name}\""))
+        .execute(&mut admin)
+        .await
+        .is_err()
+    {
+        let _ = sqlx::raw_sql(&format!("DROP DATABASE IF EXISTS \"{building}\" WITH (FORCE)"))
+            .execute(&mut admin)
+            .await;
+    }
+    name
+}
+
+/// Same steps as `bin/migrate`: each file in its own transaction, recorded
+/// in `schema_migrations`
+async fn apply_migrations(conn: &mut PgConnection, files: &[PathBuf]) {
+    sqlx::raw_sql(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (\
+            version VARCHAR(50) PRIMARY KEY,\
+            description TEXT,\
+            installed_on TIMESTAMPTZ NOT NULL DEFAULT NOW()\
+        )",
+    )
+    .execute(&mut *conn)
+    .await
+    .expect("create schema_migrations");
+    for file in files {
+        let name = file.file_name().and_then(|name| name.to_str()).unwrap_or_default().to_string();
+        let sql = std::fs::read_to_string(file).expect("readable migration");
+        let mut txn = conn.begin().await.expect("begin migration");
+        sqlx::raw_sql(&sql)
+            .execute(&mut *txn)
+            .await
+            .unwrap_or_else(|err| panic!("migration {name} failed: {err}"));
+        sqlx::query("INSERT INTO schema_migrations (version, description) VALUES ($1, $2)")
+            .bind(&name)
+            .bind(&name)
+            .execute(&mut *txn)
+            .await
+            .expect("record migration");
+        txn.commit().await.expect("commit migration");
+    }
+}
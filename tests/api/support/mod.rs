@@ -0,0 +1,423 @@
+//! Harness for the route tests.
+//!
+//! Each [`TestApp`] serves the application from [`app::build`] on a
+//! random local port, backed by a database of its own (or by the memory
+//! store with [`TestOptions::in_memory`]) and with a mock server standing in
+//! for the stories service. Tests talk to it over HTTP like any other client.
+
+mod database;
+
+use std::sync::{Arc, Once};
+use std::time::Duration;
+
+use actix_web::dev::ServerHandle;
+use actix_web::HttpServer;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, RequestBuilder, StatusCode};
+use serde_json::{json, Value};
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crazytrip_business_review_service::app;
+use crazytrip_business_review_service::clients::stories::StoriesClient;
+use crazytrip_business_review_service::config::{DatabaseConfig, HealthConfig};
+use crazytrip_business_review_service::database::DatabaseService;
+use crazytrip_business_review_service::moderation::PromotionModeration;
+use crazytrip_business_review_service::rate_limit::{
+    MemoryRateLimitStore, RateLimit, RateLimitBackend, RateLimitConfig, RateLimiter,
+};
+use crazytrip_business_review_service::repository::Repositories;
+use crazytrip_business_review_service::AppState;
+
+pub use database::TestDatabase;
+
+/// Reviewer the admin requests act as
+pub const ADMIN_ID: Uuid = Uuid::from_u128(0x2222_2222_2222_2222_2222_2222_2222_2222);
+
+/// Settings that differ between tests
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TestOptions {
+    /// Hold promotions of untrusted businesses for review
+    pub moderation: bool,
+    /// Include the stories mock in readiness
+    pub probe_stories: bool,
+    /// Apply the default rate limits
+    pub rate_limit: bool,
+    /// Trust `X-Actor-Id` from the test client, as if it were the gateway
+    pub behind_gateway: bool,
+    /// Run on the memory storage backend, without a database
+    pub in_memory: bool,
+}
+
+pub struct TestApp {
+    pub address: String,
+    /// `None` when running in memory
+    pub db: Option<Arc<DatabaseService>>,
+    /// What the app stores through, on either backend
+    pub repositories: Repositories,
+    /// Stands in for the stories service at `{uri}/api/v1`
+    pub stories: MockServer,
+    client: reqwest::Client,
+    server: ServerHandle,
+    // Dropped last, once the pool is closed
+    _database: Option<TestDatabase>,
+}
+
+impl TestApp {
+    pub async fn spawn() -> Self {
+        Self::spawn_with(TestOptions::default()).await
+    }
+
+    pub async fn spawn_with(options: TestOptions) -> Self {
+        init_logging();
+        let (database, db) = if options.in_memory {
+            (None, None)
+        } else {
+            let database = TestDatabase::create().await;
+            let db = DatabaseService::new(&DatabaseConfig {
+                url: database.url.clone(),
+                pool_max_size: 4,
+                ..DatabaseConfig::default()
+            })
+            .await
+            .expect("connect to the test database");
+            (Some(database), Some(Arc::new(db)))
+        };
+        let stories = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/stories/promotion"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({ "success": true })))
+            .mount(&stories)
+            .await;
+
+        let (state, repositories) = match &db {
+            Some(db) => (AppState::builder(db.clone()), Repositories::postgres(db.clone())),
+            None => (AppState::in_memory(), Repositories::memory()),
+        };
+        let mut state = state
+            .repositories(repositories.clone())
+            .stories_client(StoriesClient::new(format!("{}/api/v1", stories.uri()), Duration::from_secs(5)))
+            .moderation(PromotionModeration {
+                enabled: options.moderation,
+            })
+            .health_config(HealthConfig {
+                probe_stories: options.probe_stories,
+                ..HealthConfig::default()
+            });
+        if options.rate_limit {
+            state = state.rate_limiter(Arc::new(RateLimiter::new(
+                rate_limit_config(options.behind_gateway),
+                Arc::new(MemoryRateLimitStore::new()),
+            )));
+        }
+        let state = state.build();
+
+        let server = HttpServer::new(move || app::build(&state))
+            .workers(1)
+            .disable_signals()
+            .bind(("127.0.0.1", 0))
+            .expect("bind a local port");
+        let address = format!("http://{}", server.addrs()[0]);
+        let server = server.run();
+        let handle = server.handle();
+        actix_rt::spawn(server);
+
+        Self {
+            address,
+            db,
+            repositories,
+            stories,
+            client: reqwest::Client::new(),
+            server: handle,
+            _database: database,
+        }
+    }
+
+    /// Request to `path` under `/api/v1`
+    pub fn api(&self, method: Method, path: &str) -> Call {
+        self.request(method, &format!("/api/v1{path}"))
+    }
+
+    /// Request to `path` from the root of the service
+    pub fn request(&self, method: Method, path: &str) -> Call {
+        Call(self.client.request(method, format!("{}{path}", self.address)))
+    }
+
+    pub fn get(&self, path: &str) -> Call {
+        self.api(Method::GET, path)
+    }
+
+    pub fn post(&self, path: &str, body: Value) -> Call {
+        self.api(Method::POST, path).json(body)
+    }
+
+    pub fn put(&self, path: &str, body: Value) -> Call {
+        self.api(Method::PUT, path).json(body)
+    }
+
+    pub fn patch(&self, path: &str, body: Value) -> Call {
+        self.api(Method::PATCH, path)
+            .header("Content-Type", "application/merge-patch+json")
+            .body(body.to_string())
+    }
+
+    pub fn delete(&self, path: &str) -> Call {
+        self.api(Method::DELETE, path)
+    }
+}
+
+impl TestApp {
+    /// The Postgres service, for tests that look behind the API
+    pub fn db(&self) -> &DatabaseService {
+        self.db.as_deref().expect("the test app runs on Postgres")
+    }
+
+    /// Bodies of the promotions shared to the stories mock, waiting up to
+    /// five seconds for `expected` of them since shares run in the background
+    pub async fn stories_shares(&self, expected: usize) -> Vec<Value> {
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        loop {
+            let shares: Vec<Value> = self
+                .stories
+                .received_requests()
+                .await
+                .unwrap_or_default()
+                .iter()
+                .filter(|request| request.url.path() == "/api/v1/stories/promotion")
+                .filter_map(|request| serde_json::from_slice(&request.body).ok())
+                .collect();
+            if shares.len() >= expected || tokio::time::Instant::now() >= deadline {
+                return shares;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        // Stopping and closing are requested as soon as `stop` and `close`
+        // are called; waiting for them is not needed
+        drop(self.server.stop(false));
+        if let Some(db) = &self.db {
+            drop(db.pool().close());
+        }
+    }
+}
+
+/// Show the service's logs in the output of failing tests when `TEST_LOG`
+/// is set, filtered like `RUST_LOG`
+fn init_logging() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        if let Ok(filter) = std::env::var("TEST_LOG") {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new(if filter.is_empty() { "info" } else { &filter }))
+                .with_test_writer()
+                .try_init();
+        }
+    });
+}
+
+fn rate_limit_config(behind_gateway: bool) -> RateLimitConfig {
+    RateLimitConfig {
+        enabled: true,
+        store: RateLimitBackend::Memory,
+        read: RateLimit {
+            capacity: 120,
+            per_minute: 600,
+        },
+        write: RateLimit {
+            capacity: 20,
+            per_minute: 60,
+        },
+        allowlist: Vec::new(),
+        trusted_gateways: if behind_gateway { vec!["127.0.0.1/32".parse().unwrap()] } else { Vec::new() },
+        trust_forwarded_for: false,
+        prune_interval_secs: 300,
+    }
+}
+
+/// A request being built; `send` reads the whole response
+pub struct Call(RequestBuilder);
+
+impl Call {
+    pub fn json(self, body: Value) -> Self {
+        Self(self.0.json(&body))
+    }
+
+    pub fn body(self, body: impl Into<reqwest::Body>) -> Self {
+        Self(self.0.body(body))
+    }
+
+    pub fn header(self, name: &str, value: impl AsRef<str>) -> Self {
+        Self(self.0.header(name, value.as_ref()))
+    }
+
+    /// Act as the reviewer [`ADMIN_ID`]
+    pub fn admin(self) -> Self {
+        self.actor(ADMIN_ID, "Reviewer").header("X-Actor-Role", "admin")
+    }
+
+    /// Act as `id` without admin rights
+    pub fn actor(self, id: Uuid, name: &str) -> Self {
+        self.header("X-Actor-Id", id.to_string()).header("X-Actor-Name", name)
+    }
+
+    pub async fn send(self) -> TestResponse {
+        let response = self.0.send().await.expect("request reaches the test server");
+        let status = response.status();
+        let headers = response.headers().clone();
+        let text = response.text().await.expect("readable response body");
+        let body = serde_json::from_str(&text).unwrap_or(Value::Null);
+        TestResponse {
+            status,
+            headers,
+            text,
+            body,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub text: String,
+    /// The body when it is JSON, else `Null`
+    pub body: Value,
+}
+
+impl TestResponse {
+    /// `data` of a successful `ApiResponse`, failing the test on any other
+    /// status class
+    pub fn data(&self) -> &Value {
+        assert!(self.status.is_success(), "expected success, got {}: {}", self.status, self.text);
+        &self.body["data"]
+    }
+
+    /// `code` of an error `ApiResponse`
+    pub fn code(&self) -> &str {
+        self.body["code"].as_str().unwrap_or_default()
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    /// Assert the status, showing the body when it differs
+    #[track_caller]
+    pub fn assert_status(&self, expected: u16) -> &Self {
+        assert_eq!(self.status.as_u16(), expected, "unexpected status; body: {}", self.text);
+        self
+    }
+}
+
+/// `id` field of a JSON object as a UUID
+pub fn id_of(value: &Value) -> Uuid {
+    value["id"]
+        .as_str()
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .unwrap_or_else(|| panic!("no id in {value}"))
+}
+
+// ============================================================================
+// FIXTURES
+// ============================================================================
+
+pub fn registration_body(user_id: Uuid) -> Value {
+    json!({
+        "user_id": user_id,
+        "name": "Café del Puerto",
+        "category": "restaurant",
+        "address": "Calle Mayor 1, Valparaíso",
+        "description": "Family restaurant by the harbour",
+        "phone": "+56 9 1234 5678",
+        "website": "https://cafedelpuerto.example",
+        "tax_id": "76.123.456-7",
+        "document_urls": ["https://docs.example/rut.pdf"],
+        "is_multi_user_team": false,
+        "owner_email": "owner@cafedelpuerto.example",
+        "owner_username": "cafe_owner"
+    })
+}
+
+pub fn business_body(owner_user_id: Uuid) -> Value {
+    json!({
+        "owner_user_id": owner_user_id,
+        "business_name": "Café del Puerto",
+        "category": "restaurant",
+        "description": "Family restaurant by the harbour",
+        "website": "https://cafedelpuerto.example"
+    })
+}
+
+pub fn location_body(name: &str) -> Value {
+    json!({
+        "location_name": name,
+        "formatted_address": "Calle Mayor 1, Valparaíso",
+        "city": "Valparaíso",
+        "country": "CL",
+        "latitude": -33.0472,
+        "longitude": -71.6127,
+        "timezone": "America/Santiago",
+        "is_primary": false
+    })
+}
+
+/// A discount running from now until next week
+pub fn promotion_body() -> Value {
+    let now = chrono::Utc::now();
+    json!({
+        "title": "Two for one coffee",
+        "description": "Every coffee comes with a second one free",
+        "promotion_type": "discount",
+        "reward_points": 10,
+        "discount_percent": 50,
+        "max_claims": 100,
+        "requires_check_in": false,
+        "requires_purchase": true,
+        "starts_at": now - chrono::Duration::minutes(1),
+        "ends_at": now + chrono::Duration::days(7)
+    })
+}
+
+/// Owner the fixture businesses belong to
+pub const OWNER_ID: Uuid = Uuid::from_u128(0x1111_1111_1111_1111_1111_1111_1111_1111);
+
+impl TestApp {
+    pub async fn create_business(&self) -> Value {
+        let res = self.post("/businesses", business_body(OWNER_ID)).actor(OWNER_ID, "Owner").send().await;
+        res.assert_status(201);
+        res.data().clone()
+    }
+
+    pub async fn create_location(&self, business_id: Uuid, name: &str) -> Value {
+        let res = self
+            .post(&format!("/businesses/{business_id}/locations"), location_body(name))
+            .actor(OWNER_ID, "Owner")
+            .send()
+            .await;
+        res.assert_status(201);
+        res.data().clone()
+    }
+
+    /// A business with one location; returns both ids
+    pub async fn create_business_with_location(&self) -> (Uuid, Uuid) {
+        let business_id = id_of(&self.create_business().await);
+        let location_id = id_of(&self.create_location(business_id, "Harbour").await);
+        (business_id, location_id)
+    }
+
+    pub async fn create_promotion(&self, location_id: Uuid, body: Value) -> Value {
+        let res = self
+            .post(&format!("/locations/{location_id}/promotions"), body)
+            .actor(OWNER_ID, "Owner")
+            .send()
+            .await;
+        res.assert_status(201);
+        res.data().clone()
+    }
+}
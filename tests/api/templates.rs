@@ -0,0 +1,65 @@
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::support::{id_of, TestApp, OWNER_ID};
+
+fn template_body() -> Value {
+    json!({
+        "title": "Friday happy hour",
+        "promotion_type": "discount",
+        "discount_percent": 30,
+        "reward_points": 5,
+        "requires_check_in": true,
+        "requires_purchase": false,
+        "rrule": "FREQ=WEEKLY;BYDAY=FR;BYHOUR=18",
+        "dtstart": "2026-01-02T18:00:00",
+        "duration_minutes": 120
+    })
+}
+
+#[actix_rt::test]
+async fn template_takes_the_location_time_zone() {
+    let app = TestApp::spawn().await;
+    let (_, location_id) = app.create_business_with_location().await;
+    let path = format!("/locations/{location_id}/promotion-templates");
+
+    let res = app.post(&path, template_body()).actor(OWNER_ID, "Owner").send().await;
+    res.assert_status(201);
+    assert_eq!(res.data()["timezone"], "America/Santiago");
+    let id = id_of(res.data());
+
+    let res = app.get(&format!("/promotion-templates/{id}")).send().await;
+    assert_eq!(res.data()["rrule"], "FREQ=WEEKLY;BYDAY=FR;BYHOUR=18");
+    let res = app.get(&path).send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+
+    let template = format!("/promotion-templates/{id}");
+    app.delete(&template).actor(OWNER_ID, "Owner").send().await.assert_status(204);
+    app.get(&template).send().await.assert_status(404);
+}
+
+#[actix_rt::test]
+async fn invalid_rule_and_time_zone_are_rejected() {
+    let app = TestApp::spawn().await;
+    let (_, location_id) = app.create_business_with_location().await;
+    let path = format!("/locations/{location_id}/promotion-templates");
+
+    let mut body = template_body();
+    body["rrule"] = json!("FREQ=SOMETIMES");
+    let res = app.post(&path, body).actor(OWNER_ID, "Owner").send().await;
+    res.assert_status(422);
+    assert_eq!(res.body["details"][0]["code"], "invalid_rrule");
+
+    let mut body = template_body();
+    body["timezone"] = json!("Mars/Olympus_Mons");
+    let res = app.post(&path, body).actor(OWNER_ID, "Owner").send().await;
+    res.assert_status(422);
+    assert_eq!(res.body["details"][0]["code"], "invalid_timezone");
+
+    let res = app
+        .post(&format!("/locations/{}/promotion-templates", Uuid::new_v4()), template_body())
+        .actor(OWNER_ID, "Owner")
+        .send()
+        .await;
+    res.assert_status(404);
+}
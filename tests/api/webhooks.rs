@@ -0,0 +1,108 @@
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::support::{id_of, registration_body, TestApp, ADMIN_ID};
+
+fn subscription_body(url: &str) -> Value {
+    json!({
+        "url": url,
+        "event_types": ["business.approved"],
+        "description": "Partner CRM"
+    })
+}
+
+#[actix_rt::test]
+async fn subscription_secret_is_only_returned_on_create() {
+    let app = TestApp::spawn().await;
+
+    let res = app.post("/webhooks", subscription_body("https://crm.example/hooks")).admin().send().await;
+    res.assert_status(201);
+    assert!(res.data()["secret"].as_str().unwrap().starts_with("whsec_"));
+    let id = id_of(res.data());
+
+    let res = app.get(&format!("/webhooks/{id}")).admin().send().await;
+    assert_eq!(res.data()["url"], "https://crm.example/hooks");
+    assert!(res.data().get("secret").is_none());
+
+    let res = app.get("/webhooks").admin().send().await;
+    assert_eq!(res.data().as_array().map(Vec::len), Some(1));
+}
+
+#[actix_rt::test]
+async fn subscription_is_updated_and_deleted() {
+    let app = TestApp::spawn().await;
+    let res = app.post("/webhooks", subscription_body("https://crm.example/hooks")).admin().send().await;
+    let id = id_of(res.data());
+    let path = format!("/webhooks/{id}");
+
+    let res = app
+        .put(
+            &path,
+            json!({ "url": "https://crm.example/v2/hooks", "event_types": ["business.approved", "promotion.created"], "is_active": false }),
+        )
+        .admin()
+        .send()
+        .await;
+    assert_eq!(res.data()["url"], "https://crm.example/v2/hooks");
+    assert_eq!(res.data()["is_active"], false);
+
+    let res = app.put(&path, json!({ "url": "not a url", "event_types": [], "is_active": true })).admin().send().await;
+    res.assert_status(422);
+
+    app.delete(&path).admin().send().await.assert_status(204);
+    app.get(&path).admin().send().await.assert_status(404);
+}
+
+#[actix_rt::test]
+async fn events_are_queued_as_deliveries() {
+    let app = TestApp::spawn().await;
+    let res = app.post("/webhooks", subscription_body("https://crm.example/hooks")).admin().send().await;
+    let subscription_id = id_of(res.data());
+    let res = app.post("/registrations", registration_body(Uuid::new_v4())).send().await;
+    let registration_id = id_of(res.data());
+    app.post(&format!("/reviews/{registration_id}/action"), json!({ "action": "approve", "reviewer_id": ADMIN_ID }))
+        .send()
+        .await
+        .assert_status(200);
+
+    app.db().dispatch_outbox_webhooks(100).await.unwrap();
+
+    let res = app.get(&format!("/webhooks/{subscription_id}/deliveries")).admin().send().await;
+    let deliveries = res.data().as_array().unwrap();
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0]["event_type"], "business.approved");
+    assert_eq!(deliveries[0]["status"], "pending");
+}
+
+#[actix_rt::test]
+async fn webhooks_are_admin_only() {
+    let app = TestApp::spawn().await;
+    let res = app.post("/webhooks", subscription_body("https://crm.example/hooks")).admin().send().await;
+    let id = id_of(res.data());
+
+    let res = app.post("/webhooks", subscription_body("https://crm.example/hooks")).actor(Uuid::new_v4(), "Partner").send().await;
+    assert_eq!(res.code(), "admin_required");
+    app.get("/webhooks").send().await.assert_status(403);
+    app.get(&format!("/webhooks/{id}")).send().await.assert_status(403);
+    app.get(&format!("/webhooks/{id}/deliveries")).send().await.assert_status(403);
+    app.delete(&format!("/webhooks/{id}")).actor(Uuid::new_v4(), "Partner").send().await.assert_status(403);
+}
+
+#[actix_rt::test]
+async fn subscription_url_must_be_a_public_https_host() {
+    let app = TestApp::spawn().await;
+    for url in [
+        "http://crm.example/hooks",
+        "https://localhost/hooks",
+        "https://127.0.0.1/hooks",
+        "https://10.0.0.8/hooks",
+        "https://192.168.1.20/hooks",
+        "https://169.254.169.254/latest/meta-data",
+        "https://[::1]/hooks",
+        "https://[fd00::1]/hooks",
+    ] {
+        let res = app.post("/webhooks", subscription_body(url)).admin().send().await;
+        res.assert_status(422);
+        assert_eq!(res.body["details"][0]["code"], "public_https_url", "{url}: {}", res.text);
+    }
+}